@@ -8,5 +8,13 @@ export default defineConfig({
     alias: {
       tslib: 'tslib/tslib.es6.js'
     }
+  },
+  // 开发时将管理接口转发到 rsflow run --admin 127.0.0.1:8424
+  server: {
+    proxy: {
+      '/api': 'http://127.0.0.1:8424',
+      '/debug': 'http://127.0.0.1:8424',
+      '/metrics': 'http://127.0.0.1:8424'
+    }
   }
 })
@@ -0,0 +1,82 @@
+use rsflow_core::{
+    CATCH_NODE_TYPE, EngineContext, FlowContext, Node, NodeBuilder, NodeDescriptor, NodeError,
+    NodeFactory, NodeInfo, NodeInput, NodeOutput, Payload, Value,
+};
+use serde_json::json;
+use std::sync::Arc;
+
+/// catch 节点：接收同一 flow 内未连接错误端口的节点错误，从端口 0 输出带 error 字段的消息（见 error_value）
+///
+/// 配置 scope（节点 id 数组）时只接收这些节点的错误，路由由引擎根据配置计算。
+pub struct CatchNode {
+    info: NodeInfo,
+}
+
+#[async_trait::async_trait]
+impl Node for CatchNode {
+    fn info(&self) -> NodeInfo {
+        self.info.clone()
+    }
+    async fn engine_start(&self, _: EngineContext) {}
+    async fn event(&self, _: &str, _: Payload, _: &FlowContext) -> Result<(), NodeError> {
+        Ok(())
+    }
+    async fn input(&self, node_input: NodeInput, _: &FlowContext) -> Result<NodeOutput, NodeError> {
+        Ok(NodeOutput::One((0, node_input.msg)))
+    }
+}
+
+// NodeFactory
+pub struct CatchNodeFactory;
+
+#[async_trait::async_trait]
+impl NodeFactory for CatchNodeFactory {
+    async fn create(&self, node_info: NodeInfo) -> Result<Arc<dyn Node + Send + Sync>, NodeError> {
+        if let Value::Object(map) = &node_info.config {
+            match map.get("scope") {
+                None | Some(Value::NULL) | Some(Value::Array(_)) => {}
+                Some(_) => {
+                    return Err(NodeError::InvalidConfig(
+                        "catch scope must be an array of node ids".to_string(),
+                    ));
+                }
+            }
+        }
+        Ok(Arc::new(CatchNode { info: node_info }))
+    }
+}
+
+// NodeBuilder
+pub struct CatchNodeBuilder;
+
+#[async_trait::async_trait]
+impl NodeBuilder for CatchNodeBuilder {
+    fn node_type(&self) -> &str {
+        CATCH_NODE_TYPE
+    }
+
+    fn descriptor(&self) -> NodeDescriptor {
+        NodeDescriptor::new(self.node_type())
+            .display_name("Catch")
+            .category("common")
+            .icon("WarningOutlined")
+            .help("接收同一 flow 内未连接错误端口的节点错误，消息的 error 字段为错误信息。配置 scope 时只接收指定节点的错误。")
+            .inputs(&[])
+            .outputs(&["error"])
+            .config_schema(json!({
+                "type": "object",
+                "properties": {
+                    "scope": {
+                        "type": ["array", "null"],
+                        "items": { "type": "string", "format": "uuid" },
+                        "description": "只接收这些节点 id 的错误，为空时接收整个 flow"
+                    }
+                },
+                "additionalProperties": false
+            }))
+    }
+
+    async fn register(&self, _: &Value) -> Result<Box<dyn NodeFactory>, NodeError> {
+        Ok(Box::new(CatchNodeFactory))
+    }
+}
@@ -1,8 +1,15 @@
-use rsflow_core::{EngineContext, FlowContext, Node, NodeBuilder, NodeError, NodeFactory, NodeInfo, NodeInput, NodeOutput, NodeRunItem, Payload, Value};
-use std::sync::Arc;
+use rsflow_core::{
+    EngineContext, FlowContext, Node, NodeBuilder, NodeDescriptor, NodeError, NodeFactory,
+    NodeInfo, NodeInput, NodeOutput, NodeRunItem, Payload, Value,
+};
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+use tokio::task::JoinHandle;
 
 pub struct InjectNode {
     info: NodeInfo,
+    // 定时触发任务，节点停止（含热重载替换）时取消
+    timer: Mutex<Option<JoinHandle<()>>>,
 }
 
 #[async_trait::async_trait]
@@ -13,11 +20,14 @@ impl Node for InjectNode {
     async fn engine_start(&self, sender: EngineContext) {
         let node_id = self.info.id;
 
-        tokio::spawn(async move {
+        let timer = tokio::spawn(async move {
             let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(1));
 
             loop {
-                interval.tick().await;
+                tokio::select! {
+                    _ = sender.stopped() => break,
+                    _ = interval.tick() => {}
+                }
                 sender
                     .run_flow(NodeRunItem {
                         node_id,
@@ -29,6 +39,13 @@ impl Node for InjectNode {
                     .await; // ✅ 使用复制的值
             }
         });
+        *self.timer.lock().unwrap() = Some(timer);
+    }
+
+    async fn engine_stop(&self) {
+        if let Some(timer) = self.timer.lock().unwrap().take() {
+            timer.abort();
+        }
     }
 
     async fn event(&self, _: &str, _: Payload, _: &FlowContext) -> Result<(), NodeError> {
@@ -50,7 +67,10 @@ pub struct InjectNodeFactory;
 #[async_trait::async_trait]
 impl NodeFactory for InjectNodeFactory {
     async fn create(&self, node_info: NodeInfo) -> Result<Arc<dyn Node + Send + Sync>, NodeError> {
-        Ok(Arc::new(InjectNode { info: node_info }))
+        Ok(Arc::new(InjectNode {
+            info: node_info,
+            timer: Mutex::new(None),
+        }))
     }
 }
 
@@ -63,6 +83,17 @@ impl NodeBuilder for InjectNodeBuilder {
         "inject"
     }
 
+    fn descriptor(&self) -> NodeDescriptor {
+        NodeDescriptor::new(self.node_type())
+            .display_name("Inject")
+            .category("common")
+            .icon("ThunderboltOutlined")
+            .help("每秒从端口 0 输出一条消息 \"hello\"，用于触发 flow。")
+            .inputs(&[])
+            .outputs(&["output"])
+            .config_schema(json!({ "type": "object", "additionalProperties": false }))
+    }
+
     async fn register(&self, _: &Value) -> Result<Box<dyn NodeFactory>, NodeError> {
         // 返回一个 InjectNodeFactory 的实例
         Ok(Box::new(InjectNodeFactory))
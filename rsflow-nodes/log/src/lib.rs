@@ -1,8 +1,10 @@
 use rsflow_core::{
-    EngineContext, FlowContext, Node, NodeBuilder, NodeError, NodeFactory, NodeInfo, NodeInput,
-    NodeOutput, Payload, Value
+    EngineContext, FlowContext, Node, NodeBuilder, NodeDescriptor, NodeError, NodeFactory,
+    NodeInfo, NodeInput, NodeOutput, Payload, Value,
 };
+use serde_json::json;
 use std::sync::Arc;
+use tracing::info;
 
 pub struct LogNode {
     info: NodeInfo,
@@ -22,7 +24,14 @@ impl Node for LogNode {
         node_input: NodeInput,
         ctx: &FlowContext,
     ) -> Result<NodeOutput, NodeError> {
-        println!("{} {:#?}", ctx.id, node_input.msg);
+        info!(
+            ctx_id = %ctx.id,
+            node_id = %self.info.id,
+            node_name = %self.info.name,
+            port = node_input.port,
+            msg = ?node_input.msg,
+            "log"
+        );
         Ok(NodeOutput::None)
     }
 }
@@ -46,6 +55,16 @@ impl NodeBuilder for LogNodeBuilder {
         "log"
     }
 
+    fn descriptor(&self) -> NodeDescriptor {
+        NodeDescriptor::new(self.node_type())
+            .display_name("Log")
+            .category("common")
+            .icon("FileTextOutlined")
+            .help("将收到的消息输出到日志（info 级别）。")
+            .outputs(&[])
+            .config_schema(json!({ "type": "object", "additionalProperties": false }))
+    }
+
     async fn register(&self, _: &Value) -> Result<Box<dyn NodeFactory>, NodeError> {
         // 返回一个 LogNodeFactory 的实例
         Ok(Box::new(LogNodeFactory))
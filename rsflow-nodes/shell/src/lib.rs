@@ -1,16 +1,28 @@
 use rsflow_core::{
-    EngineContext, FlowContext, Node, NodeBuilder, NodeError, NodeFactory, NodeInfo, NodeInput,
-    NodeOutput, Payload, Value,
+    EngineContext, FlowContext, IoStream, Node, NodeBuilder, NodeDescriptor, NodeError,
+    NodeFactory, NodeInfo, NodeInput, NodeOutput, Payload, StreamId, Value,
 };
+use rsflow_core::flow::{Diagnostic, Flow, FlowNode};
+use serde_json::json;
+use std::process::Stdio;
 use std::sync::Arc;
 use tokio::process::Command;
 use tokio::time::{Duration, timeout};
+use tracing::warn;
+use uuid::Uuid;
 
 // Node 实例
 pub struct ShellNode {
     info: NodeInfo,
     shell_type: &'static str,
     timeout: Duration,
+    // 为 true 时立即输出，stdout 作为 Stream 逐块读取
+    stream: bool,
+}
+
+// 按平台使用的 shell，配置中的 shell 字段不生效
+fn platform_shell() -> &'static str {
+    if cfg!(windows) { "cmd" } else { "sh" }
 }
 
 fn shell_args(shell: &str, command: &str) -> Vec<String> {
@@ -32,7 +44,9 @@ impl Node for ShellNode {
     async fn event(&self, _: &str, _: Payload, _: &FlowContext) -> Result<(), NodeError> {
         Ok(())
     }
-    async fn input(&self, node_input: NodeInput, _: &FlowContext) -> Result<NodeOutput, NodeError> {
+    async fn input(&self, node_input: NodeInput, ctx: &FlowContext) -> Result<NodeOutput, NodeError> {
+        // 流模式下沿用输入对象（保留 req_id 等字段）
+        let input_value = node_input.msg.value.clone();
         // 从 input 或 config 获取 command
         let command_str = match node_input.msg {
             Payload {
@@ -63,6 +77,10 @@ impl Node for ShellNode {
             cmd.arg(a);
         }
 
+        if self.stream {
+            return self.spawn_stream(cmd, command_str, input_value, ctx).await;
+        }
+
         // 设置超时
         let output = timeout(self.timeout, cmd.output())
             .await
@@ -85,6 +103,58 @@ impl Node for ShellNode {
     }
 }
 
+impl ShellNode {
+    // 启动子进程并将 stdout 注册为 Stream，超时后结束子进程
+    // 输出输入对象并附加 command 字段
+    async fn spawn_stream(
+        &self,
+        mut cmd: Command,
+        command: String,
+        input: Value,
+        ctx: &FlowContext,
+    ) -> Result<NodeOutput, NodeError> {
+        let mut child = cmd
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(NodeError::Io)?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| NodeError::Shell("Failed to capture stdout".to_string()))?;
+
+        let name = self.info.name.clone();
+        let limit = self.timeout;
+        tokio::spawn(async move {
+            match timeout(limit, child.wait()).await {
+                Ok(Ok(status)) if !status.success() => {
+                    warn!("shell {} exited with {}", name, status);
+                }
+                Ok(Err(e)) => warn!("shell {} wait failed: {}", name, e),
+                Err(_) => {
+                    warn!("shell {} timed out", name);
+                    let _ = child.kill().await;
+                }
+                _ => {}
+            }
+        });
+
+        let stream_id = StreamId(Uuid::new_v4());
+        ctx.streams
+            .insert(stream_id, Arc::new(IoStream::reader(stdout)));
+        let mut map = match input {
+            Value::Object(map) => map,
+            _ => Default::default(),
+        };
+        map.insert("command".to_string(), Value::String(command));
+        Ok(NodeOutput::One((
+            0,
+            Payload::new_stream(Value::Object(map), stream_id),
+        )))
+    }
+}
+
 // NodeFactory
 pub struct ShellNodeFactory;
 
@@ -101,11 +171,16 @@ impl NodeFactory for ShellNodeFactory {
             _ => None,
         }
         .unwrap_or(5);
+        let stream = match &node_info.config {
+            Value::Object(map) => matches!(map.get("stream"), Some(Value::Bool(true))),
+            _ => false,
+        };
 
         Ok(Arc::new(ShellNode {
             info: node_info,
-            shell_type: if cfg!(windows) { "cmd" } else { "sh" },
+            shell_type: platform_shell(),
             timeout: Duration::from_secs(timeout_seconds),
+            stream,
         }))
     }
 }
@@ -119,7 +194,73 @@ impl NodeBuilder for ShellNodeBuilder {
         "shell"
     }
 
+    fn descriptor(&self) -> NodeDescriptor {
+        NodeDescriptor::new(self.node_type())
+            .display_name("Shell")
+            .category("function")
+            .icon("CodeOutlined")
+            .help("执行 shell 命令并从端口 0 输出 stdout。输入对象的 command 字段优先于配置；stream 为 true 时 stdout 作为 Stream 输出。")
+            .outputs(&["stdout"])
+            .config_schema(json!({
+                "type": ["string", "object"],
+                "description": "命令字符串，或包含 command 的对象",
+                "properties": {
+                    "command": { "type": "string", "description": "要执行的命令" },
+                    "timeout": { "type": "integer", "minimum": 0, "default": 5, "description": "超时（秒）" },
+                    "stream": { "type": "boolean", "default": false, "description": "以 Stream 输出 stdout" },
+                    "shell": { "type": "string", "description": "保留字段，当前按平台使用 sh 或 cmd" }
+                },
+                "additionalProperties": false
+            }))
+    }
+
+    fn lint(&self, flow: &Flow, node: &FlowNode) -> Vec<Diagnostic> {
+        let Value::Object(config) = &node.config else {
+            return Vec::new();
+        };
+        match config.get("shell") {
+            Some(Value::String(shell)) if shell != platform_shell() => vec![Diagnostic::warning(
+                "shell-platform-mismatch",
+                Some((flow, node)),
+                format!(
+                    "shell \"{}\" does not match this platform, it is ignored and \"{}\" is used",
+                    shell,
+                    platform_shell()
+                ),
+            )],
+            _ => Vec::new(),
+        }
+    }
+
     async fn register(&self, _: &Value) -> Result<Box<dyn NodeFactory>, NodeError> {
         Ok(Box::new(ShellNodeFactory))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rsflow_core::engine::{NodeBuilderMap, check_node_configs};
+    use rsflow_core::flow::{FlowBuilder, FlowModBuilder, NodeSpec};
+
+    fn shell_node(config: serde_json::Value) -> NodeSpec {
+        NodeSpec::new("shell").config(serde_json::from_value(config).unwrap())
+    }
+
+    #[test]
+    fn config_schema_rejects_misspelled_and_invalid_fields() {
+        let mut builders: NodeBuilderMap = std::collections::HashMap::new();
+        builders.insert("shell".to_string(), Box::new(ShellNodeBuilder));
+        let mut flow = FlowBuilder::new("main");
+        flow.add(shell_node(json!("echo ok")));
+        flow.add(shell_node(json!({ "command": "echo ok", "timout": 3 })));
+        flow.add(shell_node(json!({ "command": "echo ok", "timeout": "3" })));
+        let flow_mod = FlowModBuilder::new().flow(flow.build()).build();
+
+        let errors = check_node_configs(&flow_mod, &builders);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].pointer, "/flow/0/nodes/1/config");
+        assert!(errors[0].message.contains("timout"));
+        assert_eq!(errors[1].pointer, "/flow/0/nodes/2/config/timeout");
+    }
+}
@@ -0,0 +1,303 @@
+use crate::core::{NodeError, NodeOutput, Value};
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// config.debug
+///
+/// ```json
+/// "debug": { "enabled": false, "flows": [], "disabled_flows": [], "sample_rate": 1.0,
+///            "max_value_len": 1024, "buffer": 256 }
+/// ```
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct DebugConfig {
+    /// 记录所有 flow
+    #[serde(default)]
+    pub enabled: bool,
+    /// 单独开启记录的 flow id
+    #[serde(default)]
+    pub flows: Vec<Uuid>,
+    /// 单独关闭记录的 flow id，enabled 为 true 时也不记录
+    #[serde(default)]
+    pub disabled_flows: Vec<Uuid>,
+    /// 按运行采样的比例 0-1，同一运行的所有节点要么都记录要么都不记录
+    #[serde(default = "default_sample_rate")]
+    pub sample_rate: f64,
+    /// 事件中 Payload 值（JSON）的最大长度，超出部分截断
+    #[serde(default = "default_max_value_len")]
+    pub max_value_len: usize,
+    /// 订阅者的事件缓冲数，处理不及时的订阅者会丢失较早的事件
+    #[serde(default = "default_buffer")]
+    pub buffer: usize,
+}
+
+fn default_sample_rate() -> f64 {
+    1.0
+}
+
+fn default_max_value_len() -> usize {
+    1024
+}
+
+fn default_buffer() -> usize {
+    256
+}
+
+impl Default for DebugConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            flows: Vec::new(),
+            disabled_flows: Vec::new(),
+            sample_rate: default_sample_rate(),
+            max_value_len: default_max_value_len(),
+            buffer: default_buffer(),
+        }
+    }
+}
+
+impl DebugConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&self.sample_rate) {
+            return Err(format!(
+                "sample_rate must be between 0 and 1: {}",
+                self.sample_rate
+            ));
+        }
+        if self.buffer == 0 {
+            return Err("buffer must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// 一次节点执行的记录
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceEvent {
+    pub flow_id: Uuid,
+    pub ctx_id: Uuid,
+    pub run_id: Uuid,
+    pub node_id: Uuid,
+    pub node_name: String,
+    pub node_type: String,
+    pub input_port: u8,
+    /// 输入 Payload 值的 JSON，超过 max_value_len 时截断
+    pub input: String,
+    pub truncated: bool,
+    /// 有输出的端口
+    pub output_ports: Vec<u8>,
+    /// 执行耗时（微秒），含重试
+    pub duration_us: u64,
+    pub error: Option<String>,
+    /// 执行结束时间（Unix 毫秒）
+    pub timestamp: i64,
+}
+
+/// 调试记录器：按 flow 开关和采样率记录节点执行，广播给所有订阅者
+///
+/// 没有订阅者时不做任何记录。
+pub struct DebugTap {
+    config: RwLock<DebugConfig>,
+    sender: broadcast::Sender<TraceEvent>,
+}
+
+impl DebugTap {
+    pub fn new(config: DebugConfig) -> Self {
+        let (sender, _) = broadcast::channel(config.buffer.max(1));
+        Self {
+            config: RwLock::new(config),
+            sender,
+        }
+    }
+
+    /// 订阅节点执行记录
+    pub fn subscribe(&self) -> broadcast::Receiver<TraceEvent> {
+        self.sender.subscribe()
+    }
+
+    pub fn config(&self) -> DebugConfig {
+        self.config.read().unwrap().clone()
+    }
+
+    /// 开启或关闭所有 flow 的记录
+    pub fn set_enabled(&self, enabled: bool) {
+        self.config.write().unwrap().enabled = enabled;
+    }
+
+    /// 开启或关闭单个 flow 的记录，优先于 enabled
+    pub fn set_flow(&self, flow_id: Uuid, enabled: bool) {
+        let mut config = self.config.write().unwrap();
+        config.flows.retain(|id| *id != flow_id);
+        config.disabled_flows.retain(|id| *id != flow_id);
+        if enabled {
+            config.flows.push(flow_id);
+        } else {
+            config.disabled_flows.push(flow_id);
+        }
+    }
+
+    pub fn set_sample_rate(&self, sample_rate: f64) -> Result<(), String> {
+        let mut config = self.config.write().unwrap();
+        let mut updated = config.clone();
+        updated.sample_rate = sample_rate;
+        updated.validate()?;
+        *config = updated;
+        Ok(())
+    }
+
+    pub fn set_max_value_len(&self, max_value_len: usize) {
+        self.config.write().unwrap().max_value_len = max_value_len;
+    }
+
+    /// 该 flow 的这次运行是否需要记录
+    pub fn is_traced(&self, flow_id: Uuid, run_id: Uuid) -> bool {
+        if self.sender.receiver_count() == 0 {
+            return false;
+        }
+        let config = self.config.read().unwrap();
+        if config.disabled_flows.contains(&flow_id)
+            || (!config.enabled && !config.flows.contains(&flow_id))
+        {
+            return false;
+        }
+        // 按运行 id 采样，使同一运行的分支结果一致
+        (run_id.as_u128() % 10_000) as f64 / 10_000.0 < config.sample_rate
+    }
+
+    /// 截断后的值，返回 (JSON, 是否截断)
+    pub fn render_value(&self, value: &Value) -> (String, bool) {
+        let max_len = self.config.read().unwrap().max_value_len;
+        let mut json = serde_json::to_string(value).unwrap_or_default();
+        if json.len() <= max_len {
+            return (json, false);
+        }
+        let mut end = max_len;
+        while !json.is_char_boundary(end) {
+            end -= 1;
+        }
+        json.truncate(end);
+        (json, true)
+    }
+
+    /// 广播一条记录，没有订阅者时直接丢弃
+    pub(crate) fn send(&self, event: TraceEvent) {
+        let _ = self.sender.send(event);
+    }
+}
+
+impl TraceEvent {
+    /// 由执行结果得到有输出的端口和错误信息
+    pub(crate) fn outcome(result: &Result<NodeOutput, NodeError>) -> (Vec<u8>, Option<String>) {
+        match result {
+            Ok(NodeOutput::None) => (Vec::new(), None),
+            Ok(NodeOutput::One((port, _))) => (vec![*port], None),
+            Ok(NodeOutput::Many(msgs)) => {
+                let mut ports: Vec<u8> = msgs.iter().map(|(port, _)| *port).collect();
+                ports.sort_unstable();
+                ports.dedup();
+                (ports, None)
+            }
+            Err(err) => (Vec::new(), Some(format!("{:?}", err))),
+        }
+    }
+}
+
+impl Default for DebugTap {
+    fn default() -> Self {
+        Self::new(DebugConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_id(n: u128) -> Uuid {
+        Uuid::from_u128(n)
+    }
+
+    #[test]
+    fn samples_by_run_id() {
+        let tap = DebugTap::new(DebugConfig {
+            enabled: true,
+            sample_rate: 0.5,
+            ..DebugConfig::default()
+        });
+        let _rx = tap.subscribe();
+        let flow_id = Uuid::new_v4();
+        // run id 对 10000 取余后小于 5000 的运行被记录，结果不随调用变化
+        assert!(tap.is_traced(flow_id, run_id(4_999)));
+        assert!(tap.is_traced(flow_id, run_id(14_999)));
+        assert!(!tap.is_traced(flow_id, run_id(5_000)));
+        assert!(!tap.is_traced(flow_id, run_id(5_000)));
+
+        tap.set_sample_rate(0.0).unwrap();
+        assert!(!tap.is_traced(flow_id, run_id(0)));
+        tap.set_sample_rate(1.0).unwrap();
+        assert!(tap.is_traced(flow_id, run_id(9_999)));
+        assert!(tap.set_sample_rate(1.5).is_err());
+        assert_eq!(tap.config().sample_rate, 1.0);
+    }
+
+    #[test]
+    fn traces_only_with_subscribers() {
+        let tap = DebugTap::new(DebugConfig {
+            enabled: true,
+            ..DebugConfig::default()
+        });
+        assert!(!tap.is_traced(Uuid::new_v4(), run_id(0)));
+        let rx = tap.subscribe();
+        assert!(tap.is_traced(Uuid::new_v4(), run_id(0)));
+        drop(rx);
+        assert!(!tap.is_traced(Uuid::new_v4(), run_id(0)));
+    }
+
+    #[test]
+    fn per_flow_switch_overrides_enabled() {
+        let tap = DebugTap::default();
+        let _rx = tap.subscribe();
+        let (flow, other) = (Uuid::new_v4(), Uuid::new_v4());
+        assert!(!tap.is_traced(flow, run_id(0)));
+
+        tap.set_flow(flow, true);
+        assert!(tap.is_traced(flow, run_id(0)));
+        assert!(!tap.is_traced(other, run_id(0)));
+
+        // enabled 时单独关闭的 flow 仍不记录
+        tap.set_enabled(true);
+        tap.set_flow(flow, false);
+        assert!(!tap.is_traced(flow, run_id(0)));
+        assert!(tap.is_traced(other, run_id(0)));
+        let config = tap.config();
+        assert!(config.flows.is_empty());
+        assert_eq!(config.disabled_flows, vec![flow]);
+
+        tap.set_flow(flow, true);
+        assert!(tap.is_traced(flow, run_id(0)));
+        assert!(tap.config().disabled_flows.is_empty());
+        // 单独开启的 flow 不受 enabled 影响
+        tap.set_enabled(false);
+        assert!(tap.is_traced(flow, run_id(0)));
+        assert!(!tap.is_traced(other, run_id(0)));
+    }
+
+    #[test]
+    fn truncates_at_char_boundary() {
+        let tap = DebugTap::new(DebugConfig {
+            max_value_len: 5,
+            ..DebugConfig::default()
+        });
+        assert_eq!(
+            tap.render_value(&Value::String("ab".to_string())),
+            ("\"ab\"".to_string(), false)
+        );
+        // 第 5 个字节位于“文”的中间，截断到“中”之后
+        assert_eq!(
+            tap.render_value(&Value::String("中文".to_string())),
+            ("\"中".to_string(), true)
+        );
+        tap.set_max_value_len(0);
+        assert_eq!(tap.render_value(&Value::Int(1)), (String::new(), true));
+    }
+}
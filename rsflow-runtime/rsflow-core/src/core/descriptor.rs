@@ -0,0 +1,110 @@
+use serde::Serialize;
+use serde_json::json;
+
+/// 端口说明
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct PortDescriptor {
+    pub port: u8,
+    pub label: String,
+}
+
+/// 节点类型说明，供编辑器展示节点、生成配置表单
+///
+/// ```ignore
+/// NodeDescriptor::new("shell")
+///     .display_name("Shell")
+///     .category("function")
+///     .outputs(&["stdout"])
+///     .config_schema(json!({ "type": "object", "properties": { "command": { "type": "string" } } }))
+/// ```
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct NodeDescriptor {
+    pub node_type: String,
+    pub display_name: String,
+    pub category: String,
+    /// 图标名（ant-design-vue 图标，如 CodeOutlined）
+    pub icon: String,
+    /// 帮助文本（Markdown）
+    pub help: String,
+    pub inputs: Vec<PortDescriptor>,
+    pub outputs: Vec<PortDescriptor>,
+    /// FlowNode.config 的 JSON Schema
+    pub config_schema: serde_json::Value,
+    /// node_global_config 中该类型子树的 JSON Schema
+    pub global_config_schema: serde_json::Value,
+}
+
+impl NodeDescriptor {
+    /// 默认一个输入端口、一个输出端口，配置不限
+    pub fn new(node_type: &str) -> Self {
+        Self {
+            node_type: node_type.to_string(),
+            display_name: node_type.to_string(),
+            category: "common".to_string(),
+            icon: String::new(),
+            help: String::new(),
+            inputs: ports(&["input"]),
+            outputs: ports(&["output"]),
+            config_schema: json!({}),
+            global_config_schema: json!({}),
+        }
+    }
+
+    pub fn display_name(mut self, display_name: &str) -> Self {
+        self.display_name = display_name.to_string();
+        self
+    }
+
+    pub fn category(mut self, category: &str) -> Self {
+        self.category = category.to_string();
+        self
+    }
+
+    pub fn icon(mut self, icon: &str) -> Self {
+        self.icon = icon.to_string();
+        self
+    }
+
+    pub fn help(mut self, help: &str) -> Self {
+        self.help = help.to_string();
+        self
+    }
+
+    /// 输入端口标签，按顺序对应端口 0、1、2…
+    pub fn inputs(mut self, labels: &[&str]) -> Self {
+        self.inputs = ports(labels);
+        self
+    }
+
+    /// 输出端口标签，按顺序对应端口 0、1、2…
+    pub fn outputs(mut self, labels: &[&str]) -> Self {
+        self.outputs = ports(labels);
+        self
+    }
+
+    pub fn config_schema(mut self, schema: serde_json::Value) -> Self {
+        self.config_schema = schema;
+        self
+    }
+
+    pub fn global_config_schema(mut self, schema: serde_json::Value) -> Self {
+        self.global_config_schema = schema;
+        self
+    }
+
+    /// 是否有该输出端口
+    pub fn has_output(&self, port: u8) -> bool {
+        self.outputs.iter().any(|p| p.port == port)
+    }
+}
+
+fn ports(labels: &[&str]) -> Vec<PortDescriptor> {
+    labels
+        .iter()
+        .enumerate()
+        .map(|(port, label)| PortDescriptor {
+            port: port as u8,
+            label: label.to_string(),
+        })
+        .collect()
+}
@@ -1,5 +1,9 @@
 use crate::NodeError;
-use crate::core::{Resource, ResourceId, ResourceTable, Stream, StreamId, StreamTable, Value};
+use crate::core::{
+    EngineMetrics, FlowHandle, FlowRun, Resource, ResourceId, ResourceTable, Stream, StreamId,
+    StreamTable, Value,
+};
+use std::any::{Any, type_name};
 use std::collections::HashMap;
 use std::{future::Future, pin::Pin, sync::Arc};
 use tokio::sync::Mutex;
@@ -18,6 +22,10 @@ pub struct FlowContext {
     pub listeners: Arc<FlowListeners>,
     pub resources: ResourceTable,
     pub streams: StreamTable,
+    /// 本次运行的完成状态，所有分支共享
+    pub run: FlowRun,
+    /// 本分支的错误已转交错误端口或 catch 节点的次数，新分支继承
+    pub catch_depth: usize,
 }
 
 impl FlowContext {
@@ -29,28 +37,86 @@ impl FlowContext {
             listeners: Arc::new(FlowListeners::new()),
             resources: ResourceTable::new(),
             streams: StreamTable::new(),
+            run: FlowRun::new(id),
+            catch_depth: 0,
         }
     }
-    //创建并发分支
+    //创建并发分支，分支持有当前分支的资源和流，全部分支结束后释放
     pub fn new_branch(&self) -> Self {
         Self {
             id: Uuid::new_v4(),
             run_node_ids: self.run_node_ids.clone(),
             listeners: Arc::clone(&self.listeners),
-            resources: self.resources.clone(),
-            streams: self.streams.clone(),
+            resources: self.resources.branch(),
+            streams: self.streams.branch(),
+            run: self.run.clone(),
+            catch_depth: self.catch_depth,
         }
     }
-    //获取资源
-    pub async fn get_resource(&self, id: &ResourceId) -> Result<Resource, NodeError> {
-        match self.resources.get(id).await {
+    //获取运行句柄，所有分支结束后返回结果
+    pub fn handle(&self) -> FlowHandle {
+        self.run.handle()
+    }
+    //计入引擎统计：运行状态、资源表和流表
+    pub(crate) fn attach_metrics(&self, metrics: &Arc<EngineMetrics>) {
+        self.run.attach(metrics);
+        self.resources.attach(&metrics.resources);
+        self.streams.attach(&metrics.streams);
+    }
+    //插入资源，由当前分支持有，返回新的资源 id
+    pub fn insert_resource<T: Any + Send + Sync>(&self, value: T) -> ResourceId {
+        let id = ResourceId(Uuid::new_v4());
+        self.resources
+            .insert_named(id, Arc::new(value), type_name::<T>());
+        id
+    }
+    //获取未指定类型的资源
+    pub fn get_resource_any(&self, id: &ResourceId) -> Result<Resource, NodeError> {
+        match self.resources.get(id) {
             Some(res) => Ok(res.clone()),
             None => Err(NodeError::ResourceNotFound(*id)),
         }
     }
+    //获取资源并转换为 T
+    pub fn get_resource<T: Any + Send + Sync>(&self, id: &ResourceId) -> Result<Arc<T>, NodeError> {
+        let res = self.get_resource_any(id)?;
+        res.downcast::<T>().map_err(|_| self.type_mismatch::<T>(id))
+    }
+    //取出资源的所有权，用于节点之间单一消费者的交接
+    //类型不匹配或资源仍被引用时资源保留在表中
+    pub fn take_resource<T: Any + Send + Sync>(&self, id: &ResourceId) -> Result<T, NodeError> {
+        // 在表锁内检查类型和引用数，检查失败时表项不受影响
+        let res = self
+            .resources
+            .take_if(id, |res, actual| {
+                if !res.is::<T>() {
+                    Err(NodeError::ResourceTypeMismatch {
+                        id: *id,
+                        expected: type_name::<T>(),
+                        actual,
+                    })
+                } else if Arc::strong_count(res) > 1 {
+                    Err(NodeError::ResourceInUse(*id))
+                } else {
+                    Ok(())
+                }
+            })
+            .ok_or(NodeError::ResourceNotFound(*id))??;
+        res.downcast::<T>()
+            .ok()
+            .and_then(|res| Arc::try_unwrap(res).ok())
+            .ok_or(NodeError::ResourceInUse(*id))
+    }
+    fn type_mismatch<T: Any>(&self, id: &ResourceId) -> NodeError {
+        NodeError::ResourceTypeMismatch {
+            id: *id,
+            expected: type_name::<T>(),
+            actual: self.resources.type_name(id).unwrap_or("unknown"),
+        }
+    }
     //获取流
-    pub async fn get_stream(&self, id: &StreamId) -> Result<Stream, NodeError> {
-        match self.streams.get(id).await {
+    pub fn get_stream(&self, id: &StreamId) -> Result<Stream, NodeError> {
+        match self.streams.get(id) {
             Some(res) => Ok(res.clone()),
             None => Err(NodeError::StreamNotFound(*id)),
         }
@@ -64,8 +130,13 @@ pub enum FlowEventKey {
     Custom(String), // 扩展
 }
 
+struct FlowListener {
+    cb: FlowCallback,
+    once: bool,
+}
+
 pub struct FlowListeners {
-    listeners: Mutex<HashMap<FlowEventKey, Vec<FlowCallback>>>,
+    listeners: Mutex<HashMap<FlowEventKey, Vec<FlowListener>>>,
 }
 
 impl FlowListeners {
@@ -78,33 +149,70 @@ impl FlowListeners {
     /// 多次监听
     pub async fn on(&self, key: FlowEventKey, cb: FlowCallback) {
         let mut map = self.listeners.lock().await;
-        map.entry(key).or_default().push(cb);
+        map.entry(key)
+            .or_default()
+            .push(FlowListener { cb, once: false });
     }
 
     /// 一次性监听
     pub async fn once(&self, key: FlowEventKey, cb: FlowCallback) {
-        let wrapper: FlowCallback = Arc::new(move |val| {
-            let cb = cb.clone();
-            Box::pin(async move {
-                cb(val).await;
-            })
-        });
-
         let mut map = self.listeners.lock().await;
-        map.entry(key).or_default().push(wrapper);
+        map.entry(key)
+            .or_default()
+            .push(FlowListener { cb, once: true });
     }
 
-    /// 触发事件
-    pub async fn emit(&self, key: &FlowEventKey, val: Value) {
-        let callbacks = {
+    /// 触发事件，返回被调用的回调数量
+    pub async fn emit(&self, key: &FlowEventKey, val: Value) -> usize {
+        let callbacks: Vec<FlowCallback> = {
             let mut map = self.listeners.lock().await;
-            map.remove(key) // once 语义：直接拿走
+            let Some(listeners) = map.get_mut(key) else {
+                return 0;
+            };
+            let callbacks = listeners.iter().map(|l| l.cb.clone()).collect();
+            // once 语义：触发后移除
+            listeners.retain(|l| !l.once);
+            if listeners.is_empty() {
+                map.remove(key);
+            }
+            callbacks
         };
 
-        if let Some(cbs) = callbacks {
-            for cb in cbs {
-                cb(val.clone()).await;
-            }
+        for cb in &callbacks {
+            cb(val.clone()).await;
         }
+        callbacks.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn take_resource_keeps_entry_on_failure() {
+        let ctx = FlowContext::new(Uuid::new_v4());
+        let branch = ctx.new_branch();
+        let id = branch.insert_resource(String::from("data"));
+
+        let err = ctx.take_resource::<u32>(&id).unwrap_err();
+        assert!(
+            matches!(err, NodeError::ResourceTypeMismatch { actual, .. } if actual == type_name::<String>())
+        );
+
+        let held = ctx.get_resource::<String>(&id).unwrap();
+        let err = ctx.take_resource::<String>(&id).unwrap_err();
+        assert!(matches!(err, NodeError::ResourceInUse(_)));
+        drop(held);
+
+        // 失败后表项和分支的持有关系不变
+        assert_eq!(ctx.resources.type_name(&id), Some(type_name::<String>()));
+        assert_eq!(ctx.take_resource::<String>(&id).unwrap(), "data");
+        assert!(matches!(
+            ctx.take_resource::<String>(&id),
+            Err(NodeError::ResourceNotFound(_))
+        ));
+        drop(branch);
+        assert!(ctx.resources.is_empty());
     }
 }
@@ -1,10 +1,24 @@
-use crate::core::{FlowContext, NodeRunItem, Payload};
-use serde::Deserialize;
+use crate::core::{DebugConfig, FlowContext, NodeRunItem, Payload};
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct EngineConfig {
     pub msg_len: usize,
+    /// 停止时等待运行中 flow 完成的最长时间（秒）
+    #[serde(default = "default_shutdown_timeout")]
+    pub shutdown_timeout: u64,
+    /// 节点执行记录（调试）
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub debug: DebugConfig,
+}
+
+fn default_shutdown_timeout() -> u64 {
+    10
+}
+
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
 }
 
 pub enum EngineMessage {
@@ -19,4 +33,4 @@ pub enum EngineMessage {
         payload: Payload,
     },
     Stop,
-}
\ No newline at end of file
+}
@@ -0,0 +1,485 @@
+use crate::core::retry::{RetryCounters, RetryStats};
+use crate::core::table::{TableCounters, TableMetrics};
+use crate::core::{FlowStatus, NodeInfo};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// 节点执行耗时直方图的桶上限（秒）
+pub const LATENCY_BUCKETS: [f64; 11] = [
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 5.0, 10.0,
+];
+
+/// 单个节点的执行统计
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NodeStats {
+    pub node_name: String,
+    pub node_type: String,
+    /// Node::input 执行次数（重试只计一次）
+    pub inputs: u64,
+    /// 返回错误的次数
+    pub errors: u64,
+    /// 多分支输出时额外派发的分支数
+    pub branches: u64,
+    /// 与 LATENCY_BUCKETS 对应的累积计数
+    pub latency_buckets: [u64; LATENCY_BUCKETS.len()],
+    /// 耗时总和（秒）
+    pub latency_sum: f64,
+}
+
+/// 引擎消息队列的使用情况
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct QueueStats {
+    /// 队列中等待分发的消息数
+    pub len: usize,
+    /// 队列容量，即 EngineConfig::msg_len
+    pub capacity: usize,
+}
+
+/// flow 运行统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct FlowStats {
+    pub started: u64,
+    pub completed: u64,
+    pub failed: u64,
+    pub cancelled: u64,
+    /// 未被执行（引擎停止中）的运行，不计入 started
+    pub rejected: u64,
+    /// 多分支输出派发的分支数
+    pub branches: u64,
+}
+
+/// 一个引擎的运行统计：flow 运行、分支、重试、资源表和各节点的执行
+#[derive(Debug, Default)]
+pub struct EngineMetrics {
+    /// 各节点的执行统计
+    pub nodes: NodeMetrics,
+    started: AtomicU64,
+    completed: AtomicU64,
+    failed: AtomicU64,
+    cancelled: AtomicU64,
+    rejected: AtomicU64,
+    branches: AtomicU64,
+    pub(crate) retries: RetryCounters,
+    pub(crate) resources: Arc<TableCounters>,
+    pub(crate) streams: Arc<TableCounters>,
+}
+
+impl EngineMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn flow_stats(&self) -> FlowStats {
+        FlowStats {
+            started: self.started.load(Ordering::Relaxed),
+            completed: self.completed.load(Ordering::Relaxed),
+            failed: self.failed.load(Ordering::Relaxed),
+            cancelled: self.cancelled.load(Ordering::Relaxed),
+            rejected: self.rejected.load(Ordering::Relaxed),
+            branches: self.branches.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn retry_stats(&self) -> RetryStats {
+        self.retries.stats()
+    }
+
+    /// 计入本引擎的 ResourceTable / StreamTable 统计
+    pub fn table_metrics(&self) -> TableMetrics {
+        TableMetrics {
+            resources: self.resources.stats(),
+            streams: self.streams.stats(),
+        }
+    }
+
+    pub(crate) fn record_flow_started(&self) {
+        self.started.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_flow_finished(&self, status: FlowStatus) {
+        let counter = match status {
+            FlowStatus::Completed => &self.completed,
+            FlowStatus::Failed => &self.failed,
+            FlowStatus::Cancelled => &self.cancelled,
+            FlowStatus::Rejected => &self.rejected,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_branches(&self, info: &NodeInfo, count: usize) {
+        if count == 0 {
+            return;
+        }
+        self.branches.fetch_add(count as u64, Ordering::Relaxed);
+        self.nodes
+            .with_node(info, |stats| stats.branches += count as u64);
+    }
+}
+
+/// 一个引擎的节点执行统计，热重载时移除已删除节点的统计
+#[derive(Debug, Default)]
+pub struct NodeMetrics {
+    nodes: Mutex<HashMap<Uuid, NodeStats>>,
+}
+
+impl NodeMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 所有节点的执行统计
+    pub fn snapshot(&self) -> HashMap<Uuid, NodeStats> {
+        self.nodes.lock().unwrap().clone()
+    }
+
+    /// 只保留 keep 返回 true 的节点
+    pub(crate) fn retain(&self, mut keep: impl FnMut(&Uuid) -> bool) {
+        self.nodes.lock().unwrap().retain(|id, _| keep(id));
+    }
+
+    // 修改节点统计，节点名称和类型以最近一次为准（热重载后可能变化）
+    fn with_node(&self, info: &NodeInfo, f: impl FnOnce(&mut NodeStats)) {
+        let mut nodes = self.nodes.lock().unwrap();
+        let entry = nodes.entry(info.id).or_default();
+        if entry.node_name != info.name {
+            entry.node_name = info.name.clone();
+        }
+        if entry.node_type != info.node_type {
+            entry.node_type = info.node_type.clone();
+        }
+        f(entry);
+    }
+
+    pub(crate) fn record_input(&self, info: &NodeInfo, elapsed: Duration, ok: bool) {
+        let secs = elapsed.as_secs_f64();
+        self.with_node(info, |stats| {
+            stats.inputs += 1;
+            if !ok {
+                stats.errors += 1;
+            }
+            for (bucket, le) in stats.latency_buckets.iter_mut().zip(LATENCY_BUCKETS) {
+                if secs <= le {
+                    *bucket += 1;
+                }
+            }
+            stats.latency_sum += secs;
+        });
+    }
+}
+
+/// 以 Prometheus 文本格式输出一个引擎的全部指标
+pub fn render_prometheus(queue: QueueStats, metrics: &EngineMetrics) -> String {
+    let mut out = String::new();
+    let flows = metrics.flow_stats();
+
+    metric_header(
+        &mut out,
+        "rsflow_flows_started_total",
+        "counter",
+        "Flow runs started",
+    );
+    let _ = writeln!(out, "rsflow_flows_started_total {}", flows.started);
+    metric_header(
+        &mut out,
+        "rsflow_flows_finished_total",
+        "counter",
+        "Flow runs finished, by status",
+    );
+    for (status, value) in [
+        ("completed", flows.completed),
+        ("failed", flows.failed),
+        ("cancelled", flows.cancelled),
+        ("rejected", flows.rejected),
+    ] {
+        let _ = writeln!(
+            out,
+            "rsflow_flows_finished_total{{status=\"{}\"}} {}",
+            status, value
+        );
+    }
+    metric_header(
+        &mut out,
+        "rsflow_branches_total",
+        "counter",
+        "Branches dispatched by multi-wire outputs",
+    );
+    let _ = writeln!(out, "rsflow_branches_total {}", flows.branches);
+
+    metric_header(
+        &mut out,
+        "rsflow_queue_messages",
+        "gauge",
+        "Messages waiting in the engine queue",
+    );
+    let _ = writeln!(out, "rsflow_queue_messages {}", queue.len);
+    metric_header(
+        &mut out,
+        "rsflow_queue_capacity",
+        "gauge",
+        "Engine queue capacity (config.msg_len)",
+    );
+    let _ = writeln!(out, "rsflow_queue_capacity {}", queue.capacity);
+    metric_header(
+        &mut out,
+        "rsflow_queue_utilization",
+        "gauge",
+        "Engine queue usage ratio, 0-1",
+    );
+    let utilization = if queue.capacity == 0 {
+        0.0
+    } else {
+        queue.len as f64 / queue.capacity as f64
+    };
+    let _ = writeln!(out, "rsflow_queue_utilization {}", utilization);
+
+    let tables = metrics.table_metrics();
+    metric_header(
+        &mut out,
+        "rsflow_table_entries",
+        "gauge",
+        "Live entries in resource and stream tables",
+    );
+    for (table, stats) in [("resources", tables.resources), ("streams", tables.streams)] {
+        let _ = writeln!(
+            out,
+            "rsflow_table_entries{{table=\"{}\"}} {}",
+            table, stats.live
+        );
+    }
+    metric_header(
+        &mut out,
+        "rsflow_table_operations_total",
+        "counter",
+        "Resource and stream table entries inserted, released and expired",
+    );
+    for (table, stats) in [("resources", tables.resources), ("streams", tables.streams)] {
+        for (op, value) in [
+            ("inserted", stats.inserted),
+            ("released", stats.released),
+            ("expired", stats.expired),
+        ] {
+            let _ = writeln!(
+                out,
+                "rsflow_table_operations_total{{table=\"{}\",op=\"{}\"}} {}",
+                table, op, value
+            );
+        }
+    }
+
+    let retry = metrics.retry_stats();
+    metric_header(
+        &mut out,
+        "rsflow_retries_total",
+        "counter",
+        "Node input retries, by outcome",
+    );
+    for (outcome, value) in [
+        ("attempt", retry.attempts),
+        ("recovered", retry.recovered),
+        ("exhausted", retry.exhausted),
+    ] {
+        let _ = writeln!(
+            out,
+            "rsflow_retries_total{{outcome=\"{}\"}} {}",
+            outcome, value
+        );
+    }
+
+    // 按节点 id 排序，保证输出稳定
+    let mut nodes: Vec<_> = metrics.nodes.snapshot().into_iter().collect();
+    nodes.sort_by_key(|(id, _)| *id);
+
+    metric_header(
+        &mut out,
+        "rsflow_node_inputs_total",
+        "counter",
+        "Node input executions",
+    );
+    for (id, stats) in &nodes {
+        let _ = writeln!(
+            out,
+            "rsflow_node_inputs_total{{{}}} {}",
+            node_labels(id, stats),
+            stats.inputs
+        );
+    }
+    metric_header(
+        &mut out,
+        "rsflow_node_errors_total",
+        "counter",
+        "Node input executions that returned an error",
+    );
+    for (id, stats) in &nodes {
+        let _ = writeln!(
+            out,
+            "rsflow_node_errors_total{{{}}} {}",
+            node_labels(id, stats),
+            stats.errors
+        );
+    }
+    metric_header(
+        &mut out,
+        "rsflow_node_branches_total",
+        "counter",
+        "Branches dispatched by the node's multi-wire outputs",
+    );
+    for (id, stats) in &nodes {
+        let _ = writeln!(
+            out,
+            "rsflow_node_branches_total{{{}}} {}",
+            node_labels(id, stats),
+            stats.branches
+        );
+    }
+    metric_header(
+        &mut out,
+        "rsflow_node_input_duration_seconds",
+        "histogram",
+        "Node input latency including retries",
+    );
+    for (id, stats) in &nodes {
+        let labels = node_labels(id, stats);
+        for (le, count) in LATENCY_BUCKETS.iter().zip(stats.latency_buckets) {
+            let _ = writeln!(
+                out,
+                "rsflow_node_input_duration_seconds_bucket{{{},le=\"{}\"}} {}",
+                labels, le, count
+            );
+        }
+        let _ = writeln!(
+            out,
+            "rsflow_node_input_duration_seconds_bucket{{{},le=\"+Inf\"}} {}",
+            labels, stats.inputs
+        );
+        let _ = writeln!(
+            out,
+            "rsflow_node_input_duration_seconds_sum{{{}}} {}",
+            labels, stats.latency_sum
+        );
+        let _ = writeln!(
+            out,
+            "rsflow_node_input_duration_seconds_count{{{}}} {}",
+            labels, stats.inputs
+        );
+    }
+
+    out
+}
+
+fn metric_header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn node_labels(id: &Uuid, stats: &NodeStats) -> String {
+    format!(
+        "node_id=\"{}\",node_name=\"{}\",node_type=\"{}\"",
+        id,
+        escape_label(&stats.node_name),
+        escape_label(&stats.node_type)
+    )
+}
+
+// 转义标签值中的反斜杠、双引号和换行
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{FlowContext, ResourceId, Value};
+
+    fn info(name: &str) -> NodeInfo {
+        NodeInfo {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            node_type: "log".to_string(),
+            description: String::new(),
+            config: Value::NULL,
+            input_ports: HashMap::new(),
+            output_ports: HashMap::new(),
+            global_config: Value::NULL,
+            error_port: None,
+            retry: None,
+        }
+    }
+
+    #[test]
+    fn records_inputs_per_engine_and_prunes() {
+        let (a, b) = (info("a"), info("b"));
+        let metrics = EngineMetrics::new();
+        metrics
+            .nodes
+            .record_input(&a, Duration::from_millis(20), true);
+        metrics
+            .nodes
+            .record_input(&a, Duration::from_secs(2), false);
+        metrics.record_branches(&a, 2);
+        metrics
+            .nodes
+            .record_input(&b, Duration::from_millis(1), true);
+
+        let stats = &metrics.nodes.snapshot()[&a.id];
+        assert_eq!((stats.inputs, stats.errors, stats.branches), (2, 1, 2));
+        // 0.025 及以上的桶包含 20ms，5.0 及以上的桶包含 2s
+        assert_eq!(stats.latency_buckets, [0, 0, 0, 1, 1, 1, 1, 1, 1, 2, 2]);
+
+        // 另一个引擎的统计互不影响
+        let other = EngineMetrics::new();
+        assert!(other.nodes.snapshot().is_empty());
+        assert_eq!(other.flow_stats(), FlowStats::default());
+
+        metrics.nodes.retain(|id| *id == b.id);
+        let snapshot = metrics.nodes.snapshot();
+        assert_eq!(snapshot.len(), 1);
+        assert!(snapshot.contains_key(&b.id));
+        // 移除节点不影响引擎的分支总数
+        assert_eq!(metrics.flow_stats().branches, 2);
+
+        let text = render_prometheus(QueueStats::default(), &metrics);
+        assert!(text.contains(&format!("node_id=\"{}\"", b.id)));
+        assert!(!text.contains(&format!("node_id=\"{}\"", a.id)));
+        assert!(text.contains("rsflow_branches_total 2\n"));
+    }
+
+    #[test]
+    fn counts_flows_retries_and_tables_per_engine() {
+        let metrics = Arc::new(EngineMetrics::new());
+        let ctx = FlowContext::new(Uuid::new_v4());
+        ctx.resources
+            .insert(ResourceId(Uuid::new_v4()), Arc::new(1u32));
+        let handle = ctx.handle();
+        ctx.attach_metrics(&metrics);
+        ctx.run.mark_started();
+        metrics.retries.record_attempt();
+        metrics.retries.record_recovered();
+        assert_eq!(metrics.table_metrics().resources.live, 1);
+        drop(ctx);
+        assert!(handle.is_finished());
+
+        let flows = metrics.flow_stats();
+        assert_eq!((flows.started, flows.completed), (1, 1));
+        assert_eq!(metrics.retry_stats().recovered, 1);
+        let resources = metrics.table_metrics().resources;
+        assert_eq!(
+            (resources.live, resources.inserted, resources.released),
+            (0, 1, 1)
+        );
+
+        // 未计入引擎的运行不影响任何引擎的统计
+        let ctx = FlowContext::new(Uuid::new_v4());
+        ctx.run.mark_started();
+        drop(ctx);
+        assert_eq!(metrics.flow_stats().started, 1);
+        assert_eq!(EngineMetrics::new().flow_stats(), FlowStats::default());
+    }
+}
@@ -1,19 +1,33 @@
+pub mod debug;
+pub mod descriptor;
 pub mod flow;
 pub mod message;
+pub mod metrics;
 pub mod node;
 pub mod payload;
+pub mod retry;
+pub mod run;
 pub mod sender;
+pub mod stream;
+pub mod table;
 pub mod value;
 
 // 为了保持向后兼容性，从旧位置重新导出
+pub use debug::{DebugConfig, DebugTap, TraceEvent};
+pub use descriptor::{NodeDescriptor, PortDescriptor};
 pub use flow::{FlowContext, FlowEventKey, FlowListeners};
 pub use message::{EngineConfig, EngineMessage};
-pub use node::{
-    Node, NodeBuilder, NodeError, NodeFactory, NodeInfo, NodeInput, NodeInputPorts, NodeOutput,
-    NodeOutputPorts, NodeRunItem
+pub use metrics::{
+    EngineMetrics, FlowStats, NodeMetrics, NodeStats, QueueStats, render_prometheus,
 };
-pub use payload::{
-    Handle, Payload, Resource, ResourceId, ResourceTable, Stream, StreamId, StreamTable,
+pub use node::{
+    CATCH_NODE_TYPE, DELAY_NODE_TYPE, Node, NodeBuilder, NodeError, NodeFactory, NodeInfo,
+    NodeInput, NodeInputPorts, NodeOutput, NodeOutputPorts, NodeRunItem, error_value,
 };
+pub use payload::{Handle, Payload, Resource, ResourceId, Stream, StreamId, StreamTrait};
+pub use retry::{Backoff, RetryPolicy, RetryStats};
+pub use run::{FlowHandle, FlowOutput, FlowResult, FlowRun, FlowStatus, NodeFailure};
 pub use sender::EngineContext;
+pub use stream::{ChannelStream, IoStream};
+pub use table::{ResourceTable, StreamTable, TableMetrics, TableStats};
 pub use value::Value;
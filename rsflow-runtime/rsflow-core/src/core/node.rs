@@ -1,4 +1,7 @@
-use crate::core::{EngineContext, FlowContext, Payload, ResourceId, StreamId, Value};
+use crate::core::{
+    EngineContext, FlowContext, NodeDescriptor, Payload, ResourceId, RetryPolicy, StreamId, Value,
+};
+use crate::flow::{Diagnostic, Flow, FlowNode};
 use std::collections::HashMap;
 use std::sync::Arc;
 use uuid::Uuid;
@@ -40,6 +43,36 @@ pub struct NodeInfo {
     pub input_ports: NodeInputPorts,
     pub output_ports: NodeOutputPorts,
     pub global_config: Value,
+    /// 错误输出端口
+    pub error_port: Option<u8>,
+    /// 重试策略
+    pub retry: Option<RetryPolicy>,
+}
+
+/// catch 节点类型，接收未连接错误端口的节点错误
+pub const CATCH_NODE_TYPE: &str = "catch";
+
+/// 延迟节点类型，环路中包含该类型的节点时不视为死循环（见 validate_node_types）
+pub const DELAY_NODE_TYPE: &str = "delay";
+
+/// 节点出错时输出的值：原始对象加上 error 字段 { node_id, node_name, node_type, message }；
+/// 原始值不是对象时为 { error, msg }
+pub fn error_value(info: &NodeInfo, err: &NodeError, msg: Value) -> Value {
+    let mut error = HashMap::new();
+    error.insert("node_id".to_string(), Value::String(info.id.to_string()));
+    error.insert("node_name".to_string(), Value::String(info.name.clone()));
+    error.insert(
+        "node_type".to_string(),
+        Value::String(info.node_type.clone()),
+    );
+    error.insert("message".to_string(), Value::String(format!("{:?}", err)));
+
+    let mut map = match msg {
+        Value::Object(map) => map,
+        other => HashMap::from([("msg".to_string(), other)]),
+    };
+    map.insert("error".to_string(), Value::Object(error));
+    Value::Object(map)
 }
 
 // ===== 错误处理 =====
@@ -52,7 +85,55 @@ pub enum NodeError {
     Timeout,
     Cancelled,
     ResourceNotFound(ResourceId),
+    /// 资源类型与请求的类型不一致
+    ResourceTypeMismatch {
+        id: ResourceId,
+        expected: &'static str,
+        actual: &'static str,
+    },
+    /// 资源仍被其他地方引用，无法取得所有权
+    ResourceInUse(ResourceId),
     StreamNotFound(StreamId),
+    /// 流已关闭，不能再写入
+    StreamClosed,
+    /// 流不支持该操作（如只写流上的 read）
+    Unsupported(String),
+}
+
+impl NodeError {
+    /// 所有变体名，用于重试策略等配置
+    pub const KINDS: &'static [&'static str] = &[
+        "InvalidInput",
+        "InvalidConfig",
+        "Io",
+        "Shell",
+        "Timeout",
+        "Cancelled",
+        "ResourceNotFound",
+        "ResourceTypeMismatch",
+        "ResourceInUse",
+        "StreamNotFound",
+        "StreamClosed",
+        "Unsupported",
+    ];
+
+    /// 变体名
+    pub fn kind(&self) -> &'static str {
+        match self {
+            NodeError::InvalidInput(_) => "InvalidInput",
+            NodeError::InvalidConfig(_) => "InvalidConfig",
+            NodeError::Io(_) => "Io",
+            NodeError::Shell(_) => "Shell",
+            NodeError::Timeout => "Timeout",
+            NodeError::Cancelled => "Cancelled",
+            NodeError::ResourceNotFound(_) => "ResourceNotFound",
+            NodeError::ResourceTypeMismatch { .. } => "ResourceTypeMismatch",
+            NodeError::ResourceInUse(_) => "ResourceInUse",
+            NodeError::StreamNotFound(_) => "StreamNotFound",
+            NodeError::StreamClosed => "StreamClosed",
+            NodeError::Unsupported(_) => "Unsupported",
+        }
+    }
 }
 
 // ===== 核心功能trait =====
@@ -64,6 +145,9 @@ pub trait Node: Send + Sync {
     /// 引擎启动时调用
     async fn engine_start(&self, sender: EngineContext);
 
+    /// 引擎停止时调用（运行中的 flow 已排空或超时）
+    async fn engine_stop(&self) {}
+
     /// 节点接收到事件时的处理
     async fn event(
         &self,
@@ -89,5 +173,13 @@ pub trait NodeFactory: Send + Sync {
 #[async_trait::async_trait]
 pub trait NodeBuilder: Send + Sync {
     fn node_type(&self) -> &str;
+    /// 节点类型说明（端口、配置 Schema 等），默认一个输入、一个输出端口且不限制配置
+    fn descriptor(&self) -> NodeDescriptor {
+        NodeDescriptor::new(self.node_type())
+    }
+    /// 针对该类型节点的风格检查（rsflow lint），只返回 warning，默认没有
+    fn lint(&self, _flow: &Flow, _node: &FlowNode) -> Vec<Diagnostic> {
+        Vec::new()
+    }
     async fn register(&self, global_config: &Value) -> Result<Box<dyn NodeFactory>, NodeError>;
 }
@@ -1,7 +1,5 @@
-use crate::core::Value;
-use std::collections::HashMap;
+use crate::core::{NodeError, Value};
 use std::sync::Arc;
-use tokio::sync::Mutex;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -60,50 +58,43 @@ pub enum Handle {
 
 pub type Resource = Arc<dyn std::any::Any + Send + Sync>;
 
+/// 节点之间传递的流，适配器见 core::stream
+///
+/// - read / write 以字节为单位，next / send 以 Value 为单位（分帧后的数据项）
+/// - 背压：write / send 在下游缓冲区满时等待，而不是无限缓存
+/// - 关闭：close 关闭写入端，之后 write / send 返回 NodeError::StreamClosed；
+///   读取端在读完已缓冲的数据后得到 None
+#[async_trait::async_trait]
 pub trait StreamTrait: Send + Sync {
-    // 你可以定义 read / write / async next
-}
-
-pub type Stream = Arc<dyn StreamTrait>;
-
-#[derive(Clone)]
-pub struct ResourceTable {
-    map: Arc<Mutex<HashMap<ResourceId, Resource>>>,
-}
-
-#[derive(Clone)]
-pub struct StreamTable {
-    map: Arc<Mutex<HashMap<StreamId, Stream>>>,
-}
-
-impl ResourceTable {
-    pub fn new() -> Self {
-        Self {
-            map: Arc::new(Mutex::new(HashMap::new())),
-        }
+    /// 读取下一段字节，None 表示流已结束
+    async fn read(&self) -> Result<Option<Vec<u8>>, NodeError> {
+        Err(NodeError::Unsupported("read".to_string()))
     }
 
-    pub async fn insert(&self, id: ResourceId, res: Resource) {
-        self.map.lock().await.insert(id, res);
+    /// 写入数据，节点可以通过它在同一连接上回复
+    async fn write(&self, _data: &[u8]) -> Result<(), NodeError> {
+        Err(NodeError::Unsupported("write".to_string()))
     }
 
-    pub async fn get(&self, id: &ResourceId) -> Option<Resource> {
-        self.map.lock().await.get(id).cloned()
+    /// 读取下一项，默认将 read 的字节转为 Value
+    async fn next(&self) -> Result<Option<Value>, NodeError> {
+        Ok(self.read().await?.map(Value::from_bytes))
     }
-}
 
-impl StreamTable {
-    pub fn new() -> Self {
-        Self {
-            map: Arc::new(Mutex::new(HashMap::new())),
-        }
+    /// 写入一项，默认按 Value::to_bytes 写入
+    async fn send(&self, item: Value) -> Result<(), NodeError> {
+        self.write(&item.to_bytes()).await
     }
 
-    pub async fn insert(&self, id: StreamId, s: Stream) {
-        self.map.lock().await.insert(id, s);
+    /// 关闭写入端
+    async fn close(&self) -> Result<(), NodeError> {
+        Ok(())
     }
 
-    pub async fn get(&self, id: &StreamId) -> Option<Stream> {
-        self.map.lock().await.get(id).cloned()
+    /// 写入端是否已关闭
+    fn is_closed(&self) -> bool {
+        false
     }
 }
+
+pub type Stream = Arc<dyn StreamTrait>;
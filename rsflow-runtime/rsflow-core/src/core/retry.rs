@@ -0,0 +1,239 @@
+use crate::core::NodeError;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// 退避方式
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Backoff {
+    /// 每次等待 delay
+    Fixed,
+    /// 第 n 次重试等待 delay * multiplier^(n-1)，不超过 max_delay
+    #[default]
+    Exponential,
+}
+
+/// 节点重试策略，对应 FlowNode 的 retry 配置
+///
+/// ```json
+/// "retry": { "max_attempts": 3, "backoff": "exponential", "delay": 200,
+///            "max_delay": 5000, "multiplier": 2.0, "jitter": 0.2,
+///            "retry_on": ["Timeout", "Io"] }
+/// ```
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// 最多执行次数（含第一次）
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    #[serde(default)]
+    pub backoff: Backoff,
+    /// 首次重试前等待的毫秒数
+    #[serde(default = "default_delay")]
+    pub delay: u64,
+    /// 等待时间上限（毫秒）
+    #[serde(default = "default_max_delay")]
+    pub max_delay: u64,
+    #[serde(default = "default_multiplier")]
+    pub multiplier: f64,
+    /// 随机抖动比例 0-1，实际等待时间在 delay * (1 ± jitter) 之间，同样不超过 max_delay
+    #[serde(default)]
+    pub jitter: f64,
+    /// 可重试的 NodeError 变体名，见 NodeError::kind
+    #[serde(default = "default_retry_on")]
+    pub retry_on: Vec<String>,
+}
+
+fn default_max_attempts() -> u32 {
+    3
+}
+
+fn default_delay() -> u64 {
+    200
+}
+
+fn default_max_delay() -> u64 {
+    30_000
+}
+
+fn default_multiplier() -> f64 {
+    2.0
+}
+
+fn default_retry_on() -> Vec<String> {
+    vec!["Timeout".to_string(), "Io".to_string()]
+}
+
+impl RetryPolicy {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_attempts == 0 {
+            return Err("max_attempts must be at least 1".to_string());
+        }
+        if self.multiplier.is_nan() || self.multiplier < 1.0 {
+            return Err(format!("multiplier must be >= 1: {}", self.multiplier));
+        }
+        if !(0.0..=1.0).contains(&self.jitter) {
+            return Err(format!("jitter must be between 0 and 1: {}", self.jitter));
+        }
+        if let Some(kind) = self
+            .retry_on
+            .iter()
+            .find(|kind| !NodeError::KINDS.contains(&kind.as_str()))
+        {
+            return Err(format!(
+                "unknown error kind {} (expected one of {:?})",
+                kind,
+                NodeError::KINDS
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn is_retryable(&self, err: &NodeError) -> bool {
+        self.retry_on.iter().any(|kind| kind == err.kind())
+    }
+
+    /// 第 attempt 次执行失败后等待的时间（attempt 从 1 开始）
+    pub fn delay_after(&self, attempt: u32) -> Duration {
+        let base = match self.backoff {
+            Backoff::Fixed => self.delay as f64,
+            Backoff::Exponential => {
+                let exp = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+                self.delay as f64 * self.multiplier.powi(exp)
+            }
+        };
+        let max = self.max_delay as f64;
+        let mut millis = base.min(max);
+        if self.jitter > 0.0 {
+            millis *= rand::thread_rng().gen_range(1.0 - self.jitter..=1.0 + self.jitter);
+        }
+        Duration::from_millis(millis.clamp(0.0, max) as u64)
+    }
+}
+
+/// 重试统计
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RetryStats {
+    /// 累计重试次数
+    pub attempts: u64,
+    /// 重试后成功的执行数
+    pub recovered: u64,
+    /// 用尽重试次数仍失败的执行数
+    pub exhausted: u64,
+}
+
+/// 一个引擎的重试计数器
+#[derive(Debug, Default)]
+pub(crate) struct RetryCounters {
+    attempts: AtomicU64,
+    recovered: AtomicU64,
+    exhausted: AtomicU64,
+}
+
+impl RetryCounters {
+    pub fn stats(&self) -> RetryStats {
+        RetryStats {
+            attempts: self.attempts.load(Ordering::Relaxed),
+            recovered: self.recovered.load(Ordering::Relaxed),
+            exhausted: self.exhausted.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn record_attempt(&self) {
+        self.attempts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_recovered(&self) {
+        self.recovered.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_exhausted(&self) {
+        self.exhausted.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(json: &str) -> RetryPolicy {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn millis(policy: &RetryPolicy, attempt: u32) -> u64 {
+        policy.delay_after(attempt).as_millis() as u64
+    }
+
+    #[test]
+    fn defaults() {
+        let p = policy("{}");
+        assert_eq!(p.max_attempts, 3);
+        assert_eq!(p.backoff, Backoff::Exponential);
+        assert_eq!(
+            (p.delay, p.max_delay, p.multiplier, p.jitter),
+            (200, 30_000, 2.0, 0.0)
+        );
+        assert!(p.validate().is_ok());
+    }
+
+    #[test]
+    fn exponential_backoff_grows_until_max_delay() {
+        let p = policy(r#"{ "delay": 100, "multiplier": 3.0, "max_delay": 2000 }"#);
+        let delays: Vec<u64> = (1..=5).map(|attempt| millis(&p, attempt)).collect();
+        assert_eq!(delays, vec![100, 300, 900, 2000, 2000]);
+        // 指数很大时不溢出
+        assert_eq!(millis(&p, u32::MAX), 2000);
+    }
+
+    #[test]
+    fn fixed_backoff_is_constant() {
+        let p = policy(r#"{ "backoff": "fixed", "delay": 150, "max_delay": 100 }"#);
+        assert_eq!(millis(&p, 1), 100);
+        let p = policy(r#"{ "backoff": "fixed", "delay": 150 }"#);
+        assert!((1..=10).all(|attempt| millis(&p, attempt) == 150));
+    }
+
+    #[test]
+    fn jitter_stays_within_bounds() {
+        let p = policy(r#"{ "backoff": "fixed", "delay": 1000, "jitter": 0.2 }"#);
+        for _ in 0..200 {
+            let delay = millis(&p, 1);
+            assert!((800..=1200).contains(&delay), "{}", delay);
+        }
+        // 抖动后同样不超过 max_delay
+        let p = policy(r#"{ "delay": 1000, "max_delay": 1000, "jitter": 0.5 }"#);
+        for _ in 0..200 {
+            let delay = millis(&p, 3);
+            assert!((500..=1000).contains(&delay), "{}", delay);
+        }
+    }
+
+    #[test]
+    fn validate_rejects_invalid_policies() {
+        for json in [
+            r#"{ "max_attempts": 0 }"#,
+            r#"{ "multiplier": 0.5 }"#,
+            r#"{ "jitter": 1.5 }"#,
+            r#"{ "jitter": -0.1 }"#,
+            r#"{ "retry_on": ["Timeout", "Nope"] }"#,
+        ] {
+            assert!(policy(json).validate().is_err(), "{}", json);
+        }
+        let mut p = policy("{}");
+        p.multiplier = f64::NAN;
+        assert!(p.validate().is_err());
+    }
+
+    #[test]
+    fn retryable_by_error_kind() {
+        let p = policy("{}");
+        assert!(p.is_retryable(&NodeError::Timeout));
+        assert!(p.is_retryable(&NodeError::Io(std::io::Error::other("reset"))));
+        assert!(!p.is_retryable(&NodeError::InvalidInput("bad".into())));
+
+        let p = policy(r#"{ "retry_on": ["Shell"] }"#);
+        assert!(p.is_retryable(&NodeError::Shell("exit 1".into())));
+        assert!(!p.is_retryable(&NodeError::Timeout));
+    }
+}
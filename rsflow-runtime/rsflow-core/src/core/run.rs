@@ -0,0 +1,281 @@
+use crate::core::{EngineMetrics, NodeError, Payload};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// 一次 flow 运行的结束状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlowStatus {
+    /// 所有分支正常结束
+    Completed,
+    /// 所有分支已结束，且有节点返回错误
+    Failed,
+    /// 通过 FlowHandle::cancel 取消
+    Cancelled,
+    /// 未被引擎执行（引擎停止中或已退出）
+    Rejected,
+}
+
+/// 节点执行错误
+#[derive(Debug, Clone)]
+pub struct NodeFailure {
+    pub node_id: Uuid,
+    pub node_name: String,
+    pub message: String,
+}
+
+impl NodeFailure {
+    pub fn new(node_id: Uuid, node_name: String, err: &NodeError) -> Self {
+        Self {
+            node_id,
+            node_name,
+            message: format!("{:?}", err),
+        }
+    }
+}
+
+/// 最终输出：节点输出到没有连线的端口
+#[derive(Debug, Clone)]
+pub struct FlowOutput {
+    pub node_id: Uuid,
+    pub port: u8,
+    pub payload: Payload,
+}
+
+/// 一次 flow 运行（含全部分支）的结果
+#[derive(Debug, Clone)]
+pub struct FlowResult {
+    pub id: Uuid,
+    pub status: FlowStatus,
+    pub errors: Vec<NodeFailure>,
+    pub outputs: Vec<FlowOutput>,
+}
+
+struct RunState {
+    id: Uuid,
+    started: AtomicBool,
+    cancel: CancellationToken,
+    errors: Mutex<Vec<NodeFailure>>,
+    outputs: Mutex<Vec<FlowOutput>>,
+    done: watch::Sender<Option<FlowResult>>,
+    // 执行本次运行的引擎的统计，首次交给引擎时设置
+    metrics: OnceLock<Arc<EngineMetrics>>,
+}
+
+impl Drop for RunState {
+    // 最后一个分支的 FlowContext 释放时运行结束
+    fn drop(&mut self) {
+        let errors = std::mem::take(self.errors.get_mut().unwrap());
+        let status = if self.cancel.is_cancelled() {
+            FlowStatus::Cancelled
+        } else if !self.started.load(Ordering::SeqCst) {
+            FlowStatus::Rejected
+        } else if !errors.is_empty() {
+            FlowStatus::Failed
+        } else {
+            FlowStatus::Completed
+        };
+        if let Some(metrics) = self.metrics.get() {
+            metrics.record_flow_finished(status);
+        }
+        self.done.send_replace(Some(FlowResult {
+            id: self.id,
+            status,
+            errors,
+            outputs: std::mem::take(self.outputs.get_mut().unwrap()),
+        }));
+    }
+}
+
+/// 一次 flow 运行的共享状态，由同一运行的所有分支共享
+#[derive(Clone)]
+pub struct FlowRun {
+    state: Arc<RunState>,
+}
+
+impl FlowRun {
+    pub fn new(id: Uuid) -> Self {
+        let (done, _) = watch::channel(None);
+        Self {
+            state: Arc::new(RunState {
+                id,
+                started: AtomicBool::new(false),
+                cancel: CancellationToken::new(),
+                errors: Mutex::new(Vec::new()),
+                outputs: Mutex::new(Vec::new()),
+                done,
+                metrics: OnceLock::new(),
+            }),
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        self.state.id
+    }
+
+    pub fn handle(&self) -> FlowHandle {
+        FlowHandle {
+            id: self.state.id,
+            done: self.state.done.subscribe(),
+            cancel: self.state.cancel.clone(),
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancel.is_cancelled()
+    }
+
+    pub async fn cancelled(&self) {
+        self.state.cancel.cancelled().await
+    }
+
+    /// 计入引擎统计，同一运行只计入第一个执行它的引擎
+    pub(crate) fn attach(&self, metrics: &Arc<EngineMetrics>) {
+        let _ = self.state.metrics.set(Arc::clone(metrics));
+    }
+
+    pub(crate) fn mark_started(&self) {
+        if !self.state.started.swap(true, Ordering::SeqCst)
+            && let Some(metrics) = self.state.metrics.get()
+        {
+            metrics.record_flow_started();
+        }
+    }
+
+    pub(crate) fn record_error(&self, failure: NodeFailure) {
+        self.state.errors.lock().unwrap().push(failure);
+    }
+
+    pub(crate) fn record_output(&self, output: FlowOutput) {
+        self.state.outputs.lock().unwrap().push(output);
+    }
+}
+
+/// flow 运行句柄，所有分支结束后 wait 返回结果
+#[derive(Clone)]
+pub struct FlowHandle {
+    id: Uuid,
+    done: watch::Receiver<Option<FlowResult>>,
+    cancel: CancellationToken,
+}
+
+impl FlowHandle {
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// 取消运行：尚未执行的节点不再执行，执行中的节点被中断
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.done.borrow().is_some()
+    }
+
+    /// 等待所有分支结束
+    pub async fn wait(mut self) -> FlowResult {
+        // 发送端在写入结果后才会释放，wait_for 出错时结果也已写入
+        let _ = self.done.wait_for(Option::is_some).await;
+        let result = self.done.borrow().clone();
+        result.unwrap_or(FlowResult {
+            id: self.id,
+            status: FlowStatus::Rejected,
+            errors: Vec::new(),
+            outputs: Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{FlowContext, Value};
+    use crate::engine::testing::{Probe, engine_builder, start_at, start_engine, wait_until};
+    use crate::flow::{FlowBuilder, FlowModBuilder, NodeSpec};
+
+    fn failure() -> NodeFailure {
+        NodeFailure::new(
+            Uuid::new_v4(),
+            "node".to_string(),
+            &NodeError::Shell("failed".to_string()),
+        )
+    }
+
+    #[tokio::test]
+    async fn wait_returns_after_last_branch() {
+        let ctx = FlowContext::new(Uuid::new_v4());
+        let handle = ctx.handle();
+        ctx.run.mark_started();
+        let branch = ctx.new_branch();
+        branch.run.record_output(FlowOutput {
+            node_id: Uuid::new_v4(),
+            port: 0,
+            payload: Payload::new(Value::Int(1)),
+        });
+        drop(ctx);
+        assert!(!handle.is_finished());
+
+        drop(branch);
+        assert!(handle.is_finished());
+        let result = handle.wait().await;
+        assert_eq!(result.status, FlowStatus::Completed);
+        assert_eq!(result.outputs.len(), 1);
+        assert!(result.errors.is_empty());
+    }
+
+    #[tokio::test]
+    async fn wait_returns_failed_rejected_and_cancelled() {
+        let ctx = FlowContext::new(Uuid::new_v4());
+        let handle = ctx.handle();
+        ctx.run.mark_started();
+        ctx.run.record_error(failure());
+        drop(ctx);
+        let result = handle.wait().await;
+        assert_eq!(result.status, FlowStatus::Failed);
+        assert_eq!(result.errors.len(), 1);
+
+        // 未开始执行即释放
+        let ctx = FlowContext::new(Uuid::new_v4());
+        let handle = ctx.handle();
+        drop(ctx);
+        assert_eq!(handle.wait().await.status, FlowStatus::Rejected);
+
+        // 取消优先于错误
+        let ctx = FlowContext::new(Uuid::new_v4());
+        let handle = ctx.handle();
+        ctx.run.mark_started();
+        ctx.run.record_error(failure());
+        handle.cancel();
+        assert!(ctx.run.is_cancelled());
+        drop(ctx);
+        assert_eq!(handle.wait().await.status, FlowStatus::Cancelled);
+    }
+
+    #[tokio::test]
+    async fn cancel_stops_running_flow() {
+        let probe = Arc::new(Probe::default());
+        let mut flow = FlowBuilder::new("cancel");
+        let slow = flow.add(NodeSpec::new("slow"));
+        let pass = flow.add(NodeSpec::new("pass"));
+        flow.wire(slow, 0, pass, 0);
+        let flow_mod = FlowModBuilder::new().flow(flow.build()).build();
+        let (handle, _) = start_engine(engine_builder(&probe), flow_mod).await;
+
+        let run = handle
+            .context()
+            .run_flow(start_at(slow.id(), Value::Int(1)))
+            .await;
+        wait_until(|| probe.inputs() == 1).await;
+        run.cancel();
+        let result = run.wait().await;
+        assert_eq!(result.status, FlowStatus::Cancelled);
+        assert!(result.outputs.is_empty());
+
+        // 被中断的 slow 节点不再向下游输出
+        tokio::time::sleep(std::time::Duration::from_millis(150)).await;
+        assert_eq!(probe.inputs(), 1);
+    }
+}
@@ -1,20 +1,50 @@
-use crate::core::{EngineMessage, FlowContext, NodeRunItem, Payload};
+use crate::core::{
+    DebugTap, EngineMessage, EngineMetrics, FlowContext, FlowHandle, NodeRunItem, Payload,
+    QueueStats,
+};
 use crate::flow::FlowMod;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
+#[derive(Clone)]
 pub struct EngineContext {
-    pub flow_mod:Arc<FlowMod>,
+    /// 与引擎共享，热重载后随之更新，读取用 get_mod
+    pub flow_mod: Arc<RwLock<Arc<FlowMod>>>,
     pub sender: tokio::sync::mpsc::Sender<EngineMessage>,
+    /// 引擎停止信号
+    pub shutdown: CancellationToken,
+    /// 节点执行记录
+    pub debug: Arc<DebugTap>,
+    /// 本引擎的运行、重试、资源表和节点执行统计
+    pub metrics: Arc<EngineMetrics>,
 }
 
 impl EngineContext {
-    pub async fn run_flow(&self, start_node: NodeRunItem) {
+    /// 当前的 FlowMod
+    pub fn get_mod(&self) -> Arc<FlowMod> {
+        Arc::clone(&self.flow_mod.read().unwrap())
+    }
+
+    /// 启动 flow，返回的句柄在所有分支结束后给出结果
+    pub async fn run_flow(&self, start_node: NodeRunItem) -> FlowHandle {
         let ctx = FlowContext::new(Uuid::new_v4());
-        let _ = self
-            .sender
+        let handle = ctx.handle();
+        // 停止中不再接收新的 flow，ctx 被丢弃，句柄结果为 Rejected
+        self.run_flow_with_ctx(ctx, start_node).await;
+        handle
+    }
+
+    /// 使用调用方创建的上下文启动 flow，便于在启动前注册 listeners
+    pub async fn run_flow_with_ctx(&self, ctx: FlowContext, start_node: NodeRunItem) -> bool {
+        ctx.attach_metrics(&self.metrics);
+        if self.is_stopping() {
+            return false;
+        }
+        self.sender
             .send(EngineMessage::RunFlow { ctx, start_node })
-            .await;
+            .await
+            .is_ok()
     }
     
     pub async fn node_send(
@@ -34,4 +64,28 @@ impl EngineContext {
             })
             .await;
     }
-}
\ No newline at end of file
+
+    /// 引擎是否已开始停止
+    pub fn is_stopping(&self) -> bool {
+        self.shutdown.is_cancelled()
+    }
+
+    /// 引擎消息队列的使用情况
+    pub fn queue_stats(&self) -> QueueStats {
+        let capacity = self.sender.max_capacity();
+        QueueStats {
+            len: capacity - self.sender.capacity(),
+            capacity,
+        }
+    }
+
+    /// 以 Prometheus 文本格式输出引擎指标
+    pub fn render_metrics(&self) -> String {
+        crate::core::render_prometheus(self.queue_stats(), &self.metrics)
+    }
+
+    /// 等待引擎停止信号，供节点的后台任务退出使用
+    pub async fn stopped(&self) {
+        self.shutdown.cancelled().await
+    }
+}
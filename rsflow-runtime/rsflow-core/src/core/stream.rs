@@ -0,0 +1,324 @@
+use crate::core::{NodeError, StreamTrait, Value};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::sync::{Mutex, mpsc};
+
+// 单次 read 的最大字节数
+const READ_CHUNK: usize = 8 * 1024;
+
+type BoxReader = Pin<Box<dyn AsyncRead + Send>>;
+type BoxWriter = Pin<Box<dyn AsyncWrite + Send>>;
+
+/// tokio::io::AsyncRead / AsyncWrite 适配为 Stream
+///
+/// 读取按块进行，不缓存整个内容；写入使用 write_all，由底层 IO 提供背压。
+pub struct IoStream {
+    reader: Option<Mutex<BoxReader>>,
+    writer: Option<Mutex<BoxWriter>>,
+    closed: AtomicBool,
+}
+
+impl IoStream {
+    pub fn new<R, W>(reader: R, writer: W) -> Self
+    where
+        R: AsyncRead + Send + 'static,
+        W: AsyncWrite + Send + 'static,
+    {
+        Self {
+            reader: Some(Mutex::new(Box::pin(reader))),
+            writer: Some(Mutex::new(Box::pin(writer))),
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    /// 只读流
+    pub fn reader<R: AsyncRead + Send + 'static>(reader: R) -> Self {
+        Self {
+            reader: Some(Mutex::new(Box::pin(reader))),
+            writer: None,
+            closed: AtomicBool::new(true),
+        }
+    }
+
+    /// 只写流
+    pub fn writer<W: AsyncWrite + Send + 'static>(writer: W) -> Self {
+        Self {
+            reader: None,
+            writer: Some(Mutex::new(Box::pin(writer))),
+            closed: AtomicBool::new(false),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl StreamTrait for IoStream {
+    async fn read(&self) -> Result<Option<Vec<u8>>, NodeError> {
+        let Some(reader) = &self.reader else {
+            return Err(NodeError::Unsupported("read".to_string()));
+        };
+        let mut buf = vec![0u8; READ_CHUNK];
+        let n = reader
+            .lock()
+            .await
+            .read(&mut buf)
+            .await
+            .map_err(NodeError::Io)?;
+        if n == 0 {
+            return Ok(None);
+        }
+        buf.truncate(n);
+        Ok(Some(buf))
+    }
+
+    async fn write(&self, data: &[u8]) -> Result<(), NodeError> {
+        let Some(writer) = &self.writer else {
+            return Err(NodeError::Unsupported("write".to_string()));
+        };
+        if self.is_closed() {
+            return Err(NodeError::StreamClosed);
+        }
+        writer
+            .lock()
+            .await
+            .write_all(data)
+            .await
+            .map_err(NodeError::Io)
+    }
+
+    async fn close(&self) -> Result<(), NodeError> {
+        if self.closed.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+        if let Some(writer) = &self.writer {
+            writer.lock().await.shutdown().await.map_err(NodeError::Io)?;
+        }
+        Ok(())
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+}
+
+/// mpsc 通道适配为 Stream，以 Value 为单位传递
+///
+/// 使用有界通道时 send 在通道满时等待（背压）；close 丢弃发送端，
+/// 接收端读完剩余数据后得到 None。
+pub struct ChannelStream {
+    rx: Option<Mutex<mpsc::Receiver<Value>>>,
+    tx: std::sync::Mutex<Option<mpsc::Sender<Value>>>,
+    // 是否可写，与 close 后的关闭状态分开
+    writable: bool,
+}
+
+impl ChannelStream {
+    pub fn new(tx: mpsc::Sender<Value>, rx: mpsc::Receiver<Value>) -> Self {
+        Self {
+            rx: Some(Mutex::new(rx)),
+            tx: std::sync::Mutex::new(Some(tx)),
+            writable: true,
+        }
+    }
+
+    /// 只读流
+    pub fn from_receiver(rx: mpsc::Receiver<Value>) -> Self {
+        Self {
+            rx: Some(Mutex::new(rx)),
+            tx: std::sync::Mutex::new(None),
+            writable: false,
+        }
+    }
+
+    /// 只写流
+    pub fn from_sender(tx: mpsc::Sender<Value>) -> Self {
+        Self {
+            rx: None,
+            tx: std::sync::Mutex::new(Some(tx)),
+            writable: true,
+        }
+    }
+
+    /// 创建一对相连的流：(写入端, 读取端)，capacity 为缓冲的项数
+    pub fn pipe(capacity: usize) -> (Self, Self) {
+        let (tx, rx) = mpsc::channel(capacity);
+        (Self::from_sender(tx), Self::from_receiver(rx))
+    }
+
+    fn sender(&self) -> Result<mpsc::Sender<Value>, NodeError> {
+        self.tx
+            .lock()
+            .unwrap()
+            .clone()
+            .ok_or(NodeError::StreamClosed)
+    }
+}
+
+#[async_trait::async_trait]
+impl StreamTrait for ChannelStream {
+    async fn read(&self) -> Result<Option<Vec<u8>>, NodeError> {
+        Ok(self.next().await?.map(|item| item.to_bytes()))
+    }
+
+    async fn write(&self, data: &[u8]) -> Result<(), NodeError> {
+        self.send(Value::from_bytes(data.to_vec())).await
+    }
+
+    async fn next(&self) -> Result<Option<Value>, NodeError> {
+        let Some(rx) = &self.rx else {
+            return Err(NodeError::Unsupported("read".to_string()));
+        };
+        Ok(rx.lock().await.recv().await)
+    }
+
+    async fn send(&self, item: Value) -> Result<(), NodeError> {
+        if !self.writable {
+            return Err(NodeError::Unsupported("write".to_string()));
+        }
+        self.sender()?
+            .send(item)
+            .await
+            .map_err(|_| NodeError::StreamClosed)
+    }
+
+    async fn close(&self) -> Result<(), NodeError> {
+        self.tx.lock().unwrap().take();
+        Ok(())
+    }
+
+    fn is_closed(&self) -> bool {
+        self.tx.lock().unwrap().as_ref().is_none_or(|tx| tx.is_closed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn bytes(s: &str) -> Option<Vec<u8>> {
+        Some(s.as_bytes().to_vec())
+    }
+
+    #[tokio::test]
+    async fn io_stream_read_write_and_close() {
+        let (client, mut server) = tokio::io::duplex(64);
+        let stream = IoStream::writer(client);
+        stream.write(b"hello").await.unwrap();
+        stream.close().await.unwrap();
+        // 重复关闭无副作用，关闭后写入返回 StreamClosed
+        stream.close().await.unwrap();
+        assert!(stream.is_closed());
+        assert!(matches!(
+            stream.write(b"late").await,
+            Err(NodeError::StreamClosed)
+        ));
+        assert!(matches!(
+            stream.read().await,
+            Err(NodeError::Unsupported(_))
+        ));
+        let mut received = Vec::new();
+        server.read_to_end(&mut received).await.unwrap();
+        assert_eq!(received, b"hello");
+
+        let stream = IoStream::reader(&b"data"[..]);
+        assert_eq!(stream.read().await.unwrap(), bytes("data"));
+        assert_eq!(stream.read().await.unwrap(), None);
+        assert!(matches!(
+            stream.write(b"x").await,
+            Err(NodeError::Unsupported(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn io_stream_write_waits_for_reader() {
+        let (client, mut server) = tokio::io::duplex(4);
+        let (reader, writer) = tokio::io::split(client);
+        let stream = IoStream::new(reader, writer);
+
+        // 缓冲区只有 4 字节，对端读取前写入不能完成
+        let write = stream.write(b"12345678");
+        tokio::pin!(write);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(50), &mut write)
+                .await
+                .is_err()
+        );
+        let mut buf = [0u8; 8];
+        let read = async {
+            server.read_exact(&mut buf).await.unwrap();
+        };
+        let (written, _) = tokio::join!(write, read);
+        written.unwrap();
+        assert_eq!(&buf, b"12345678");
+
+        server.write_all(b"ok").await.unwrap();
+        assert_eq!(stream.read().await.unwrap(), bytes("ok"));
+    }
+
+    #[tokio::test]
+    async fn channel_stream_send_next_and_close() {
+        let (writer, reader) = ChannelStream::pipe(4);
+        writer.send(Value::Int(1)).await.unwrap();
+        writer.write(b"raw").await.unwrap();
+        writer.close().await.unwrap();
+        assert!(writer.is_closed());
+        assert!(matches!(
+            writer.send(Value::Int(2)).await,
+            Err(NodeError::StreamClosed)
+        ));
+        assert!(matches!(
+            writer.next().await,
+            Err(NodeError::Unsupported(_))
+        ));
+
+        // 关闭后读完剩余数据得到 None
+        assert_eq!(reader.next().await.unwrap(), Some(Value::Int(1)));
+        assert_eq!(reader.read().await.unwrap(), bytes("raw"));
+        assert_eq!(reader.next().await.unwrap(), None);
+        assert!(matches!(
+            reader.send(Value::Int(3)).await,
+            Err(NodeError::Unsupported(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn channel_stream_backpressure() {
+        let (writer, reader) = ChannelStream::pipe(1);
+        writer.send(Value::Int(1)).await.unwrap();
+        // 通道已满，send 等待读取
+        let send = writer.send(Value::Int(2));
+        tokio::pin!(send);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(50), &mut send)
+                .await
+                .is_err()
+        );
+        assert_eq!(reader.next().await.unwrap(), Some(Value::Int(1)));
+        send.await.unwrap();
+        assert_eq!(reader.next().await.unwrap(), Some(Value::Int(2)));
+
+        // 读取端释放后写入返回 StreamClosed
+        drop(reader);
+        assert!(writer.is_closed());
+        assert!(matches!(
+            writer.send(Value::Int(3)).await,
+            Err(NodeError::StreamClosed)
+        ));
+    }
+
+    #[tokio::test]
+    async fn closed_duplex_channel_stream_reports_closed() {
+        let (tx, rx) = mpsc::channel(4);
+        let stream = ChannelStream::new(tx, rx);
+        stream.send(Value::Int(1)).await.unwrap();
+        assert_eq!(stream.next().await.unwrap(), Some(Value::Int(1)));
+
+        stream.close().await.unwrap();
+        assert!(matches!(
+            stream.send(Value::Int(2)).await,
+            Err(NodeError::StreamClosed)
+        ));
+        assert_eq!(stream.next().await.unwrap(), None);
+    }
+}
@@ -0,0 +1,513 @@
+use crate::core::{NodeError, Resource, ResourceId, Stream, StreamId};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use std::time::Duration;
+use tokio::runtime::Handle;
+
+/// 表项计数器，同一引擎的同类表共享
+#[derive(Debug, Default)]
+pub struct TableCounters {
+    live: AtomicUsize,
+    inserted: AtomicU64,
+    released: AtomicU64,
+    expired: AtomicU64,
+}
+
+impl TableCounters {
+    pub fn stats(&self) -> TableStats {
+        TableStats {
+            live: self.live.load(Ordering::Relaxed),
+            inserted: self.inserted.load(Ordering::Relaxed),
+            released: self.released.load(Ordering::Relaxed),
+            expired: self.expired.load(Ordering::Relaxed),
+        }
+    }
+
+    // 并入另一组计数器（表在计入引擎前的计数）
+    fn add(&self, other: &TableCounters) {
+        let stats = other.stats();
+        self.live.fetch_add(stats.live, Ordering::Relaxed);
+        self.inserted.fetch_add(stats.inserted, Ordering::Relaxed);
+        self.released.fetch_add(stats.released, Ordering::Relaxed);
+        self.expired.fetch_add(stats.expired, Ordering::Relaxed);
+    }
+}
+
+/// 表项统计
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TableStats {
+    /// 当前存活的表项数
+    pub live: usize,
+    /// 累计插入数
+    pub inserted: u64,
+    /// 累计释放数（最后一个分支结束、remove / take）
+    pub released: u64,
+    /// 累计因 TTL 过期移除的数量
+    pub expired: u64,
+}
+
+/// 一个引擎的 ResourceTable / StreamTable 统计
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TableMetrics {
+    pub resources: TableStats,
+    pub streams: TableStats,
+}
+
+/// 表的键类型
+pub trait TableKey: Copy + Eq + Hash + Send + Sync + 'static {}
+
+impl<T: Copy + Eq + Hash + Send + Sync + 'static> TableKey for T {}
+
+struct Entry<V> {
+    value: V,
+    // 持有该表项的分支数
+    refs: usize,
+    // 区分同一 id 的先后两次插入
+    generation: u64,
+    // 插入时记录的类型名，用于类型不匹配时的错误信息
+    type_name: &'static str,
+}
+
+// 未通过 insert_named 插入时的类型名
+const UNKNOWN_TYPE: &str = "unknown";
+
+struct Entries<K, V> {
+    map: Mutex<HashMap<K, Entry<V>>>,
+    next_generation: AtomicU64,
+    // 计入引擎前使用自身的计数器；计数器只在持有 map 锁时更新和替换
+    local: TableCounters,
+    shared: OnceLock<Arc<TableCounters>>,
+}
+
+impl<K: TableKey, V> Entries<K, V> {
+    fn counters(&self) -> &TableCounters {
+        self.shared.get().map_or(&self.local, |shared| shared)
+    }
+
+    fn remove(&self, id: &K) -> Option<V> {
+        let mut map = self.map.lock().unwrap();
+        let entry = map.remove(id)?;
+        self.counters().live.fetch_sub(1, Ordering::Relaxed);
+        self.counters().released.fetch_add(1, Ordering::Relaxed);
+        Some(entry.value)
+    }
+
+    // 分支不再持有该表项，引用数归零时移除
+    fn release(&self, id: &K, generation: u64) {
+        let mut map = self.map.lock().unwrap();
+        let Some(entry) = map.get_mut(id) else {
+            return;
+        };
+        if entry.generation != generation {
+            return;
+        }
+        entry.refs -= 1;
+        if entry.refs == 0 {
+            map.remove(id);
+            self.counters().live.fetch_sub(1, Ordering::Relaxed);
+            self.counters().released.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+// 一个分支持有的表项：id -> generation，分支结束（最后一个克隆被释放）时逐一释放
+struct Hold<K: TableKey, V> {
+    entries: Arc<Entries<K, V>>,
+    ids: Mutex<HashMap<K, u64>>,
+}
+
+impl<K: TableKey, V> Drop for Hold<K, V> {
+    fn drop(&mut self) {
+        for (id, generation) in self.ids.get_mut().unwrap().drain() {
+            self.entries.release(&id, generation);
+        }
+    }
+}
+
+/// 按分支引用计数的句柄表
+///
+/// - 同一 flow 的所有分支共享表项；FlowContext::new_branch 通过 branch 创建新分支，
+///   新分支持有父分支当前持有的全部表项
+/// - 表项在最后一个持有它的分支结束时释放；clone 不创建新分支，与原表共同持有
+/// - remove / take 立即移除表项，insert_with_ttl 的表项到期后自动移除
+pub struct Table<K: TableKey, V> {
+    hold: Arc<Hold<K, V>>,
+}
+
+impl<K: TableKey, V> Clone for Table<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            hold: Arc::clone(&self.hold),
+        }
+    }
+}
+
+impl<K: TableKey, V: Clone + Send + Sync + 'static> Table<K, V> {
+    pub fn new() -> Self {
+        Self::with_entries(Arc::new(Entries {
+            map: Mutex::new(HashMap::new()),
+            next_generation: AtomicU64::new(0),
+            local: TableCounters::default(),
+            shared: OnceLock::new(),
+        }))
+    }
+
+    fn with_entries(entries: Arc<Entries<K, V>>) -> Self {
+        Self {
+            hold: Arc::new(Hold {
+                entries,
+                ids: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+
+    fn entries(&self) -> &Arc<Entries<K, V>> {
+        &self.hold.entries
+    }
+
+    /// 之后的计数计入 counters（引擎的计数器），已有的计数一并计入；
+    /// 已计入其他计数器时不变
+    pub(crate) fn attach(&self, counters: &Arc<TableCounters>) {
+        let entries = self.entries();
+        let _map = entries.map.lock().unwrap();
+        if entries.shared.set(Arc::clone(counters)).is_ok() {
+            counters.add(&entries.local);
+        }
+    }
+
+    /// 创建新分支，持有当前分支持有的所有表项
+    pub fn branch(&self) -> Self {
+        let ids = self.hold.ids.lock().unwrap().clone();
+        let mut held = HashMap::new();
+        {
+            let mut map = self.entries().map.lock().unwrap();
+            for (id, generation) in ids {
+                if let Some(entry) = map.get_mut(&id)
+                    && entry.generation == generation
+                {
+                    entry.refs += 1;
+                    held.insert(id, generation);
+                }
+            }
+        }
+        let table = Self::with_entries(Arc::clone(self.entries()));
+        *table.hold.ids.lock().unwrap() = held;
+        table
+    }
+
+    /// 插入表项，由当前分支持有；id 已存在时替换值
+    pub fn insert(&self, id: K, value: V) {
+        self.insert_entry(id, value, UNKNOWN_TYPE);
+    }
+
+    /// 插入表项并记录值的类型名
+    pub fn insert_named(&self, id: K, value: V, type_name: &'static str) {
+        self.insert_entry(id, value, type_name);
+    }
+
+    /// 插入表项，ttl 到期后无论是否仍被持有都会移除
+    ///
+    /// 到期移除由 tokio 任务完成，须在 tokio 运行时中调用，否则返回错误且不插入。
+    pub fn insert_with_ttl(&self, id: K, value: V, ttl: Duration) -> Result<(), NodeError> {
+        let runtime = Handle::try_current().map_err(|_| {
+            NodeError::Unsupported("insert_with_ttl outside a tokio runtime".to_string())
+        })?;
+        let generation = self.insert_entry(id, value, UNKNOWN_TYPE);
+        let entries: Weak<Entries<K, V>> = Arc::downgrade(self.entries());
+        runtime.spawn(async move {
+            tokio::time::sleep(ttl).await;
+            let Some(entries) = entries.upgrade() else {
+                return;
+            };
+            let mut map = entries.map.lock().unwrap();
+            if map.get(&id).is_some_and(|e| e.generation == generation) {
+                map.remove(&id);
+                entries.counters().live.fetch_sub(1, Ordering::Relaxed);
+                entries.counters().expired.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        Ok(())
+    }
+
+    fn insert_entry(&self, id: K, value: V, type_name: &'static str) -> u64 {
+        let mut ids = self.hold.ids.lock().unwrap();
+        let mut map = self.entries().map.lock().unwrap();
+        if let Some(entry) = map.get_mut(&id) {
+            entry.value = value;
+            entry.type_name = type_name;
+            if ids.get(&id) != Some(&entry.generation) {
+                entry.refs += 1;
+                ids.insert(id, entry.generation);
+            }
+            return entry.generation;
+        }
+        let generation = self
+            .entries()
+            .next_generation
+            .fetch_add(1, Ordering::Relaxed);
+        map.insert(
+            id,
+            Entry {
+                value,
+                refs: 1,
+                generation,
+                type_name,
+            },
+        );
+        ids.insert(id, generation);
+        self.entries()
+            .counters()
+            .live
+            .fetch_add(1, Ordering::Relaxed);
+        self.entries()
+            .counters()
+            .inserted
+            .fetch_add(1, Ordering::Relaxed);
+        generation
+    }
+
+    pub fn get(&self, id: &K) -> Option<V> {
+        self.entries()
+            .map
+            .lock()
+            .unwrap()
+            .get(id)
+            .map(|entry| entry.value.clone())
+    }
+
+    /// 插入时记录的类型名，未记录时为 "unknown"
+    pub fn type_name(&self, id: &K) -> Option<&'static str> {
+        self.entries()
+            .map
+            .lock()
+            .unwrap()
+            .get(id)
+            .map(|entry| entry.type_name)
+    }
+
+    /// 当前分支也持有该表项（例如从其他分支收到的 id），返回表项是否存在
+    pub fn retain(&self, id: &K) -> bool {
+        let mut ids = self.hold.ids.lock().unwrap();
+        let mut map = self.entries().map.lock().unwrap();
+        let Some(entry) = map.get_mut(id) else {
+            return false;
+        };
+        if ids.get(id) != Some(&entry.generation) {
+            entry.refs += 1;
+            ids.insert(*id, entry.generation);
+        }
+        true
+    }
+
+    /// 立即移除表项
+    pub fn remove(&self, id: &K) -> bool {
+        self.hold.ids.lock().unwrap().remove(id);
+        self.entries().remove(id).is_some()
+    }
+
+    /// 移除表项并取得其值，用于单一消费者
+    pub fn take(&self, id: &K) -> Option<V> {
+        self.hold.ids.lock().unwrap().remove(id);
+        self.entries().remove(id)
+    }
+
+    /// check 通过时移除表项并取得其值，检查与移除在同一次加锁内完成
+    ///
+    /// 表项不存在时返回 None；check 失败时表项（包括持有关系和 TTL）保持不变。
+    pub fn take_if<E>(
+        &self,
+        id: &K,
+        check: impl FnOnce(&V, &'static str) -> Result<(), E>,
+    ) -> Option<Result<V, E>> {
+        let mut ids = self.hold.ids.lock().unwrap();
+        let mut map = self.entries().map.lock().unwrap();
+        let entry = map.get(id)?;
+        if let Err(e) = check(&entry.value, entry.type_name) {
+            return Some(Err(e));
+        }
+        let entry = map.remove(id)?;
+        ids.remove(id);
+        self.entries()
+            .counters()
+            .live
+            .fetch_sub(1, Ordering::Relaxed);
+        self.entries()
+            .counters()
+            .released
+            .fetch_add(1, Ordering::Relaxed);
+        Some(Ok(entry.value))
+    }
+
+    /// 表中（所有分支共享）的表项数
+    pub fn len(&self) -> usize {
+        self.entries().map.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<K: TableKey, V: Clone + Send + Sync + 'static> Default for Table<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub type ResourceTable = Table<ResourceId, Resource>;
+pub type StreamTable = Table<StreamId, Stream>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    struct TestId(u32);
+
+    type TestTable = Table<TestId, &'static str>;
+
+    const ID: TestId = TestId(1);
+
+    #[test]
+    fn entry_lives_while_a_hold_exists() {
+        let root = TestTable::new();
+        let first = root.branch();
+        first.insert(ID, "value");
+        let second = first.branch();
+        let clone = second.clone();
+
+        drop(first);
+        assert_eq!(root.get(&ID), Some("value"));
+        // clone 与原表共同持有
+        drop(second);
+        assert_eq!(root.get(&ID), Some("value"));
+        drop(clone);
+        assert_eq!(root.get(&ID), None);
+        assert!(root.is_empty());
+    }
+
+    #[test]
+    fn retain_adds_a_hold() {
+        let root = TestTable::new();
+        let branch = root.branch();
+        branch.insert(ID, "value");
+        let other = root.branch();
+        assert!(other.retain(&ID));
+        assert!(!other.retain(&TestId(2)));
+
+        drop(branch);
+        assert_eq!(root.get(&ID), Some("value"));
+        drop(other);
+        assert_eq!(root.get(&ID), None);
+    }
+
+    #[test]
+    fn remove_and_take_release_immediately() {
+        let table = TestTable::new();
+        let branch = table.branch();
+        table.insert(ID, "value");
+        table.insert(TestId(2), "other");
+        assert!(table.remove(&ID));
+        assert!(!table.remove(&ID));
+        assert_eq!(branch.take(&TestId(2)), Some("other"));
+        assert_eq!(branch.take(&TestId(2)), None);
+        assert!(table.is_empty());
+    }
+
+    #[test]
+    fn reused_id_is_not_released_by_old_holds() {
+        let root = TestTable::new();
+        let first = root.branch();
+        first.insert(ID, "old");
+        let second = first.branch();
+        assert!(first.remove(&ID));
+        first.insert(ID, "new");
+
+        // second 持有的是旧的表项，释放时不影响同 id 的新表项
+        drop(second);
+        assert_eq!(root.get(&ID), Some("new"));
+        drop(first);
+        assert_eq!(root.get(&ID), None);
+    }
+
+    #[test]
+    fn take_if_keeps_entry_when_check_fails() {
+        let root = TestTable::new();
+        let branch = root.branch();
+        branch.insert_named(ID, "value", "str");
+
+        let taken = branch.take_if(&ID, |_, type_name| match type_name {
+            "str" => Err("rejected"),
+            _ => Ok(()),
+        });
+        assert_eq!(taken, Some(Err("rejected")));
+        // 持有关系不变，分支结束时照常释放
+        drop(branch);
+        assert_eq!(root.get(&ID), None);
+
+        root.insert(ID, "value");
+        let taken = root.take_if(&ID, |value, _| match *value {
+            "value" => Ok(()),
+            _ => Err("unexpected"),
+        });
+        assert_eq!(taken, Some(Ok("value")));
+        assert_eq!(root.take_if(&ID, |_, _| Err("unexpected")), None);
+    }
+
+    #[tokio::test]
+    async fn ttl_expires_held_entries() {
+        let table = TestTable::new();
+        let counters = Arc::new(TableCounters::default());
+        table.attach(&counters);
+        table
+            .insert_with_ttl(ID, "value", Duration::from_millis(20))
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(table.get(&ID), None);
+        assert_eq!(counters.stats().expired, 1);
+    }
+
+    #[tokio::test]
+    async fn ttl_does_not_expire_reused_id() {
+        let table = TestTable::new();
+        table
+            .insert_with_ttl(TestId(2), "old", Duration::from_millis(20))
+            .unwrap();
+        assert!(table.remove(&TestId(2)));
+        table.insert(TestId(2), "new");
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(table.get(&TestId(2)), Some("new"));
+    }
+
+    #[test]
+    fn ttl_requires_runtime() {
+        let table = TestTable::new();
+        let res = table.insert_with_ttl(TestId(3), "value", Duration::from_millis(20));
+        assert!(matches!(res, Err(NodeError::Unsupported(_))));
+        assert_eq!(table.get(&TestId(3)), None);
+    }
+
+    #[test]
+    fn attach_counts_existing_and_later_entries() {
+        let table = TestTable::new();
+        table.insert(ID, "before");
+        table.insert(TestId(2), "removed");
+        assert!(table.remove(&TestId(2)));
+
+        let counters = Arc::new(TableCounters::default());
+        let branch = table.branch();
+        branch.attach(&counters);
+        // 已计入的表不再计入其他计数器
+        let other = Arc::new(TableCounters::default());
+        table.attach(&other);
+        table.insert(TestId(3), "after");
+        drop(table);
+        drop(branch);
+
+        let stats = counters.stats();
+        assert_eq!((stats.live, stats.inserted, stats.released), (0, 3, 3));
+        assert_eq!(other.stats(), TableStats::default());
+    }
+}
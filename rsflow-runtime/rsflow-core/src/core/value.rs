@@ -1,7 +1,7 @@
 use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
 use std::collections::HashMap;
-use uuid::Uuid;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Value {
@@ -17,6 +17,36 @@ pub enum Value {
     Object(HashMap<String, Value>),
 }
 
+impl Value {
+    /// 将 Value 反序列化为具体类型，用于读取结构化配置
+    pub fn deserialize_into<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::to_value(self).and_then(serde_json::from_value)
+    }
+
+    /// 字节数据表示为 0-255 的整数数组
+    pub fn from_bytes(bytes: Vec<u8>) -> Value {
+        Value::Array(bytes.into_iter().map(|b| Value::Int(b as i32)).collect())
+    }
+
+    /// 转为字节：字符串按 UTF-8，0-255 的整数数组按字节，其余序列化为 JSON
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Value::String(s) => s.as_bytes().to_vec(),
+            Value::Array(items) => {
+                let bytes: Option<Vec<u8>> = items
+                    .iter()
+                    .map(|item| match item {
+                        Value::Int(b) => u8::try_from(*b).ok(),
+                        _ => None,
+                    })
+                    .collect();
+                bytes.unwrap_or_else(|| serde_json::to_vec(self).unwrap_or_default())
+            }
+            other => serde_json::to_vec(other).unwrap_or_default(),
+        }
+    }
+}
+
 impl Serialize for Value {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -1,14 +1,26 @@
+use crate::core::NodeDescriptor;
 use crate::core::node::NodeBuilder;
+use crate::engine::handle::EngineHandle;
 use crate::engine::plugin::{EnginePlugin};
+use crate::engine::schema::check_node_configs;
+use crate::flow::{
+    Diagnostic, FlowMod, lint_flow, parse_flow_file, parse_flow_str, validate_flow,
+    validate_node_types,
+};
 use std::sync::Arc;
 use std::collections::HashMap;
+use std::io::Error as IoError;
 
 pub type NodeBuilderMap = HashMap<String, Box<dyn NodeBuilder>>;
 pub type PluginMap = HashMap<String, Arc<dyn EnginePlugin + Send + Sync>>;
+/// 尚未 configure 的插件
+pub type PluginBuilderMap = HashMap<String, Box<dyn EnginePlugin + Send + Sync>>;
 
 pub struct EngineBuilder {
     nodes: NodeBuilderMap,
-    plugins: PluginMap,
+    plugins: PluginBuilderMap,
+    /// 最后合并，覆盖注册节点和插件节点
+    overrides: NodeBuilderMap,
 }
 
 impl EngineBuilder {
@@ -16,6 +28,7 @@ impl EngineBuilder {
         Self {
             nodes: HashMap::new(),
             plugins: HashMap::new(),
+            overrides: HashMap::new(),
         }
     }
 
@@ -35,20 +48,157 @@ impl EngineBuilder {
         B: EnginePlugin + 'static,
     {
         self.plugins
-            .insert(builder.name().to_string(), Arc::new(builder));
+            .insert(builder.name().to_string(), Box::new(builder));
         self
     }
 
+    /// 替换一个节点类型，包括插件提供的节点，用于测试中 mock 节点
+    pub fn override_node<B>(mut self, builder: B) -> Self
+    where
+        B: NodeBuilder + 'static,
+    {
+        self.overrides
+            .insert(builder.node_type().to_string(), Box::new(builder));
+        self
+    }
+
+    /// 已注册节点类型的说明（含插件提供的节点），无需构建 Engine
+    pub fn node_descriptors(&self) -> Vec<NodeDescriptor> {
+        // 与构建时一致，插件节点覆盖同名的注册节点，替换的节点覆盖两者
+        let mut descriptors: HashMap<String, NodeDescriptor> = self
+            .nodes
+            .iter()
+            .map(|(t, b)| (t.clone(), b.descriptor()))
+            .collect();
+        for plugin in self.plugins.values() {
+            for (t, b) in plugin.internal_nodes() {
+                descriptors.insert(t, b.descriptor());
+            }
+        }
+        for (t, b) in &self.overrides {
+            descriptors.insert(t.clone(), b.descriptor());
+        }
+        let mut descriptors: Vec<NodeDescriptor> = descriptors.into_values().collect();
+        descriptors.sort_by(|a, b| a.node_type.cmp(&b.node_type));
+        descriptors
+    }
+
     /// 构建 Engine
     pub async fn build(
         self,
         flow_file_path: &str,
-    ) -> std::result::Result<std::sync::Arc<crate::engine::engine::Engine>, std::io::Error> {
-        crate::engine::engine::Engine::create_with_builders(
+    ) -> std::result::Result<EngineHandle, std::io::Error> {
+        let flow_mod = parse_flow_file(flow_file_path)?;
+        self.create(flow_mod, Some(flow_file_path.to_string()))
+            .await
+    }
+
+    /// 由 FlowMod 构建 Engine（如 FlowModBuilder 的结果），不关联流程文件
+    ///
+    /// 这样构建的引擎不能 reload / watch，deploy 和 update_mod 不写回文件。
+    pub async fn build_from_mod(self, flow_mod: FlowMod) -> Result<EngineHandle, IoError> {
+        self.create(flow_mod, None).await
+    }
+
+    /// 由 flow.json 格式的字符串构建 Engine，同 build_from_mod
+    pub async fn build_from_str(self, json: &str) -> Result<EngineHandle, IoError> {
+        self.build_from_mod(parse_flow_str(json)?).await
+    }
+
+    async fn create(
+        self,
+        flow_mod: FlowMod,
+        flow_file_path: Option<String>,
+    ) -> Result<EngineHandle, IoError> {
+        let engine = crate::engine::engine::Engine::create_with_builders(
+            flow_mod,
             flow_file_path,
             self.nodes,
             self.plugins,
+            self.overrides,
         )
-        .await
+        .await?;
+        Ok(EngineHandle::new(engine))
+    }
+
+    /// 检查 flow 文件而不创建节点（rsflow validate）：结构、插件配置、节点类型和节点配置
+    ///
+    /// 只有文件无法读取或解析时返回 Err，其余问题都作为诊断返回。
+    pub async fn validate(self, flow_file_path: &str) -> Result<Vec<Diagnostic>, IoError> {
+        let flow_mod = parse_flow_file(flow_file_path)?;
+        let mut diagnostics = validate_flow(&flow_mod);
+        let (builders, plugin_diagnostics) = self.resolve(&flow_mod).await;
+        diagnostics.extend(plugin_diagnostics);
+        diagnostics.extend(validate_node_types(&flow_mod, &descriptors(&builders)));
+        diagnostics.extend(
+            check_node_configs(&flow_mod, &builders)
+                .into_iter()
+                .map(Diagnostic::from),
+        );
+        Ok(diagnostics)
+    }
+
+    /// 风格检查（rsflow lint）：通用检查加上各节点类型的 NodeBuilder::lint，结果均为 warning
+    pub async fn lint(self, flow_file_path: &str) -> Result<Vec<Diagnostic>, IoError> {
+        let flow_mod = parse_flow_file(flow_file_path)?;
+        let (builders, _) = self.resolve(&flow_mod).await;
+        let mut diagnostics = lint_flow(&flow_mod, &descriptors(&builders));
+        for flow in flow_mod.enabled_flows() {
+            for node in &flow.nodes {
+                if let Some(builder) = builders.get(&node.node_type) {
+                    diagnostics.extend(builder.lint(flow, node));
+                }
+            }
+        }
+
+        // 按 flow、节点的声明顺序输出
+        let order: HashMap<_, usize> = flow_mod
+            .enabled_flows()
+            .flat_map(|flow| flow.nodes.iter())
+            .enumerate()
+            .map(|(i, node)| (node.id, i))
+            .collect();
+        diagnostics.sort_by_key(|d| d.node_id.and_then(|id| order.get(&id).copied()));
+        Ok(diagnostics)
     }
+
+    // 逐个配置插件并合并节点构建器（与构建时一致，插件节点覆盖同名的注册节点，替换的节点覆盖两者），
+    // 配置失败的插件仍使用其节点，问题作为诊断返回
+    async fn resolve(self, flow_mod: &FlowMod) -> (NodeBuilderMap, Vec<Diagnostic>) {
+        let mut diagnostics = Vec::new();
+        let mut names: Vec<&String> = flow_mod.plugin_config.names().collect();
+        names.sort();
+        for name in names {
+            if !self.plugins.contains_key(name) {
+                diagnostics.push(Diagnostic::warning(
+                    "unknown-plugin",
+                    None,
+                    format!("plugin_config.{} has no registered plugin, ignored", name),
+                ));
+            }
+        }
+
+        let mut plugins: Vec<_> = self.plugins.into_iter().collect();
+        plugins.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut builders = self.nodes;
+        for (name, mut plugin) in plugins {
+            if let Err(err) = plugin.configure(&flow_mod.plugin_config.get(&name)).await {
+                diagnostics.push(Diagnostic::error(
+                    "invalid-plugin-config",
+                    None,
+                    format!("invalid plugin_config.{}: {:?}", name, err),
+                ));
+            }
+            builders.extend(plugin.internal_nodes());
+        }
+        builders.extend(self.overrides);
+        (builders, diagnostics)
+    }
+}
+
+fn descriptors(builders: &NodeBuilderMap) -> HashMap<String, NodeDescriptor> {
+    builders
+        .iter()
+        .map(|(t, builder)| (t.clone(), builder.descriptor()))
+        .collect()
 }
@@ -1,44 +1,104 @@
 use crate::core::{
-    EngineContext, EngineMessage, FlowContext, Node, NodeInput, NodeOutput, NodeRunItem, Payload,
+    DebugTap, EngineContext, EngineMessage, EngineMetrics, FlowContext, FlowOutput, Node,
+    NodeDescriptor, NodeError, NodeFailure, NodeInfo, NodeInput, NodeOutput, NodeRunItem, Payload,
+    TraceEvent, error_value,
 };
-use crate::engine::flow_processor::FlowProcessor;
-use crate::engine::{NodeBuilderMap, PluginMap};
-use crate::flow::FlowMod;
+use crate::core::retry::RetryCounters;
+use crate::engine::flow_processor::{CatchRoutes, FactoryMap, FlowIndex, FlowProcessor};
+use crate::engine::reload::{ReloadSummary, diff_flow_mod};
+use crate::engine::status::{EngineState, EngineStatus, FlowSummary};
+use crate::engine::{NodeBuilderMap, PluginBuilderMap, PluginMap};
+use crate::flow::{FlowFormat, FlowMod};
 
-use std::collections::{HashMap, VecDeque};
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::{Error as IoError, ErrorKind};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::{Duration, Instant};
 
 use tokio::sync::{Mutex, mpsc};
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
+use tracing::{Instrument, debug, error, info, info_span, warn};
 use uuid::Uuid;
 
 type NodeMap = HashMap<Uuid, Arc<dyn Node + Send + Sync>>;
 type Nodes = Arc<NodeMap>;
 type Plugins = Arc<PluginMap>;
 
+// 同一分支的错误最多转交处理的次数（与 Node-RED 相同），防止 catch 下游出错形成循环
+const MAX_CATCH_DEPTH: usize = 10;
+
 pub struct Engine {
-    flow_mod: Arc<FlowMod>,
-    //节点实例列表
-    nodes: Nodes,
+    //由 FlowMod 直接构建时为 None，不能 reload / watch，部署时不写回
+    flow_file_path: Option<String>,
+    //与 EngineContext 共享，热重载后插件和节点读到新的 flow_mod
+    flow_mod: Arc<RwLock<Arc<FlowMod>>>,
+    //catch 路由、节点所属 flow 等索引，随 flow_mod 一起替换
+    index: RwLock<Arc<FlowIndex>>,
+    //节点实例列表（热重载时整体替换，运行中的 flow 持有旧快照）
+    nodes: RwLock<Nodes>,
+    //节点构建器与已注册的工厂，热重载时复用
+    node_builders: NodeBuilderMap,
+    node_factories: Mutex<FactoryMap>,
     //插件
     plugins: Plugins,
     //节点调度器
     receiver: Mutex<mpsc::Receiver<EngineMessage>>,
     sender: mpsc::Sender<EngineMessage>,
+    //停止信号
+    shutdown: CancellationToken,
+    //运行中的 flow / 事件任务
+    tracker: TaskTracker,
+    started: AtomicBool,
+    //以 start_isolated 启动：插件和节点的 engine_start / engine_stop 均不调用
+    isolated: AtomicBool,
+    started_at: OnceLock<Instant>,
+    //串行化热重载
+    reload_lock: Mutex<()>,
+    //节点执行记录
+    debug: Arc<DebugTap>,
+    //运行和节点执行统计，热重载时移除已删除的节点
+    metrics: Arc<EngineMetrics>,
 }
 
 impl Engine {
-    pub fn get_mod(&self) -> &FlowMod {
-        &self.flow_mod
+    pub fn get_mod(&self) -> Arc<FlowMod> {
+        Arc::clone(&self.flow_mod.read().unwrap())
+    }
+
+    pub fn flow_file_path(&self) -> Option<&str> {
+        self.flow_file_path.as_deref()
+    }
+
+    fn nodes(&self) -> Nodes {
+        Arc::clone(&self.nodes.read().unwrap())
+    }
+
+    fn index(&self) -> Arc<FlowIndex> {
+        Arc::clone(&self.index.read().unwrap())
+    }
+
+    // 替换 flow_mod 及由它计算的索引
+    fn set_mod(&self, flow_mod: FlowMod, index: FlowIndex) {
+        *self.index.write().unwrap() = Arc::new(index);
+        *self.flow_mod.write().unwrap() = Arc::new(flow_mod);
     }
 
     /// ⚠️ 只能通过 Builder 调用
     pub async fn create_with_builders(
-        flow_file_path: &str,
+        flow_mod: FlowMod,
+        flow_file_path: Option<String>,
         node_builders: NodeBuilderMap, //节点构建器
-        plugins: PluginMap,            //插件
+        plugins: PluginBuilderMap,     //插件
+        overrides: NodeBuilderMap,     //替换的节点类型（测试 mock）
     ) -> Result<Arc<Self>, std::io::Error> {
-        // 解析流程文件并验证
-        let flow_mod = FlowProcessor::parse_flow_file(flow_file_path)?;
+        // 验证 flow 配置
+        FlowProcessor::validate(&flow_mod)?;
+
+        // 配置插件
+        let plugins = FlowProcessor::configure_plugins(plugins, &flow_mod.plugin_config).await?;
 
         // 提取节点和节点类型
         let rsflow_nodes = FlowProcessor::extract_nodes(&flow_mod);
@@ -50,10 +110,16 @@ impl Engine {
         for v in plugins.values() {
             all_node_builders.extend(v.internal_nodes());
         }
+        all_node_builders.extend(overrides);
+
+        // 创建任何节点之前校验节点类型和所有节点配置
+        FlowProcessor::validate_node_types(&flow_mod, &all_node_builders)?;
+        FlowProcessor::validate_configs(&flow_mod, &all_node_builders)?;
+        let index = FlowProcessor::index(&flow_mod)?;
 
         // 将构建器转换为工厂
         let node_factories = FlowProcessor::builders_to_factories(
-            all_node_builders,
+            &all_node_builders,
             &node_types,
             &flow_mod.node_global_config,
         )
@@ -68,104 +134,508 @@ impl Engine {
         .await?;
 
         let (tx, rx) = mpsc::channel(flow_mod.config.msg_len);
+        let debug = Arc::new(DebugTap::new(flow_mod.config.debug.clone()));
 
         Ok(Arc::new(Self {
-            flow_mod: Arc::new(flow_mod),
-            nodes: Arc::new(nodes),
+            flow_file_path,
+            index: RwLock::new(Arc::new(index)),
+            flow_mod: Arc::new(RwLock::new(Arc::new(flow_mod))),
+            nodes: RwLock::new(Arc::new(nodes)),
+            node_builders: all_node_builders,
+            node_factories: Mutex::new(node_factories),
             plugins: Arc::new(plugins),
             receiver: Mutex::new(rx),
             sender: tx,
+            shutdown: CancellationToken::new(),
+            tracker: TaskTracker::new(),
+            started: AtomicBool::new(false),
+            isolated: AtomicBool::new(false),
+            started_at: OnceLock::new(),
+            reload_lock: Mutex::new(()),
+            debug,
+            metrics: Arc::new(EngineMetrics::new()),
         }))
     }
 
+    /// 重新加载流程文件，只重建新增、删除或变更的节点
+    ///
+    /// 未变更的节点实例保持不变，运行中的 flow 继续使用旧的节点快照。
+    /// 任一节点创建失败时不做任何替换。
+    pub async fn reload(&self) -> Result<ReloadSummary, IoError> {
+        let _guard = self.reload_lock.lock().await;
+
+        let Some(flow_file_path) = &self.flow_file_path else {
+            return Err(IoError::new(
+                ErrorKind::Unsupported,
+                "engine was not built from a flow file, use deploy instead",
+            ));
+        };
+        let new_mod = FlowProcessor::parse_flow_file(flow_file_path)?;
+        self.apply_mod(new_mod).await
+    }
+
+    /// 校验并部署新的 FlowMod，成功后写回流程文件（如有）
+    pub async fn deploy(&self, flow_mod: FlowMod) -> Result<ReloadSummary, IoError> {
+        self.update_mod(|current| {
+            *current = flow_mod;
+            Ok(())
+        })
+        .await
+    }
+
+    /// 修改当前 FlowMod 后校验、部署并写回流程文件（如有）
+    ///
+    /// 修改到写回期间不会有其他重载或部署，修改返回错误或部署失败时不做任何替换。
+    pub async fn update_mod<F>(&self, update: F) -> Result<ReloadSummary, IoError>
+    where
+        F: FnOnce(&mut FlowMod) -> Result<(), IoError>,
+    {
+        let _guard = self.reload_lock.lock().await;
+
+        let mut new_mod = (*self.get_mod()).clone();
+        update(&mut new_mod)?;
+        FlowProcessor::validate(&new_mod)?;
+        // 按流程文件的格式写回，原文件为简写时仍写成简写（id 会换成展开后的 UUID）
+        let content = match &self.flow_file_path {
+            Some(path) => {
+                let format = FlowFormat::from_path(Path::new(path));
+                let compact =
+                    std::fs::read_to_string(path).is_ok_and(|current| format.is_compact(&current));
+                Some((path, format.serialize(&new_mod, compact)?))
+            }
+            None => None,
+        };
+
+        let summary = self.apply_mod(new_mod).await?;
+        if let Some((path, content)) = content {
+            std::fs::write(path, content)?;
+        }
+        Ok(summary)
+    }
+
+    // 替换为新的 FlowMod，调用方需持有 reload_lock
+    async fn apply_mod(&self, new_mod: FlowMod) -> Result<ReloadSummary, IoError> {
+        let old_mod = self.get_mod();
+
+        if new_mod.config.msg_len != old_mod.config.msg_len {
+            warn!("config.msg_len changed, restart required to take effect");
+        }
+        if new_mod.config.debug != old_mod.config.debug {
+            warn!("config.debug changed, restart required to take effect");
+        }
+        if new_mod.plugin_config != old_mod.plugin_config {
+            warn!("plugin_config changed, restart required to take effect");
+        }
+
+        FlowProcessor::validate_node_types(&new_mod, &self.node_builders)?;
+        FlowProcessor::validate_configs(&new_mod, &self.node_builders)?;
+        let index = FlowProcessor::index(&new_mod)?;
+
+        let summary = diff_flow_mod(&old_mod, &new_mod);
+        if summary.is_empty() {
+            self.set_mod(new_mod, index);
+            return Ok(summary);
+        }
+
+        let new_nodes: HashMap<Uuid, _> = FlowProcessor::extract_nodes(&new_mod)
+            .into_iter()
+            .map(|node| (node.id, node))
+            .collect();
+        let rebuild_nodes: Vec<_> = summary
+            .added
+            .iter()
+            .chain(summary.changed.iter())
+            .filter_map(|id| new_nodes.get(id).cloned())
+            .collect();
+
+        // 新增类型或全局配置变更的类型需要重新注册工厂
+        let mut factories = self.node_factories.lock().await;
+        let register_types: HashSet<String> = FlowProcessor::extract_node_types(&rebuild_nodes)
+            .into_iter()
+            .filter(|t| !factories.contains_key(t) || summary.global_config_changed.contains(t))
+            .collect();
+        let new_factories = FlowProcessor::builders_to_factories(
+            &self.node_builders,
+            &register_types,
+            &new_mod.node_global_config,
+        )
+        .await?;
+
+        // 新注册类型的节点用新工厂创建，其余沿用已有工厂；全部成功后再写回
+        let (fresh_nodes, reused_nodes): (Vec<_>, Vec<_>) = rebuild_nodes
+            .into_iter()
+            .partition(|node| register_types.contains(&node.node_type));
+        let mut created = FlowProcessor::create_nodes_from_flow(
+            fresh_nodes,
+            &new_factories,
+            &new_mod.node_global_config,
+        )
+        .await?;
+        created.extend(
+            FlowProcessor::create_nodes_from_flow(
+                reused_nodes,
+                &factories,
+                &new_mod.node_global_config,
+            )
+            .await?,
+        );
+        factories.extend(new_factories);
+        drop(factories);
+
+        // 组装新的节点表：未变更的沿用旧实例
+        let old_nodes = self.nodes();
+        let mut nodes: NodeMap = HashMap::new();
+        for id in new_nodes.keys() {
+            if let Some(node) = created.get(id).or_else(|| old_nodes.get(id)) {
+                nodes.insert(*id, Arc::clone(node));
+            } else {
+                return Err(IoError::other(format!("Node {} missing after reload", id)));
+            }
+        }
+
+        self.set_mod(new_mod, index);
+        self.metrics.nodes.retain(|id| nodes.contains_key(id));
+        *self.nodes.write().unwrap() = Arc::new(nodes);
+
+        // 停止被替换的旧实例，启动新实例
+        let isolated = self.isolated.load(Ordering::SeqCst);
+        for id in summary.removed.iter().chain(summary.changed.iter()) {
+            if let Some(node) = old_nodes.get(id).filter(|_| !isolated) {
+                log_node(node.as_ref(), "Stopping node");
+                node.engine_stop().await;
+            }
+        }
+        if self.started.load(Ordering::SeqCst) && !isolated && !self.is_stopping() {
+            for node in created.values() {
+                log_node(node.as_ref(), "Starting node");
+                node.engine_start(self.engine_context()).await;
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// 已注册的节点类型（含插件提供的节点）
+    pub fn node_types(&self) -> Vec<String> {
+        let mut types: Vec<String> = self.node_builders.keys().cloned().collect();
+        types.sort();
+        types
+    }
+
+    /// 已注册节点类型的说明，按类型名排序
+    pub fn node_descriptors(&self) -> Vec<NodeDescriptor> {
+        let mut descriptors: Vec<NodeDescriptor> = self
+            .node_builders
+            .values()
+            .map(|b| b.descriptor())
+            .collect();
+        descriptors.sort_by(|a, b| a.node_type.cmp(&b.node_type));
+        descriptors
+    }
+
+    /// 引擎当前状态
+    pub fn status(&self) -> EngineStatus {
+        let state = if self.is_stopping() {
+            EngineState::Stopping
+        } else if self.started.load(Ordering::SeqCst) {
+            EngineState::Running
+        } else {
+            EngineState::Created
+        };
+        let flow_mod = self.get_mod();
+        EngineStatus {
+            state,
+            flow_file: self.flow_file_path.clone(),
+            uptime_secs: self
+                .started_at
+                .get()
+                .map(|at| at.elapsed().as_secs())
+                .unwrap_or_default(),
+            flows: flow_mod
+                .flow
+                .iter()
+                .map(|flow| FlowSummary {
+                    id: flow.id,
+                    name: flow.name.clone(),
+                    disabled: flow.disabled,
+                    nodes: flow.nodes.len(),
+                })
+                .collect(),
+            nodes: self.nodes().len(),
+            queue: self.engine_context().queue_stats(),
+            runs: self.metrics.flow_stats(),
+        }
+    }
+
+    /// 等待停止信号
+    pub async fn stopped(&self) {
+        self.shutdown.cancelled().await
+    }
+
+    /// 请求停止 Engine
+    pub fn shutdown(&self) {
+        self.shutdown.cancel();
+    }
+
+    pub fn is_stopping(&self) -> bool {
+        self.shutdown.is_cancelled()
+    }
+
+    /// 引擎上下文，供插件以外的组件（如管理接口）启动 flow 或读取指标
+    pub fn engine_context(&self) -> EngineContext {
+        EngineContext {
+            flow_mod: Arc::clone(&self.flow_mod),
+            sender: self.sender.clone(),
+            shutdown: self.shutdown.clone(),
+            debug: Arc::clone(&self.debug),
+            metrics: Arc::clone(&self.metrics),
+        }
+    }
+
     /// 启动 Engine（生命周期锚点）
+    #[tracing::instrument(name = "engine", skip_all, fields(flow_file = self.flow_file_path.as_deref()))]
     pub async fn start(self: Arc<Self>) {
-        println!("Starting engine...");
+        self.run(false).await
+    }
+
+    /// 只运行消息循环，不启动插件和节点：不监听端口、不启动定时器，
+    /// flow 只能通过 EngineContext::run_flow 启动（用于 rsflow test）
+    #[tracing::instrument(name = "engine", skip_all, fields(flow_file = self.flow_file_path.as_deref()))]
+    pub async fn start_isolated(self: Arc<Self>) {
+        self.run(true).await
+    }
+
+    async fn run(self: Arc<Self>, isolated: bool) {
+        info!("Starting engine");
+        self.isolated.store(isolated, Ordering::SeqCst);
 
         // 启动插件
-        for (name, plugin) in self.plugins.iter() {
-            let engine_ctx = EngineContext {
-                flow_mod: self.flow_mod.clone(),
-                sender: self.sender.clone(),
-            };
-            println!("Starting plugin: {}", name);
-            plugin.engine_start(engine_ctx).await;
+        if !isolated {
+            for (name, plugin) in self.plugins.iter() {
+                let engine_ctx = self.engine_context();
+                info!(plugin = %name, "Starting plugin");
+                plugin.engine_start(engine_ctx).await;
+            }
         }
 
         // 启动节点
-        for (node_id, node) in self.nodes.iter() {
-            let engine_ctx = EngineContext {
-                flow_mod: self.flow_mod.clone(),
-                sender: self.sender.clone(),
-            };
-            println!("Starting node: {} - {}", node_id, node.info().name);
-            node.engine_start(engine_ctx).await;
+        let _guard = self.reload_lock.lock().await;
+        self.started.store(true, Ordering::SeqCst);
+        let _ = self.started_at.set(Instant::now());
+        if !isolated {
+            for node in self.nodes().values() {
+                let engine_ctx = self.engine_context();
+                log_node(node.as_ref(), "Starting node");
+                node.engine_start(engine_ctx).await;
+            }
         }
 
-        println!("Engine started. Waiting for messages...");
+        drop(_guard);
+
+        info!("Engine started, waiting for messages");
 
         // 消息循环
         let mut rx = self.receiver.lock().await;
-        while let Some(msg) = rx.recv().await {
-            match msg {
-                EngineMessage::RunFlow { ctx, start_node } => {
-                    self.flow_run(ctx, start_node);
-                }
-                EngineMessage::NodeEvent {
-                    node_id,
-                    ctx,
-                    event_type,
-                    payload,
-                } => {
-                    self.node_event(node_id, event_type, ctx, payload);
-                }
-                EngineMessage::Stop => {
-                    println!("Engine stopping...");
+        loop {
+            tokio::select! {
+                _ = self.shutdown.cancelled() => break,
+                msg = rx.recv() => match msg {
+                    Some(EngineMessage::Stop) | None => break,
+                    Some(msg) => self.dispatch(msg),
+                },
+            }
+        }
+
+        info!("Engine stopping");
+        self.shutdown.cancel();
+        self.drain(&mut rx).await;
+
+        // 通知节点和插件停止
+        let _guard = self.reload_lock.lock().await;
+        if !isolated {
+            for node in self.nodes().values() {
+                log_node(node.as_ref(), "Stopping node");
+                node.engine_stop().await;
+            }
+            for (name, plugin) in self.plugins.iter() {
+                info!(plugin = %name, "Stopping plugin");
+                plugin.engine_stop().await;
+            }
+        }
+
+        info!("Engine stopped");
+    }
+
+    /// 分发引擎消息
+    fn dispatch(&self, msg: EngineMessage) {
+        match msg {
+            EngineMessage::RunFlow { ctx, start_node } => {
+                self.flow_run(ctx, start_node);
+            }
+            EngineMessage::NodeEvent {
+                node_id,
+                ctx,
+                event_type,
+                payload,
+            } => {
+                self.node_event(node_id, event_type, ctx, payload);
+            }
+            EngineMessage::Stop => {}
+        }
+    }
+
+    /// 等待运行中的 flow 完成，最长 shutdown_timeout 秒
+    ///
+    /// 分支通过 RunFlow 消息派发，父任务在发送完成后才会结束，
+    /// 因此任务全部结束且队列为空时即没有运行中的 flow。
+    async fn drain(&self, rx: &mut mpsc::Receiver<EngineMessage>) {
+        self.tracker.close();
+        let timeout = Duration::from_secs(self.get_mod().config.shutdown_timeout);
+        let deadline = tokio::time::sleep(timeout);
+        tokio::pin!(deadline);
+
+        loop {
+            tokio::select! {
+                _ = &mut deadline => {
+                    warn!(
+                        tasks = self.tracker.len(),
+                        "Shutdown deadline reached, running tasks abandoned"
+                    );
                     break;
                 }
+                Some(msg) = rx.recv() => self.dispatch(msg),
+                _ = self.tracker.wait() => {
+                    if rx.is_empty() {
+                        break;
+                    }
+                }
             }
         }
-
-        println!("Engine stopped.");
     }
 
     /// 节点消息事件
     fn node_event(&self, node_id: Uuid, event_type: String, ctx: FlowContext, payload: Payload) {
-        let nodes: Nodes = Arc::clone(&self.nodes);
+        let nodes: Nodes = self.nodes();
+        let Some(node) = nodes.get(&node_id).cloned() else {
+            warn!(%node_id, event_type, "Node event target not found");
+            return;
+        };
+        let info = node.info();
+        let span = info_span!(
+            "node_event",
+            flow_id = %self.flow_id_of(node_id),
+            ctx_id = %ctx.id,
+            node_id = %node_id,
+            node_name = %info.name,
+            node_type = %info.node_type,
+            event_type = %event_type,
+        );
 
-        tokio::spawn(async move {
-            if let Some(node) = nodes.get(&node_id) {
+        self.tracker.spawn(
+            async move {
                 if let Err(err) = node.event(&event_type, payload, &ctx).await {
-                    eprintln!("Node event error: {:?}", err);
+                    error!(error = ?err, "Node event error");
                 }
             }
-        });
+            .instrument(span),
+        );
     }
 
     /// 核心调度逻辑
     fn flow_run(&self, mut ctx: FlowContext, start_node: NodeRunItem) {
-        let nodes: Nodes = Arc::clone(&self.nodes);
+        let nodes: Nodes = self.nodes();
+        let index = self.index();
         let sender = self.sender.clone();
+        let debug = Arc::clone(&self.debug);
+        let metrics = Arc::clone(&self.metrics);
+        let shutdown = self.shutdown.clone();
+        let span = info_span!("flow_run", ctx_id = %ctx.id, run_id = %ctx.handle().id());
 
-        tokio::spawn(async move {
+        self.tracker.spawn(async move {
+            ctx.attach_metrics(&metrics);
+            ctx.run.mark_started();
             let mut flow_run_nodes: VecDeque<NodeRunItem> = VecDeque::new();
             flow_run_nodes.push_back(start_node);
 
             while let Some(node_run_item) = flow_run_nodes.pop_front() {
+                // 运行已取消，剩余节点不再执行
+                if ctx.run.is_cancelled() {
+                    break;
+                }
                 let Some(node) = nodes.get(&node_run_item.node_id) else {
-                    eprintln!("Node {} not found", node_run_item.node_id);
+                    warn!(node_id = %node_run_item.node_id, "Node not found");
                     continue;
                 };
 
-                match node.input(node_run_item.node_input, &ctx).await {
+                // 出错时需要原始消息的节点才保留一份
+                let info = node.info();
+                let error_targets = error_targets(&info, &index.catch_routes);
+                let original = (!error_targets.is_empty()).then(|| node_run_item.node_input.msg.clone());
+
+                let flow_id = index.node_flows.get(&info.id).copied().unwrap_or_default();
+                let input_port = node_run_item.node_input.port;
+                let traced_input = debug
+                    .is_traced(flow_id, ctx.run.id())
+                    .then(|| debug.render_value(&node_run_item.node_input.msg.value));
+
+                let node_span = info_span!(
+                    "node_input",
+                    flow_id = %flow_id,
+                    ctx_id = %ctx.id,
+                    node_id = %info.id,
+                    node_name = %info.name,
+                    node_type = %info.node_type,
+                    port = input_port,
+                );
+                let started = std::time::Instant::now();
+                let result = async {
+                    tokio::select! {
+                        result = input_with_retry(node.as_ref(), &info, node_run_item.node_input, &ctx, &shutdown, &metrics.retries) => result,
+                        _ = ctx.run.cancelled() => Err(NodeError::Cancelled),
+                    }
+                }
+                .instrument(node_span.clone())
+                .await;
+                let elapsed = started.elapsed();
+                node_span.in_scope(|| debug!(elapsed = ?elapsed, ok = result.is_ok(), "Node input done"));
+                // 运行取消导致的中断不计入节点统计
+                if !(ctx.run.is_cancelled() && matches!(result, Err(NodeError::Cancelled))) {
+                    metrics.nodes.record_input(&info, elapsed, result.is_ok());
+                }
+                if let Some((input, truncated)) = traced_input {
+                    let (output_ports, error) = TraceEvent::outcome(&result);
+                    debug.send(TraceEvent {
+                        flow_id,
+                        ctx_id: ctx.id,
+                        run_id: ctx.run.id(),
+                        node_id: info.id,
+                        node_name: info.name.clone(),
+                        node_type: info.node_type.clone(),
+                        input_port,
+                        input,
+                        truncated,
+                        output_ports,
+                        duration_us: elapsed.as_micros() as u64,
+                        error,
+                        timestamp: chrono::Utc::now().timestamp_millis(),
+                    });
+                }
+                match result {
                     Ok(node_output) => {
                         ctx.run_node_ids.push(node_run_item.node_id);
                         match node_output {
                             NodeOutput::None => continue,
                             NodeOutput::One((port, msg)) => {
                                 // 获取执行node的输出节点定义
-                                let out_ids = node.info().output_ports;
-                                if let Some(out_node_ids) = out_ids.get(&port) {
+                                let out_ids = &info.output_ports;
+                                if !has_wires(out_ids, port) {
+                                    // 没有连线的端口作为最终输出
+                                    ctx.run.record_output(FlowOutput {
+                                        node_id: node_run_item.node_id,
+                                        port,
+                                        payload: msg,
+                                    });
+                                } else if let Some(out_node_ids) = out_ids.get(&port) {
                                     if out_node_ids.len() == 1 {
                                         // 单分支，继续当前线程执行
                                         let (out_node_id, out_node_port) = &out_node_ids[0];
@@ -192,6 +662,7 @@ impl Engine {
                                         }
 
                                         // 其余分支发送RunFlow消息
+                                        metrics.record_branches(&info, iter.len());
                                         for (out_node_id, out_node_port) in iter {
                                             let branch_ctx = ctx.new_branch();
 
@@ -210,10 +681,7 @@ impl Engine {
                                                 })
                                                 .await
                                             {
-                                                eprintln!(
-                                                    "Failed to send RunFlow message: {:?}",
-                                                    e
-                                                );
+                                                error!(error = ?e, "Failed to send RunFlow message");
                                             }
                                         }
                                     }
@@ -221,9 +689,15 @@ impl Engine {
                             }
                             NodeOutput::Many(msgs) => {
                                 // 获取执行node的输出节点定义
-                                let out_ids = node.info().output_ports;
+                                let out_ids = &info.output_ports;
                                 for (_, (port, msg)) in msgs.iter().enumerate() {
-                                    if let Some(out_node_ids) = out_ids.get(port) {
+                                    if !has_wires(out_ids, *port) {
+                                        ctx.run.record_output(FlowOutput {
+                                            node_id: node_run_item.node_id,
+                                            port: *port,
+                                            payload: msg.clone(),
+                                        });
+                                    } else if let Some(out_node_ids) = out_ids.get(port) {
                                         if out_node_ids.len() == 1 {
                                             // 单分支，继续当前线程执行
                                             let (out_node_id, out_node_port) = &out_node_ids[0];
@@ -250,6 +724,7 @@ impl Engine {
                                             }
 
                                             // 其余分支发送RunFlow消息
+                                            metrics.record_branches(&info, iter.len());
                                             for (out_node_id, out_node_port) in iter {
                                                 let branch_ctx = ctx.new_branch();
 
@@ -268,10 +743,7 @@ impl Engine {
                                                     })
                                                     .await
                                                 {
-                                                    eprintln!(
-                                                        "Failed to send RunFlow message: {:?}",
-                                                        e
-                                                    );
+                                                    error!(error = ?e, "Failed to send RunFlow message");
                                                 }
                                             }
                                         }
@@ -280,17 +752,335 @@ impl Engine {
                             }
                         }
                     }
+                    Err(NodeError::Cancelled) if ctx.run.is_cancelled() => break,
                     Err(err) => {
-                        eprintln!(
-                            "Node {} - {} error: {:?}",
-                            node_run_item.node_id,
-                            node.info().name,
-                            err
-                        );
+                        node_span.in_scope(|| warn!(error = ?err, "Node input error"));
+                        // 有错误端口或 catch 节点时转交处理，否则记为本次运行的错误；
+                        // 本分支转交次数已达上限时（catch 下游出错又回到 catch）不再转交
+                        let routed = original.filter(|_| ctx.catch_depth < MAX_CATCH_DEPTH);
+                        let Some(mut msg) = routed else {
+                            if !error_targets.is_empty() {
+                                node_span.in_scope(|| {
+                                    warn!(max_depth = MAX_CATCH_DEPTH, "Error routed too many times, flow failed")
+                                });
+                            }
+                            ctx.run.record_error(NodeFailure::new(
+                                node_run_item.node_id,
+                                info.name,
+                                &err,
+                            ));
+                            continue;
+                        };
+                        ctx.catch_depth += 1;
+                        msg.value = error_value(&info, &err, msg.value);
+                        let mut targets = error_targets.into_iter();
+                        if let Some((first_id, first_port)) = targets.next() {
+                            flow_run_nodes.push_back(NodeRunItem {
+                                node_id: first_id,
+                                node_input: NodeInput {
+                                    port: first_port,
+                                    msg: msg.clone(),
+                                },
+                            });
+                        }
+                        for (target_id, target_port) in targets {
+                            if let Err(e) = sender
+                                .send(EngineMessage::RunFlow {
+                                    ctx: ctx.new_branch(),
+                                    start_node: NodeRunItem {
+                                        node_id: target_id,
+                                        node_input: NodeInput {
+                                            port: target_port,
+                                            msg: msg.clone(),
+                                        },
+                                    },
+                                })
+                                .await
+                            {
+                                error!(error = ?e, "Failed to send RunFlow message");
+                            }
+                        }
                         continue;
                     }
                 }
             }
+        }.instrument(span));
+    }
+
+    // 节点所属的 flow id，未知时为 nil
+    fn flow_id_of(&self, node_id: Uuid) -> Uuid {
+        self.index()
+            .node_flows
+            .get(&node_id)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+// 节点生命周期日志
+fn log_node(node: &(dyn Node + Send + Sync), message: &str) {
+    let info = node.info();
+    info!(node_id = %info.id, node_name = %info.name, node_type = %info.node_type, "{}", message);
+}
+
+// 按节点的重试策略执行 Node::input，未配置时只执行一次；
+// 引擎停止时不再等待下一次重试，返回最后一次的错误
+async fn input_with_retry(
+    node: &(dyn Node + Send + Sync),
+    info: &NodeInfo,
+    input: NodeInput,
+    ctx: &FlowContext,
+    shutdown: &CancellationToken,
+    counters: &RetryCounters,
+) -> Result<NodeOutput, NodeError> {
+    let Some(policy) = &info.retry else {
+        return node.input(input, ctx).await;
+    };
+    let mut attempt = 1;
+    loop {
+        match node.input(input.clone(), ctx).await {
+            Ok(output) => {
+                if attempt > 1 {
+                    counters.record_recovered();
+                }
+                return Ok(output);
+            }
+            Err(err) if attempt < policy.max_attempts && policy.is_retryable(&err) => {
+                let delay = policy.delay_after(attempt);
+                warn!(
+                    attempt,
+                    max_attempts = policy.max_attempts,
+                    error = ?err,
+                    delay = ?delay,
+                    "Node input failed, retrying"
+                );
+                counters.record_attempt();
+                tokio::select! {
+                    _ = tokio::time::sleep(delay) => {}
+                    _ = shutdown.cancelled() => {
+                        warn!(attempt, "Engine stopping, retry abandoned");
+                        return Err(err);
+                    }
+                }
+                attempt += 1;
+            }
+            Err(err) => {
+                if attempt > 1 {
+                    counters.record_exhausted();
+                }
+                return Err(err);
+            }
+        }
+    }
+}
+
+// 节点出错时的去向：已连线的错误端口，否则为 catch 节点的 0 号端口
+fn error_targets(info: &NodeInfo, catch_routes: &CatchRoutes) -> Vec<(Uuid, u8)> {
+    if let Some(port) = info.error_port
+        && has_wires(&info.output_ports, port)
+    {
+        return info.output_ports[&port].clone();
+    }
+    catch_routes
+        .get(&info.id)
+        .map(|ids| ids.iter().map(|id| (*id, 0)).collect())
+        .unwrap_or_default()
+}
+
+// 端口是否连接了下游节点
+fn has_wires(out_ids: &crate::core::NodeOutputPorts, port: u8) -> bool {
+    out_ids.get(&port).is_some_and(|ids| !ids.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Backoff, FlowStats, FlowStatus, RetryPolicy, Value};
+    use crate::engine::testing::{
+        Probe, TestPlugin, engine_builder, start_at, start_engine, wait_until,
+    };
+    use crate::flow::{FlowBuilder, FlowModBuilder, NodeSpec};
+
+    #[tokio::test]
+    async fn stop_drains_running_flows_and_stops_nodes() {
+        let probe = Arc::new(Probe::default());
+        let plugin = Arc::new(Probe::default());
+        let mut flow = FlowBuilder::new("main");
+        let slow = flow.add(NodeSpec::new("slow"));
+        let pass = flow.add(NodeSpec::new("pass"));
+        flow.wire(slow, 0, pass, 0);
+        let flow_mod = FlowModBuilder::new().flow(flow.build()).build();
+        let builder = engine_builder(&probe).register_engine_plugin(TestPlugin {
+            probe: Arc::clone(&plugin),
         });
+        let (handle, task) = start_engine(builder, flow_mod).await;
+
+        let run = handle
+            .context()
+            .run_flow(start_at(slow.id(), Value::Int(1)))
+            .await;
+        // slow 节点执行中请求停止
+        wait_until(|| probe.inputs() == 1).await;
+        handle.shutdown();
+        task.await.unwrap();
+
+        // 运行中的 flow 执行完毕，下游节点也被执行
+        let result = run.wait().await;
+        assert_eq!(result.status, FlowStatus::Completed);
+        assert_eq!(result.outputs.len(), 1);
+        assert_eq!(result.outputs[0].node_id, pass.id());
+        assert_eq!(probe.inputs(), 2);
+        assert_eq!((probe.started(), probe.stopped()), (2, 2));
+        assert_eq!((plugin.started(), plugin.stopped()), (1, 1));
+
+        // 停止后不再接收新的 flow
+        let rejected = handle
+            .context()
+            .run_flow(start_at(slow.id(), Value::NULL))
+            .await;
+        assert_eq!(rejected.wait().await.status, FlowStatus::Rejected);
+    }
+
+    #[tokio::test]
+    async fn error_loop_through_catch_ends() {
+        let probe = Arc::new(Probe::default());
+        let mut flow = FlowBuilder::new("loop");
+        let fail = flow.add(NodeSpec::new("fail"));
+        let catch = flow.add(NodeSpec::new(crate::core::CATCH_NODE_TYPE));
+        flow.wire(catch, 0, fail, 0);
+        let flow_mod = FlowModBuilder::new().flow(flow.build()).build();
+        let (handle, _) = start_engine(engine_builder(&probe), flow_mod).await;
+
+        let result = handle
+            .context()
+            .run_flow(start_at(fail.id(), Value::Int(1)))
+            .await
+            .wait()
+            .await;
+        // fail 执行 1 + MAX_CATCH_DEPTH 次，catch 执行 MAX_CATCH_DEPTH 次
+        assert_eq!(result.status, FlowStatus::Failed);
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].node_id, fail.id());
+        assert_eq!(probe.inputs(), 2 * MAX_CATCH_DEPTH + 1);
+    }
+
+    #[tokio::test]
+    async fn stop_interrupts_retry_backoff() {
+        let probe = Arc::new(Probe::default());
+        let mut flow = FlowBuilder::new("retry");
+        let fail = flow.add(NodeSpec::new("fail").retry(RetryPolicy {
+            max_attempts: 3,
+            backoff: Backoff::Fixed,
+            delay: 60_000,
+            max_delay: 60_000,
+            multiplier: 1.0,
+            jitter: 0.0,
+            retry_on: vec!["Shell".to_string()],
+        }));
+        let flow_mod = FlowModBuilder::new().flow(flow.build()).build();
+        let (handle, task) = start_engine(engine_builder(&probe), flow_mod).await;
+
+        let run = handle
+            .context()
+            .run_flow(start_at(fail.id(), Value::Int(1)))
+            .await;
+        wait_until(|| probe.inputs() == 1).await;
+        // 等待重试期间停止，不等到 shutdown_timeout
+        let started = Instant::now();
+        handle.shutdown();
+        task.await.unwrap();
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        let result = run.wait().await;
+        assert_eq!(result.status, FlowStatus::Failed);
+        assert!(result.errors[0].message.contains("failed"));
+        assert_eq!(probe.inputs(), 1);
+        assert_eq!(handle.context().metrics.retry_stats().attempts, 1);
+    }
+
+    #[tokio::test]
+    async fn metrics_are_per_engine() {
+        let probe = Arc::new(Probe::default());
+        let mut flow = FlowBuilder::new("main");
+        let pass = flow.add(NodeSpec::new("pass"));
+        let fail = flow.add(NodeSpec::new("fail"));
+        let flow_mod = FlowModBuilder::new().flow(flow.build()).build();
+        let (first, _) = start_engine(engine_builder(&probe), flow_mod.clone()).await;
+        let (second, _) = start_engine(engine_builder(&probe), flow_mod).await;
+
+        let ctx = first.context();
+        ctx.run_flow(start_at(pass.id(), Value::Int(1)))
+            .await
+            .wait()
+            .await;
+        ctx.run_flow(start_at(fail.id(), Value::Int(2)))
+            .await
+            .wait()
+            .await;
+
+        let stats = ctx.metrics.flow_stats();
+        assert_eq!((stats.started, stats.completed, stats.failed), (2, 1, 1));
+        assert_eq!(ctx.metrics.table_metrics().resources.live, 0);
+        // 另一个引擎没有运行过 flow
+        assert_eq!(second.context().metrics.flow_stats(), FlowStats::default());
+        assert!(second.context().metrics.nodes.snapshot().is_empty());
+    }
+
+    #[tokio::test]
+    async fn update_mod_keeps_compact_flow_file_compact() {
+        let compact = r#"{
+            "config": { "msg_len": 10 },
+            "flow": [{
+                "id": "main",
+                "nodes": [
+                    { "id": "a", "node_type": "pass" },
+                    { "id": "b", "node_type": "pass" }
+                ],
+                "wires": ["a -> b"]
+            }]
+        }"#;
+        let probe = Arc::new(Probe::default());
+        let path = std::env::temp_dir().join(format!("rsflow-{}.json", Uuid::new_v4()));
+        std::fs::write(&path, compact).unwrap();
+        let handle = engine_builder(&probe)
+            .build(path.to_str().unwrap())
+            .await
+            .unwrap();
+        handle
+            .update_mod(|flow_mod| {
+                flow_mod.flow[0].nodes[1].name = "renamed".to_string();
+                Ok(())
+            })
+            .await
+            .unwrap();
+
+        // 写回的仍是简写，重新解析得到部署的 FlowMod
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(FlowFormat::Json.is_compact(&written));
+        assert!(written.contains("\"wires\""));
+        assert_eq!(
+            FlowFormat::Json.parse(&written).unwrap(),
+            *handle.flow_mod()
+        );
+
+        // 完整形式的文件写回完整形式
+        let full = FlowFormat::Json
+            .serialize(&handle.flow_mod(), false)
+            .unwrap();
+        std::fs::write(&path, full).unwrap();
+        handle
+            .update_mod(|flow_mod| {
+                flow_mod.flow[0].nodes[0].name = "first".to_string();
+                Ok(())
+            })
+            .await
+            .unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(!FlowFormat::Json.is_compact(&written));
+        assert_eq!(
+            FlowFormat::Json.parse(&written).unwrap(),
+            *handle.flow_mod()
+        );
     }
 }
@@ -1,13 +1,32 @@
-use crate::core::{NodeBuilder, NodeFactory, NodeInfo, NodeInputPorts, NodeOutputPorts,Value};
-use crate::flow::{FlowMod, FlowNode, parse_flow_all_nodes, parse_flow_file, validate_flow};
+use crate::core::{
+    CATCH_NODE_TYPE, NodeBuilder, NodeDescriptor, NodeError, NodeFactory, NodeInfo, NodeInputPorts,
+    NodeOutputPorts, Value,
+};
+use crate::engine::{PluginBuilderMap, PluginMap, check_node_configs};
+use crate::flow::{
+    Diagnostic, FlowMod, FlowNode, PluginConfig, parse_flow_all_nodes, parse_flow_file,
+    validate_flow, validate_node_types,
+};
 
 use std::collections::{HashMap, HashSet};
 use std::io::Error as IoError;
 use std::io::ErrorKind;
+use std::sync::Arc;
+use tracing::warn;
 use uuid::Uuid;
 
 // 类型别名
-type FactoryMap = HashMap<String, Box<dyn NodeFactory>>;
+pub type FactoryMap = HashMap<String, Box<dyn NodeFactory>>;
+/// 节点 id -> 接收其错误的 catch 节点
+pub type CatchRoutes = HashMap<Uuid, Vec<Uuid>>;
+
+/// 由 FlowMod 计算的运行时索引，随 FlowMod 一起替换
+#[derive(Default)]
+pub struct FlowIndex {
+    pub catch_routes: CatchRoutes,
+    /// 节点 id -> 所属 flow id
+    pub node_flows: HashMap<Uuid, Uuid>,
+}
 type BuilderMap = HashMap<String, Box<dyn NodeBuilder>>;
 
 /// 流程处理器，负责解析和处理流程配置，创建节点实例
@@ -20,21 +39,132 @@ impl FlowProcessor {
         let flow_mod = parse_flow_file(file_path)?;
 
         // 验证 flow 配置
-        if let Err(err) = validate_flow(&flow_mod) {
-            return Err(IoError::new(
-                ErrorKind::InvalidData,
-                format!("Flow validation failed: {}", err),
-            ));
-        }
+        Self::validate(&flow_mod)?;
 
         Ok(flow_mod)
     }
 
+    /// 验证 flow 配置，warning 只输出日志，有 error 时报告所有 error
+    pub fn validate(flow_mod: &FlowMod) -> Result<(), IoError> {
+        Self::report(validate_flow(flow_mod))
+    }
+
+    /// 按已注册的节点类型验证 flow：未注册的类型、端口、可达性和环路
+    pub fn validate_node_types(flow_mod: &FlowMod, builders: &BuilderMap) -> Result<(), IoError> {
+        let descriptors: HashMap<String, NodeDescriptor> = builders
+            .iter()
+            .map(|(t, builder)| (t.clone(), builder.descriptor()))
+            .collect();
+        Self::report(validate_node_types(flow_mod, &descriptors))
+    }
+
+    fn report(diagnostics: Vec<Diagnostic>) -> Result<(), IoError> {
+        let (errors, warnings): (Vec<_>, Vec<_>) =
+            diagnostics.into_iter().partition(|d| d.is_error());
+        for warning in &warnings {
+            warn!("{}", warning);
+        }
+        if errors.is_empty() {
+            return Ok(());
+        }
+        let details: Vec<String> = errors.iter().map(|e| format!("  {}", e)).collect();
+        Err(IoError::new(
+            ErrorKind::InvalidData,
+            format!("Flow validation failed:\n{}", details.join("\n")),
+        ))
+    }
+
+    /// 按节点类型声明的 Schema 校验节点配置和 node_global_config，报告所有错误
+    pub fn validate_configs(flow_mod: &FlowMod, builders: &BuilderMap) -> Result<(), IoError> {
+        let errors = check_node_configs(flow_mod, builders);
+        if errors.is_empty() {
+            return Ok(());
+        }
+        let details: Vec<String> = errors.iter().map(|e| format!("  {}", e)).collect();
+        Err(IoError::new(
+            ErrorKind::InvalidData,
+            format!("Invalid node config:\n{}", details.join("\n")),
+        ))
+    }
+
+    /// 将 plugin_config 中对应的子树交给各插件校验并保存
+    pub async fn configure_plugins(
+        plugins: PluginBuilderMap,
+        plugin_config: &PluginConfig,
+    ) -> Result<PluginMap, IoError> {
+        for name in plugin_config.names() {
+            if !plugins.contains_key(name) {
+                warn!("plugin_config.{} has no registered plugin, ignored", name);
+            }
+        }
+
+        let mut configured: PluginMap = HashMap::new();
+        for (name, mut plugin) in plugins {
+            if let Err(err) = plugin.configure(&plugin_config.get(&name)).await {
+                return Err(IoError::new(
+                    ErrorKind::InvalidData,
+                    format!("Invalid plugin_config.{}: {:?}", name, err),
+                ));
+            }
+            configured.insert(name, Arc::from(plugin));
+        }
+
+        Ok(configured)
+    }
+
     /// 从流程模型中提取所有节点
     pub fn extract_nodes(flow_mod: &FlowMod) -> Vec<FlowNode> {
         parse_flow_all_nodes(flow_mod.clone())
     }
 
+    /// 计算运行时索引，catch 节点的 scope 无效时返回错误
+    pub fn index(flow_mod: &FlowMod) -> Result<FlowIndex, IoError> {
+        Ok(FlowIndex {
+            catch_routes: Self::catch_routes(flow_mod)?,
+            node_flows: flow_mod
+                .enabled_flows()
+                .flat_map(|flow| flow.nodes.iter().map(|node| (node.id, flow.id)))
+                .collect(),
+        })
+    }
+
+    /// 计算每个节点的错误由哪些 catch 节点接收
+    ///
+    /// catch 节点只接收同一 flow 内的错误；配置 scope（节点 id 数组）时只接收这些节点的错误，
+    /// 否则接收整个 flow。catch 节点自身的错误不会再被 catch。
+    pub fn catch_routes(flow_mod: &FlowMod) -> Result<CatchRoutes, IoError> {
+        let mut routes = CatchRoutes::new();
+        for flow in flow_mod.enabled_flows() {
+            let mut catches: Vec<(Uuid, Option<HashSet<Uuid>>)> = Vec::new();
+            for node in flow.nodes.iter().filter(|n| n.node_type == CATCH_NODE_TYPE) {
+                let scope = catch_scope(&node.config).map_err(|err| {
+                    IoError::new(
+                        ErrorKind::InvalidData,
+                        format!("Invalid config of catch node {}: {:?}", node.id, err),
+                    )
+                })?;
+                catches.push((node.id, scope));
+            }
+            if catches.is_empty() {
+                continue;
+            }
+            for node in &flow.nodes {
+                if node.node_type == CATCH_NODE_TYPE {
+                    continue;
+                }
+                let targets: Vec<Uuid> = catches
+                    .iter()
+                    .filter(|(_, scope)| scope.as_ref().is_none_or(|ids| ids.contains(&node.id)))
+                    .map(|(id, _)| *id)
+                    .collect();
+                if !targets.is_empty() {
+                    routes.insert(node.id, targets);
+                }
+            }
+        }
+        Ok(routes)
+    }
+
     /// 从节点列表中提取所有节点类型
     pub fn extract_node_types(rsflow_nodes: &[FlowNode]) -> HashSet<String> {
         rsflow_nodes
@@ -45,7 +175,7 @@ impl FlowProcessor {
 
     /// 将节点构建器转换为节点工厂
     pub async fn builders_to_factories(
-        builders: BuilderMap,
+        builders: &BuilderMap,
         node_types: &HashSet<String>,
         node_global_config: &Value
     ) -> Result<FactoryMap, IoError> {
@@ -127,6 +257,8 @@ impl FlowProcessor {
                     input_ports: inputs,
                     output_ports: outputs,
                     global_config: global_config.unwrap_or(Value::NULL),
+                    error_port: flow_node.error_port,
+                    retry: flow_node.retry.clone(),
                 };
 
                 match factory.create(node_info).await {
@@ -146,3 +278,108 @@ impl FlowProcessor {
         Ok(nodes)
     }
 }
+
+// catch 节点配置的 scope，未配置或为空时接收整个 flow
+fn catch_scope(config: &Value) -> Result<Option<HashSet<Uuid>>, NodeError> {
+    let Value::Object(map) = config else {
+        return Ok(None);
+    };
+    let Some(Value::Array(ids)) = map.get("scope") else {
+        return Ok(None);
+    };
+    // 无效的条目直接报错，不能忽略后退化为接收整个 flow
+    let mut scope = HashSet::new();
+    for id in ids {
+        let parsed = match id {
+            Value::String(s) => Uuid::parse_str(s).ok(),
+            _ => None,
+        };
+        let Some(parsed) = parsed else {
+            return Err(NodeError::InvalidConfig(format!(
+                "catch scope entry {} is not a node id",
+                serde_json::to_string(id).unwrap_or_default()
+            )));
+        };
+        scope.insert(parsed);
+    }
+    Ok(if scope.is_empty() { None } else { Some(scope) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::testing::{Probe, TestPlugin, engine_builder};
+    use crate::flow::{FlowBuilder, FlowModBuilder, NodeSpec};
+
+    fn scope(ids: Vec<Value>) -> Value {
+        Value::Object(HashMap::from([("scope".to_string(), Value::Array(ids))]))
+    }
+
+    #[test]
+    fn catch_scope_parses_node_ids() {
+        let id = Uuid::new_v4();
+        let parsed = catch_scope(&scope(vec![Value::String(id.to_string())])).unwrap();
+        assert_eq!(parsed, Some(HashSet::from([id])));
+        // 未配置或空数组时接收整个 flow
+        assert_eq!(catch_scope(&Value::NULL).unwrap(), None);
+        assert_eq!(catch_scope(&scope(Vec::new())).unwrap(), None);
+    }
+
+    #[test]
+    fn catch_scope_rejects_invalid_entries() {
+        let id = Uuid::new_v4().to_string();
+        for entry in [Value::String("not-a-uuid".into()), Value::Int(1)] {
+            let ids = vec![Value::String(id.clone()), entry];
+            match catch_scope(&scope(ids)) {
+                Err(NodeError::InvalidConfig(msg)) => {
+                    assert!(msg.contains("not-a-uuid") || msg.contains(" 1 "), "{}", msg)
+                }
+                other => panic!("unexpected result: {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn catch_routes_reports_invalid_scope() {
+        let mut flow = FlowBuilder::new("main");
+        let log = flow.add(NodeSpec::new("log"));
+        flow.add(
+            NodeSpec::new(CATCH_NODE_TYPE).config(scope(vec![Value::String(log.id().to_string())])),
+        );
+        let flow_mod = FlowModBuilder::new().flow(flow.build()).build();
+        let routes = FlowProcessor::catch_routes(&flow_mod).unwrap();
+        assert_eq!(routes.get(&log.id()).map(Vec::len), Some(1));
+
+        let mut flow = FlowBuilder::new("main");
+        flow.add(NodeSpec::new("log"));
+        flow.add(NodeSpec::new(CATCH_NODE_TYPE).config(scope(vec![Value::String("log".into())])));
+        let flow_mod = FlowModBuilder::new().flow(flow.build()).build();
+        let err = FlowProcessor::catch_routes(&flow_mod).unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains("InvalidConfig") && msg.contains("log"), "{}", msg);
+    }
+
+    #[tokio::test]
+    async fn invalid_plugin_config_fails_build() {
+        let probe = Arc::new(Probe::default());
+        let builder = || {
+            engine_builder(&probe).register_engine_plugin(TestPlugin {
+                probe: Arc::clone(&probe),
+            })
+        };
+
+        let flow_mod = FlowModBuilder::new()
+            .plugin_config("test", Value::String("bad".into()))
+            .build();
+        let err = builder().build_from_mod(flow_mod).await.err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert!(err.to_string().contains("plugin_config.test"), "{}", err);
+        assert!(err.to_string().contains("must be an object"), "{}", err);
+
+        let flow_mod = FlowModBuilder::new()
+            .plugin_config("test", Value::Object(HashMap::new()))
+            .build();
+        assert!(builder().build_from_mod(flow_mod).await.is_ok());
+        assert_eq!(probe.started(), 0);
+    }
+}
@@ -0,0 +1,86 @@
+use crate::core::{EngineContext, NodeDescriptor};
+use crate::engine::engine::Engine;
+use crate::engine::reload::{ReloadSummary, watch_flow_file};
+use crate::engine::status::EngineStatus;
+use crate::flow::FlowMod;
+use std::sync::Arc;
+
+/// Engine 的外部控制句柄，可克隆后在信号处理等任务中请求停止
+#[derive(Clone)]
+pub struct EngineHandle {
+    engine: Arc<Engine>,
+}
+
+impl EngineHandle {
+    pub(crate) fn new(engine: Arc<Engine>) -> Self {
+        Self { engine }
+    }
+
+    pub fn engine(&self) -> &Arc<Engine> {
+        &self.engine
+    }
+
+    /// 启动 Engine，直到停止流程完成后返回
+    pub async fn start(&self) {
+        Arc::clone(&self.engine).start().await;
+    }
+
+    /// 只运行消息循环，不启动插件和节点（见 Engine::start_isolated），直到停止流程完成后返回
+    pub async fn start_isolated(&self) {
+        Arc::clone(&self.engine).start_isolated().await;
+    }
+
+    /// 请求停止：不再接收新的 flow，等待运行中的 flow 完成后退出
+    pub fn shutdown(&self) {
+        self.engine.shutdown();
+    }
+
+    /// 重新加载流程文件，只重建有变化的节点
+    pub async fn reload(&self) -> Result<ReloadSummary, std::io::Error> {
+        self.engine.reload().await
+    }
+
+    /// 校验并部署新的 FlowMod，成功后写回流程文件
+    pub async fn deploy(&self, flow_mod: FlowMod) -> Result<ReloadSummary, std::io::Error> {
+        self.engine.deploy(flow_mod).await
+    }
+
+    /// 修改当前 FlowMod 后校验、部署并写回流程文件
+    pub async fn update_mod<F>(&self, update: F) -> Result<ReloadSummary, std::io::Error>
+    where
+        F: FnOnce(&mut FlowMod) -> Result<(), std::io::Error>,
+    {
+        self.engine.update_mod(update).await
+    }
+
+    /// 当前的 FlowMod
+    pub fn flow_mod(&self) -> Arc<FlowMod> {
+        self.engine.get_mod()
+    }
+
+    pub fn status(&self) -> EngineStatus {
+        self.engine.status()
+    }
+
+    pub fn node_types(&self) -> Vec<String> {
+        self.engine.node_types()
+    }
+
+    pub fn node_descriptors(&self) -> Vec<NodeDescriptor> {
+        self.engine.node_descriptors()
+    }
+
+    pub fn context(&self) -> EngineContext {
+        self.engine.engine_context()
+    }
+
+    /// 监听流程文件变化并自动热重载，直到引擎停止
+    pub async fn watch(&self) -> notify::Result<()> {
+        watch_flow_file(Arc::clone(&self.engine)).await
+    }
+
+    /// 是否已请求停止
+    pub fn is_stopping(&self) -> bool {
+        self.engine.is_stopping()
+    }
+}
@@ -1,9 +1,19 @@
 pub mod builder;
 pub mod engine;
 pub mod flow_processor;
+pub mod handle;
 pub mod plugin;
+pub mod reload;
+pub mod schema;
+pub mod status;
+#[cfg(test)]
+pub(crate) mod testing;
 
-pub use builder::{EngineBuilder, NodeBuilderMap, PluginMap};
+pub use builder::{EngineBuilder, NodeBuilderMap, PluginBuilderMap, PluginMap};
 pub use engine::Engine;
-pub use flow_processor::FlowProcessor;
+pub use flow_processor::{CatchRoutes, FlowIndex, FlowProcessor};
+pub use handle::EngineHandle;
 pub use plugin::EnginePlugin;
+pub use reload::{ReloadSummary, diff_flow_mod, watch_flow_file};
+pub use schema::{ConfigError, check_node_configs};
+pub use status::{EngineState, EngineStatus, FlowSummary};
@@ -1,8 +1,15 @@
-use crate::core::EngineContext;
+use crate::core::{EngineContext, NodeError, Value};
 use crate::engine::NodeBuilderMap;
 #[async_trait::async_trait]
 pub trait EnginePlugin: Send + Sync {
     fn name(&self) -> &'static str;
+    /// 构建时调用，传入 plugin_config 中以 name() 为键的子树（未配置时为 NULL），
+    /// 在 internal_nodes 和 engine_start 之前执行，返回错误会使构建失败
+    async fn configure(&mut self, _config: &Value) -> Result<(), NodeError> {
+        Ok(())
+    }
     fn internal_nodes(&self) -> NodeBuilderMap;
     async fn engine_start(&self,serde: EngineContext);
+    /// 引擎停止时调用，在所有节点的 engine_stop 之后
+    async fn engine_stop(&self) {}
 }
@@ -0,0 +1,249 @@
+use crate::core::Value;
+use crate::engine::engine::Engine;
+use crate::flow::{FlowMod, FlowNode};
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use notify::{RecursiveMode, Watcher};
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tracing::{error, info};
+use uuid::Uuid;
+
+/// 一次热重载的差异结果
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct ReloadSummary {
+    pub added: Vec<Uuid>,
+    pub removed: Vec<Uuid>,
+    pub changed: Vec<Uuid>,
+    /// node_global_config 发生变化的节点类型
+    pub global_config_changed: HashSet<String>,
+}
+
+impl ReloadSummary {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+fn global_config_of<'a>(global_config: &'a Value, node_type: &str) -> Option<&'a Value> {
+    match global_config {
+        Value::Object(map) => map.get(node_type),
+        _ => None,
+    }
+}
+
+/// 对比新旧 FlowMod，节点定义或其类型的全局配置变化都视为变更
+pub fn diff_flow_mod(old: &FlowMod, new: &FlowMod) -> ReloadSummary {
+    let old_nodes: HashMap<Uuid, &FlowNode> = old
+        .enabled_flows()
+        .flat_map(|flow| flow.nodes.iter())
+        .map(|node| (node.id, node))
+        .collect();
+    let new_nodes: HashMap<Uuid, &FlowNode> = new
+        .enabled_flows()
+        .flat_map(|flow| flow.nodes.iter())
+        .map(|node| (node.id, node))
+        .collect();
+
+    let mut summary = ReloadSummary::default();
+
+    let node_types: HashSet<&str> = old_nodes
+        .values()
+        .chain(new_nodes.values())
+        .map(|node| node.node_type.as_str())
+        .collect();
+    for t in node_types {
+        if global_config_of(&old.node_global_config, t)
+            != global_config_of(&new.node_global_config, t)
+        {
+            summary.global_config_changed.insert(t.to_string());
+        }
+    }
+
+    for (id, node) in &new_nodes {
+        match old_nodes.get(id) {
+            None => summary.added.push(*id),
+            Some(old_node) => {
+                if *old_node != *node || summary.global_config_changed.contains(&node.node_type) {
+                    summary.changed.push(*id);
+                }
+            }
+        }
+    }
+    for id in old_nodes.keys() {
+        if !new_nodes.contains_key(id) {
+            summary.removed.push(*id);
+        }
+    }
+
+    summary
+}
+
+/// 监听流程文件变化并触发热重载，直到引擎停止
+///
+/// 监听所在目录而不是文件本身，编辑器通过重命名替换文件时也能收到事件。
+pub async fn watch_flow_file(engine: Arc<Engine>) -> notify::Result<()> {
+    let Some(path) = engine.flow_file_path().map(PathBuf::from) else {
+        return Err(notify::Error::generic(
+            "engine was not built from a flow file",
+        ));
+    };
+    let file_name = path.file_name().map(|name| name.to_os_string());
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        if let Ok(event) = res {
+            let hit = event
+                .paths
+                .iter()
+                .any(|p| p.file_name().map(|n| n.to_os_string()) == file_name);
+            if hit && (event.kind.is_modify() || event.kind.is_create()) {
+                let _ = tx.send(());
+            }
+        }
+    })?;
+    watcher.watch(Path::new(&dir), RecursiveMode::NonRecursive)?;
+    info!("Watching flow file: {}", path.display());
+
+    loop {
+        tokio::select! {
+            _ = engine.stopped() => break,
+            event = rx.recv() => {
+                if event.is_none() {
+                    break;
+                }
+                // 合并短时间内的连续写入
+                tokio::time::sleep(Duration::from_millis(300)).await;
+                while rx.try_recv().is_ok() {}
+
+                match engine.reload().await {
+                    Ok(summary) if summary.is_empty() => {}
+                    Ok(summary) => info!(
+                        "Flow reloaded: {} added, {} removed, {} changed",
+                        summary.added.len(),
+                        summary.removed.len(),
+                        summary.changed.len()
+                    ),
+                    Err(err) => error!("Flow reload failed: {}", err),
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flow::{FlowBuilder, FlowModBuilder, NodeSpec};
+
+    fn flow_mod(flows: Vec<FlowBuilder>) -> FlowMod {
+        flows
+            .into_iter()
+            .fold(FlowModBuilder::new(), |builder, flow| {
+                builder.flow(flow.build())
+            })
+            .build()
+    }
+
+    fn sorted(mut ids: Vec<Uuid>) -> Vec<Uuid> {
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn same_mod_is_empty() {
+        let mut flow = FlowBuilder::new("main");
+        flow.add(NodeSpec::new("log"));
+        let old = flow_mod(vec![flow]);
+        let summary = diff_flow_mod(&old, &old.clone());
+        assert!(summary.is_empty());
+        assert!(summary.global_config_changed.is_empty());
+    }
+
+    #[test]
+    fn added_and_removed_nodes() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let flow_id = Uuid::new_v4();
+        let mut old = FlowBuilder::new("main").id(flow_id);
+        old.add(NodeSpec::new("log").id(a));
+        old.add(NodeSpec::new("log").id(b));
+        let mut new = FlowBuilder::new("main").id(flow_id);
+        new.add(NodeSpec::new("log").id(a));
+        new.add(NodeSpec::new("log").id(c));
+
+        let summary = diff_flow_mod(&flow_mod(vec![old]), &flow_mod(vec![new]));
+        assert_eq!(summary.added, vec![c]);
+        assert_eq!(summary.removed, vec![b]);
+        assert!(summary.changed.is_empty());
+    }
+
+    #[test]
+    fn changed_node_definition_and_wires() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut old = FlowBuilder::new("main");
+        old.add(NodeSpec::new("log").id(a).name("before"));
+        old.add(NodeSpec::new("log").id(b));
+        let mut new = FlowBuilder::new("main");
+        let from = new.add(NodeSpec::new("log").id(a).name("after"));
+        let to = new.add(NodeSpec::new("log").id(b));
+        new.wire(from, 0, to, 0);
+
+        let summary = diff_flow_mod(&flow_mod(vec![old]), &flow_mod(vec![new]));
+        // a 改名并新增输出，b 新增输入
+        assert_eq!(sorted(summary.changed), sorted(vec![a, b]));
+        assert!(summary.added.is_empty());
+        assert!(summary.removed.is_empty());
+    }
+
+    #[test]
+    fn global_config_change_marks_nodes_of_type() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut flow = FlowBuilder::new("main");
+        flow.add(NodeSpec::new("shell").id(a));
+        flow.add(NodeSpec::new("log").id(b));
+        let old = FlowModBuilder::new().flow(flow.build()).build();
+        let mut new = old.clone();
+        new.node_global_config = Value::Object(HashMap::from([(
+            "shell".to_string(),
+            Value::String("sh".into()),
+        )]));
+
+        let summary = diff_flow_mod(&old, &new);
+        assert_eq!(summary.changed, vec![a]);
+        assert_eq!(
+            summary.global_config_changed,
+            HashSet::from(["shell".to_string()])
+        );
+    }
+
+    #[test]
+    fn disabled_flow_nodes_are_removed_and_enabled_again_added() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let build = |disabled: bool| {
+            let mut main = FlowBuilder::new("main");
+            main.add(NodeSpec::new("log").id(a));
+            let mut other = FlowBuilder::new("other").disabled(disabled);
+            other.add(NodeSpec::new("log").id(b));
+            flow_mod(vec![main, other])
+        };
+        let (enabled, disabled) = (build(false), build(true));
+
+        let summary = diff_flow_mod(&enabled, &disabled);
+        assert_eq!(summary.removed, vec![b]);
+        assert!(summary.added.is_empty() && summary.changed.is_empty());
+
+        let summary = diff_flow_mod(&disabled, &enabled);
+        assert_eq!(summary.added, vec![b]);
+        assert!(summary.removed.is_empty() && summary.changed.is_empty());
+    }
+}
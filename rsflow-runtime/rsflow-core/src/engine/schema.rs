@@ -0,0 +1,317 @@
+use crate::core::Value;
+use crate::engine::NodeBuilderMap;
+use crate::flow::{Diagnostic, FlowMod, Severity};
+use jsonschema::JSONSchema;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+use uuid::Uuid;
+
+/// 节点配置不符合节点类型声明的 Schema
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ConfigError {
+    /// 所属 flow 名称，node_global_config 的错误为 None
+    pub flow: Option<String>,
+    pub node: Option<String>,
+    pub flow_id: Option<Uuid>,
+    pub node_id: Option<Uuid>,
+    pub node_type: String,
+    /// 出错位置在 flow 文件中的 JSON Pointer，如 /flow/0/nodes/1/config/timeout
+    pub pointer: String,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.flow, &self.node) {
+            (Some(flow), Some(node)) => write!(
+                f,
+                "flow \"{}\" node \"{}\" ({})",
+                flow, node, self.node_type
+            )?,
+            _ if self.pointer.is_empty() => write!(f, "node type \"{}\"", self.node_type)?,
+            _ => write!(f, "node_global_config ({})", self.node_type)?,
+        }
+        if !self.pointer.is_empty() {
+            write!(f, " at {}", self.pointer)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+impl From<ConfigError> for Diagnostic {
+    fn from(err: ConfigError) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            code: "invalid-config",
+            flow_id: err.flow_id,
+            node_id: err.node_id,
+            message: err.to_string(),
+        }
+    }
+}
+
+/// 按各节点类型的 config_schema / global_config_schema 校验未停用 flow 中的节点配置
+/// 和 node_global_config，返回所有错误
+///
+/// 未注册的节点类型不在这里检查；未配置的 config（null）按空对象校验。
+pub fn check_node_configs(flow_mod: &FlowMod, builders: &NodeBuilderMap) -> Vec<ConfigError> {
+    let mut errors = Vec::new();
+    let mut schemas: HashMap<&str, Option<JSONSchema>> = HashMap::new();
+
+    for (flow_index, flow) in flow_mod.flow.iter().enumerate() {
+        if flow.disabled {
+            continue;
+        }
+        for (node_index, node) in flow.nodes.iter().enumerate() {
+            let Some(builder) = builders.get(&node.node_type) else {
+                continue;
+            };
+            let schema = schemas.entry(node.node_type.as_str()).or_insert_with(|| {
+                compile(
+                    &builder.descriptor().config_schema,
+                    &mut errors,
+                    &node.node_type,
+                )
+            });
+            let Some(schema) = schema else {
+                continue;
+            };
+            let config = match &node.config {
+                Value::NULL => serde_json::json!({}),
+                config => to_json(config),
+            };
+            if let Err(failures) = schema.validate(&config) {
+                let pointer = format!("/flow/{}/nodes/{}/config", flow_index, node_index);
+                for failure in failures {
+                    errors.push(ConfigError {
+                        flow: Some(flow.name.clone()),
+                        node: Some(node.name.clone()),
+                        flow_id: Some(flow.id),
+                        node_id: Some(node.id),
+                        node_type: node.node_type.clone(),
+                        pointer: format!("{}{}", pointer, failure.instance_path),
+                        message: failure.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    if let Value::Object(global) = &flow_mod.node_global_config {
+        let mut types: Vec<&String> = global.keys().collect();
+        types.sort();
+        for node_type in types {
+            let Some(builder) = builders.get(node_type) else {
+                continue;
+            };
+            let Some(schema) = compile(
+                &builder.descriptor().global_config_schema,
+                &mut errors,
+                node_type,
+            ) else {
+                continue;
+            };
+            let pointer = format!("/node_global_config/{}", escape_pointer(node_type));
+            if let Err(failures) = schema.validate(&to_json(&global[node_type])) {
+                for failure in failures {
+                    errors.push(ConfigError {
+                        flow: None,
+                        node: None,
+                        flow_id: None,
+                        node_id: None,
+                        node_type: node_type.clone(),
+                        pointer: format!("{}{}", pointer, failure.instance_path),
+                        message: failure.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    errors
+}
+
+// 编译节点类型声明的 Schema，空 Schema 不需要校验返回 None
+fn compile(
+    schema: &serde_json::Value,
+    errors: &mut Vec<ConfigError>,
+    node_type: &str,
+) -> Option<JSONSchema> {
+    if schema.as_object().is_some_and(|map| map.is_empty()) {
+        return None;
+    }
+    match JSONSchema::compile(schema) {
+        Ok(schema) => Some(schema),
+        Err(e) => {
+            errors.push(ConfigError {
+                flow: None,
+                node: None,
+                flow_id: None,
+                node_id: None,
+                node_type: node_type.to_string(),
+                pointer: String::new(),
+                message: format!("invalid schema declared by node type: {}", e),
+            });
+            None
+        }
+    }
+}
+
+fn to_json(value: &Value) -> serde_json::Value {
+    serde_json::to_value(value).unwrap_or(serde_json::Value::Null)
+}
+
+// JSON Pointer 中 ~ 和 / 需要转义
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{NodeBuilder, NodeDescriptor, NodeError, NodeFactory};
+    use crate::flow::{FlowBuilder, FlowModBuilder, NodeSpec};
+    use serde_json::json;
+
+    // 只声明 Schema 的节点类型
+    struct SchemaBuilder {
+        node_type: &'static str,
+        config: serde_json::Value,
+        global_config: serde_json::Value,
+    }
+
+    #[async_trait::async_trait]
+    impl NodeBuilder for SchemaBuilder {
+        fn node_type(&self) -> &str {
+            self.node_type
+        }
+
+        fn descriptor(&self) -> NodeDescriptor {
+            NodeDescriptor::new(self.node_type)
+                .config_schema(self.config.clone())
+                .global_config_schema(self.global_config.clone())
+        }
+
+        async fn register(&self, _: &Value) -> Result<Box<dyn NodeFactory>, NodeError> {
+            Err(NodeError::Unsupported("register".to_string()))
+        }
+    }
+
+    fn value(json: serde_json::Value) -> Value {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn builders() -> NodeBuilderMap {
+        let mut builders: NodeBuilderMap = HashMap::new();
+        builders.insert(
+            "cmd".to_string(),
+            Box::new(SchemaBuilder {
+                node_type: "cmd",
+                config: json!({
+                    "type": "object",
+                    "properties": {
+                        "command": { "type": "string" },
+                        "timeout": { "type": "integer", "minimum": 0 }
+                    },
+                    "required": ["command"],
+                    "additionalProperties": false
+                }),
+                global_config: json!({}),
+            }),
+        );
+        builders.insert(
+            "net/tcp".to_string(),
+            Box::new(SchemaBuilder {
+                node_type: "net/tcp",
+                config: json!({}),
+                global_config: json!({
+                    "type": "object",
+                    "properties": { "limit": { "type": "integer" } }
+                }),
+            }),
+        );
+        builders.insert(
+            "broken".to_string(),
+            Box::new(SchemaBuilder {
+                node_type: "broken",
+                config: json!({ "type": 5 }),
+                global_config: json!({}),
+            }),
+        );
+        builders
+    }
+
+    #[test]
+    fn reports_all_errors_with_pointers() {
+        let mut skipped = FlowBuilder::new("skipped").disabled(true);
+        skipped.add(NodeSpec::new("cmd").config(value(json!({ "timeout": "x" }))));
+        let mut flow = FlowBuilder::new("main");
+        flow.add(NodeSpec::new("cmd").config(value(json!({ "command": "ls" }))));
+        let bad = flow.add(
+            NodeSpec::new("cmd")
+                .name("bad")
+                .config(value(json!({ "command": "ls", "timeout": -1 }))),
+        );
+        flow.add(NodeSpec::new("cmd").config(value(json!({ "command": "ls", "timout": 3 }))));
+        flow.add(NodeSpec::new("cmd"));
+        flow.add(NodeSpec::new("unknown").config(value(json!({ "any": true }))));
+        let main = flow.build();
+        let main_id = main.id;
+        let flow_mod = FlowModBuilder::new()
+            .flow(skipped.build())
+            .flow(main)
+            .node_global_config("net/tcp", value(json!({ "limit": "many" })))
+            .build();
+
+        let errors = check_node_configs(&flow_mod, &builders());
+        let pointers: Vec<&str> = errors.iter().map(|e| e.pointer.as_str()).collect();
+        // 停用的 flow 和未注册的类型不检查，其余错误一次全部返回
+        assert_eq!(
+            pointers,
+            [
+                "/flow/1/nodes/1/config/timeout",
+                "/flow/1/nodes/2/config",
+                "/flow/1/nodes/3/config",
+                "/node_global_config/net~1tcp/limit",
+            ]
+        );
+        assert_eq!(errors[0].flow_id, Some(main_id));
+        assert_eq!(errors[0].node_id, Some(bad.id()));
+        assert_eq!(
+            errors[0].to_string(),
+            format!(
+                "flow \"main\" node \"bad\" (cmd) at /flow/1/nodes/1/config/timeout: {}",
+                errors[0].message
+            )
+        );
+        // 多余的属性指向 config 本身，信息中包含属性名
+        assert!(errors[1].message.contains("timout"));
+        // 未配置按空对象校验
+        assert!(errors[2].message.contains("command"));
+        assert_eq!(errors[3].node_type, "net/tcp");
+        assert_eq!(errors[3].node_id, None);
+        assert!(
+            errors[3]
+                .to_string()
+                .starts_with("node_global_config (net/tcp) at ")
+        );
+    }
+
+    #[test]
+    fn invalid_schema_reported_once_per_type() {
+        let mut flow = FlowBuilder::new("main");
+        flow.add(NodeSpec::new("broken"));
+        flow.add(NodeSpec::new("broken"));
+        let flow_mod = FlowModBuilder::new().flow(flow.build()).build();
+
+        let errors = check_node_configs(&flow_mod, &builders());
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].pointer, "");
+        assert!(
+            errors[0]
+                .to_string()
+                .starts_with("node type \"broken\": invalid schema declared by node type")
+        );
+    }
+}
@@ -0,0 +1,38 @@
+use crate::core::{FlowStats, QueueStats};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// 引擎运行阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EngineState {
+    /// 已构建，尚未调用 start
+    Created,
+    Running,
+    /// 已请求停止，等待运行中的 flow 完成
+    Stopping,
+}
+
+/// flow 概要
+#[derive(Debug, Clone, Serialize)]
+pub struct FlowSummary {
+    pub id: Uuid,
+    pub name: String,
+    pub disabled: bool,
+    pub nodes: usize,
+}
+
+/// 引擎状态
+#[derive(Debug, Clone, Serialize)]
+pub struct EngineStatus {
+    pub state: EngineState,
+    /// 由 FlowMod 直接构建时为 null
+    pub flow_file: Option<String>,
+    /// 自 start 起经过的秒数
+    pub uptime_secs: u64,
+    pub flows: Vec<FlowSummary>,
+    /// 当前的节点实例数（不含停用的 flow）
+    pub nodes: usize,
+    pub queue: QueueStats,
+    pub runs: FlowStats,
+}
@@ -0,0 +1,204 @@
+//! 引擎测试用的节点和插件
+use crate::core::{
+    EngineContext, FlowContext, Node, NodeBuilder, NodeError, NodeFactory, NodeInfo, NodeInput,
+    NodeOutput, NodeRunItem, Payload, Value,
+};
+use crate::engine::{EngineBuilder, EngineHandle, EnginePlugin, NodeBuilderMap};
+use crate::flow::FlowMod;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// 测试节点的行为
+#[derive(Clone, Copy)]
+pub(crate) enum Behavior {
+    /// 原样从 0 号端口输出
+    Pass,
+    /// 返回 NodeError::Shell
+    Fail,
+    /// 等待后从 0 号端口输出
+    Slow(Duration),
+}
+
+/// 节点和插件的调用计数，同一类型的所有实例共用
+#[derive(Default)]
+pub(crate) struct Probe {
+    pub started: AtomicUsize,
+    pub stopped: AtomicUsize,
+    pub inputs: AtomicUsize,
+}
+
+impl Probe {
+    pub fn started(&self) -> usize {
+        self.started.load(Ordering::SeqCst)
+    }
+
+    pub fn stopped(&self) -> usize {
+        self.stopped.load(Ordering::SeqCst)
+    }
+
+    pub fn inputs(&self) -> usize {
+        self.inputs.load(Ordering::SeqCst)
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct TestNodeBuilder {
+    node_type: &'static str,
+    behavior: Behavior,
+    probe: Arc<Probe>,
+}
+
+impl TestNodeBuilder {
+    pub fn new(node_type: &'static str, behavior: Behavior, probe: &Arc<Probe>) -> Self {
+        Self {
+            node_type,
+            behavior,
+            probe: Arc::clone(probe),
+        }
+    }
+}
+
+struct TestNode {
+    info: NodeInfo,
+    behavior: Behavior,
+    probe: Arc<Probe>,
+}
+
+#[async_trait::async_trait]
+impl Node for TestNode {
+    fn info(&self) -> NodeInfo {
+        self.info.clone()
+    }
+
+    async fn engine_start(&self, _: EngineContext) {
+        self.probe.started.fetch_add(1, Ordering::SeqCst);
+    }
+
+    async fn engine_stop(&self) {
+        self.probe.stopped.fetch_add(1, Ordering::SeqCst);
+    }
+
+    async fn event(&self, _: &str, _: Payload, _: &FlowContext) -> Result<(), NodeError> {
+        Ok(())
+    }
+
+    async fn input(&self, input: NodeInput, _: &FlowContext) -> Result<NodeOutput, NodeError> {
+        self.probe.inputs.fetch_add(1, Ordering::SeqCst);
+        match self.behavior {
+            Behavior::Pass => Ok(NodeOutput::One((0, input.msg))),
+            Behavior::Fail => Err(NodeError::Shell("failed".to_string())),
+            Behavior::Slow(delay) => {
+                tokio::time::sleep(delay).await;
+                Ok(NodeOutput::One((0, input.msg)))
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl NodeFactory for TestNodeBuilder {
+    async fn create(&self, info: NodeInfo) -> Result<Arc<dyn Node + Send + Sync>, NodeError> {
+        Ok(Arc::new(TestNode {
+            info,
+            behavior: self.behavior,
+            probe: Arc::clone(&self.probe),
+        }))
+    }
+}
+
+#[async_trait::async_trait]
+impl NodeBuilder for TestNodeBuilder {
+    fn node_type(&self) -> &str {
+        self.node_type
+    }
+
+    async fn register(&self, _: &Value) -> Result<Box<dyn NodeFactory>, NodeError> {
+        Ok(Box::new(self.clone()))
+    }
+}
+
+/// 配置只接受对象或 NULL 的插件
+pub(crate) struct TestPlugin {
+    pub probe: Arc<Probe>,
+}
+
+#[async_trait::async_trait]
+impl EnginePlugin for TestPlugin {
+    fn name(&self) -> &'static str {
+        "test"
+    }
+
+    async fn configure(&mut self, config: &Value) -> Result<(), NodeError> {
+        match config {
+            Value::NULL | Value::Object(_) => Ok(()),
+            _ => Err(NodeError::InvalidConfig(
+                "test plugin config must be an object".to_string(),
+            )),
+        }
+    }
+
+    fn internal_nodes(&self) -> NodeBuilderMap {
+        HashMap::new()
+    }
+
+    async fn engine_start(&self, _: EngineContext) {
+        self.probe.started.fetch_add(1, Ordering::SeqCst);
+    }
+
+    async fn engine_stop(&self) {
+        self.probe.stopped.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// 注册 pass / fail / slow（100ms）和 catch（同 pass）节点
+pub(crate) fn engine_builder(probe: &Arc<Probe>) -> EngineBuilder {
+    EngineBuilder::new()
+        .register_node(TestNodeBuilder::new("pass", Behavior::Pass, probe))
+        .register_node(TestNodeBuilder::new("fail", Behavior::Fail, probe))
+        .register_node(TestNodeBuilder::new(
+            "slow",
+            Behavior::Slow(Duration::from_millis(100)),
+            probe,
+        ))
+        .register_node(TestNodeBuilder::new(
+            crate::core::CATCH_NODE_TYPE,
+            Behavior::Pass,
+            probe,
+        ))
+}
+
+/// 构建并在后台启动引擎
+pub(crate) async fn start_engine(
+    builder: EngineBuilder,
+    flow_mod: FlowMod,
+) -> (EngineHandle, tokio::task::JoinHandle<()>) {
+    let handle = builder.build_from_mod(flow_mod).await.unwrap();
+    let engine = handle.clone();
+    let task = tokio::spawn(async move { engine.start().await });
+    (handle, task)
+}
+
+/// 从节点的 0 号端口启动 flow
+pub(crate) fn start_at(node_id: Uuid, value: Value) -> NodeRunItem {
+    NodeRunItem {
+        node_id,
+        node_input: NodeInput {
+            port: 0,
+            msg: Payload::new(value),
+        },
+    }
+}
+
+/// 等待条件成立，最长 5 秒
+pub(crate) async fn wait_until(cond: impl Fn() -> bool) {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while !cond() {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .expect("condition not met within 5 seconds");
+}
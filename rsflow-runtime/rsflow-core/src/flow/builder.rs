@@ -0,0 +1,389 @@
+use crate::core::{DebugConfig, EngineConfig, RetryPolicy, Value};
+use crate::flow::models::{
+    Flow, FlowMod, FlowNode, FlowNodeInputPort, FlowNodeOutputPort, FlowNodeOutputPortItem,
+    PluginConfig,
+};
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+/// 在代码中构建 FlowMod，配合 EngineBuilder::build_from_mod 使用
+///
+/// ```ignore
+/// let mut flow = FlowBuilder::new("main");
+/// let shell = flow.add(NodeSpec::new("shell").config(config));
+/// let log = flow.add(NodeSpec::new("log").name("Log"));
+/// flow.wire(shell, 0, log, 0);
+///
+/// let flow_mod = FlowModBuilder::new().msg_len(100).flow(flow.build()).build();
+/// let handle = engine_builder().build_from_mod(flow_mod).await?;
+/// ```
+pub struct FlowModBuilder {
+    config: EngineConfig,
+    plugin_config: HashMap<String, Value>,
+    node_global_config: HashMap<String, Value>,
+    flows: Vec<Flow>,
+}
+
+impl FlowModBuilder {
+    /// 默认 msg_len 为 1000，shutdown_timeout 为 10 秒
+    pub fn new() -> Self {
+        Self {
+            config: EngineConfig {
+                msg_len: 1000,
+                shutdown_timeout: 10,
+                debug: DebugConfig::default(),
+            },
+            plugin_config: HashMap::new(),
+            node_global_config: HashMap::new(),
+            flows: Vec::new(),
+        }
+    }
+
+    pub fn msg_len(mut self, msg_len: usize) -> Self {
+        self.config.msg_len = msg_len;
+        self
+    }
+
+    pub fn shutdown_timeout(mut self, secs: u64) -> Self {
+        self.config.shutdown_timeout = secs;
+        self
+    }
+
+    pub fn debug(mut self, debug: DebugConfig) -> Self {
+        self.config.debug = debug;
+        self
+    }
+
+    /// 插件配置，name 为插件的 name()
+    pub fn plugin_config(mut self, name: &str, config: Value) -> Self {
+        self.plugin_config.insert(name.to_string(), config);
+        self
+    }
+
+    /// 某个节点类型的全局配置（node_global_config 中的子树）
+    pub fn node_global_config(mut self, node_type: &str, config: Value) -> Self {
+        self.node_global_config
+            .insert(node_type.to_string(), config);
+        self
+    }
+
+    pub fn flow(mut self, flow: Flow) -> Self {
+        self.flows.push(flow);
+        self
+    }
+
+    pub fn build(self) -> FlowMod {
+        FlowMod {
+            config: self.config,
+            plugin_config: PluginConfig(self.plugin_config),
+            node_global_config: Value::Object(self.node_global_config),
+            flow: self.flows,
+        }
+    }
+}
+
+impl Default for FlowModBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// FlowBuilder::add 返回的节点引用，用于连线
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeRef(Uuid);
+
+impl NodeRef {
+    pub fn id(&self) -> Uuid {
+        self.0
+    }
+}
+
+/// 节点定义：默认随机 id，名称与类型相同，配置为 NULL
+pub struct NodeSpec {
+    node: FlowNode,
+}
+
+impl NodeSpec {
+    pub fn new(node_type: &str) -> Self {
+        Self {
+            node: FlowNode {
+                id: Uuid::new_v4(),
+                name: node_type.to_string(),
+                node_type: node_type.to_string(),
+                description: String::new(),
+                config: Value::NULL,
+                input: Vec::new(),
+                output: Vec::new(),
+                error_port: None,
+                retry: None,
+            },
+        }
+    }
+
+    /// 固定节点 id，便于重载时识别同一节点
+    pub fn id(mut self, id: Uuid) -> Self {
+        self.node.id = id;
+        self
+    }
+
+    pub fn name(mut self, name: &str) -> Self {
+        self.node.name = name.to_string();
+        self
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.node.description = description.to_string();
+        self
+    }
+
+    pub fn config(mut self, config: Value) -> Self {
+        self.node.config = config;
+        self
+    }
+
+    /// 错误输出端口，连线同普通输出端口
+    pub fn error_port(mut self, port: u8) -> Self {
+        self.node.error_port = Some(port);
+        self
+    }
+
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.node.retry = Some(retry);
+        self
+    }
+}
+
+/// 单个 flow 的构建器，节点的 input / output 由连线生成
+pub struct FlowBuilder {
+    id: Uuid,
+    name: String,
+    description: String,
+    disabled: bool,
+    nodes: Vec<FlowNode>,
+    wires: Vec<(Uuid, u8, Uuid, u8)>,
+}
+
+impl FlowBuilder {
+    /// 默认随机 flow id
+    pub fn new(name: &str) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            description: String::new(),
+            disabled: false,
+            nodes: Vec::new(),
+            wires: Vec::new(),
+        }
+    }
+
+    pub fn id(mut self, id: Uuid) -> Self {
+        self.id = id;
+        self
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = description.to_string();
+        self
+    }
+
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+
+    /// 添加节点，按添加顺序写入 flow
+    pub fn add(&mut self, spec: NodeSpec) -> NodeRef {
+        let id = spec.node.id;
+        self.nodes.push(spec.node);
+        NodeRef(id)
+    }
+
+    /// 连接 from 的输出端口到 to 的输入端口，重复的连线只保留一条
+    pub fn wire(&mut self, from: NodeRef, from_port: u8, to: NodeRef, to_port: u8) -> &mut Self {
+        let wire = (from.0, from_port, to.0, to_port);
+        if !self.wires.contains(&wire) {
+            self.wires.push(wire);
+        }
+        self
+    }
+
+    /// 生成 Flow：output 按连线顺序，input 由所有节点的 output 反推，端口按编号排列
+    ///
+    /// 引用了其他 flow 节点的连线会原样写入 output，由 flow 校验报告。
+    pub fn build(self) -> Flow {
+        type Ports<T> = HashMap<Uuid, BTreeMap<u8, Vec<T>>>;
+        let mut outputs: Ports<FlowNodeOutputPortItem> = HashMap::new();
+        let mut inputs: Ports<Uuid> = HashMap::new();
+        for (from, from_port, to, to_port) in self.wires {
+            outputs
+                .entry(from)
+                .or_default()
+                .entry(from_port)
+                .or_default()
+                .push(FlowNodeOutputPortItem {
+                    id: to,
+                    port: to_port,
+                });
+            let sources = inputs.entry(to).or_default().entry(to_port).or_default();
+            if !sources.contains(&from) {
+                sources.push(from);
+            }
+        }
+
+        let nodes = self
+            .nodes
+            .into_iter()
+            .map(|mut node| {
+                node.output = outputs
+                    .remove(&node.id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(port, nodes)| FlowNodeOutputPort { port, nodes })
+                    .collect();
+                node.input = inputs
+                    .remove(&node.id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(port, nodes)| FlowNodeInputPort { port, nodes })
+                    .collect();
+                node
+            })
+            .collect();
+
+        Flow {
+            id: self.id,
+            name: self.name,
+            description: self.description,
+            disabled: self.disabled,
+            nodes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flow::validate_flow;
+
+    fn inputs(node: &FlowNode) -> Vec<(u8, Vec<Uuid>)> {
+        node.input
+            .iter()
+            .map(|input| (input.port, input.nodes.clone()))
+            .collect()
+    }
+
+    fn outputs(node: &FlowNode) -> Vec<(u8, Vec<(Uuid, u8)>)> {
+        node.output
+            .iter()
+            .map(|output| {
+                let items = output
+                    .nodes
+                    .iter()
+                    .map(|item| (item.id, item.port))
+                    .collect();
+                (output.port, items)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn derives_inputs_from_wires() {
+        let mut flow = FlowBuilder::new("main");
+        let a = flow.add(NodeSpec::new("pass"));
+        let b = flow.add(NodeSpec::new("pass"));
+        let c = flow.add(NodeSpec::new("pass"));
+        flow.wire(b, 1, c, 1)
+            .wire(a, 0, c, 0)
+            .wire(b, 0, c, 0)
+            .wire(a, 0, c, 0);
+        let flow = flow.build();
+
+        // 重复的连线只保留一条，端口按编号排列，同一端口的来源按连线顺序
+        assert_eq!(outputs(&flow.nodes[0]), [(0, vec![(c.id(), 0)])]);
+        assert_eq!(
+            outputs(&flow.nodes[1]),
+            [(0, vec![(c.id(), 0)]), (1, vec![(c.id(), 1)])]
+        );
+        assert_eq!(
+            inputs(&flow.nodes[2]),
+            [(0, vec![a.id(), b.id()]), (1, vec![b.id()])]
+        );
+        assert!(flow.nodes[0].input.is_empty());
+        assert!(flow.nodes[2].output.is_empty());
+        let flow_mod = FlowModBuilder::new().flow(flow).build();
+        assert_eq!(validate_flow(&flow_mod), []);
+    }
+
+    #[test]
+    fn duplicate_names_keep_distinct_nodes() {
+        let mut flow = FlowBuilder::new("main");
+        let first = flow.add(NodeSpec::new("pass").name("same"));
+        let second = flow.add(NodeSpec::new("pass").name("same"));
+        flow.wire(first, 0, second, 0);
+        let mut other = FlowBuilder::new("main");
+        other.add(NodeSpec::new("pass").name("same"));
+        let flow_mod = FlowModBuilder::new()
+            .flow(flow.build())
+            .flow(other.build())
+            .build();
+
+        // 名称不要求唯一，连线按 id 区分
+        assert_ne!(first.id(), second.id());
+        assert_ne!(flow_mod.flow[0].id, flow_mod.flow[1].id);
+        let nodes = &flow_mod.flow[0].nodes;
+        assert_eq!(outputs(&nodes[0]), [(0, vec![(second.id(), 0)])]);
+        assert_eq!(inputs(&nodes[1]), [(0, vec![first.id()])]);
+        assert_eq!(validate_flow(&flow_mod), []);
+
+        // 固定 id 重复时由 flow 校验报告
+        let id = Uuid::new_v4();
+        let mut flow = FlowBuilder::new("main");
+        flow.add(NodeSpec::new("pass").id(id));
+        flow.add(NodeSpec::new("pass").id(id));
+        let flow_mod = FlowModBuilder::new().flow(flow.build()).build();
+        let diagnostics = validate_flow(&flow_mod);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "duplicate-node-id");
+    }
+
+    #[test]
+    fn wires_across_flows_only_write_outputs() {
+        let mut main = FlowBuilder::new("main");
+        let mut other = FlowBuilder::new("other");
+        let source = main.add(NodeSpec::new("pass"));
+        let target = other.add(NodeSpec::new("pass"));
+        main.wire(source, 0, target, 0);
+        let flow_mod = FlowModBuilder::new()
+            .flow(main.build())
+            .flow(other.build())
+            .build();
+
+        // 另一个 flow 的节点不会得到 input，flow 校验给出 warning
+        assert_eq!(
+            outputs(&flow_mod.flow[0].nodes[0]),
+            [(0, vec![(target.id(), 0)])]
+        );
+        assert!(flow_mod.flow[1].nodes[0].input.is_empty());
+        let diagnostics = validate_flow(&flow_mod);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "input-output-mismatch");
+        assert_eq!(diagnostics[0].node_id, Some(source.id()));
+        assert!(!diagnostics[0].is_error());
+    }
+
+    #[test]
+    fn wire_to_unknown_node_is_reported() {
+        let mut flow = FlowBuilder::new("main");
+        let source = flow.add(NodeSpec::new("pass"));
+        // 未加入任何 flow 的节点
+        let unknown = FlowBuilder::new("scratch").add(NodeSpec::new("pass"));
+        flow.wire(source, 0, unknown, 0).wire(unknown, 0, source, 0);
+        let flow_mod = FlowModBuilder::new().flow(flow.build()).build();
+
+        let source_node = &flow_mod.flow[0].nodes[0];
+        assert_eq!(outputs(source_node), [(0, vec![(unknown.id(), 0)])]);
+        assert_eq!(inputs(source_node), [(0, vec![unknown.id()])]);
+        let codes: Vec<&str> = validate_flow(&flow_mod).iter().map(|d| d.code).collect();
+        assert_eq!(codes, ["dangling-output", "dangling-input"]);
+    }
+}
@@ -0,0 +1,437 @@
+use crate::core::CATCH_NODE_TYPE;
+use crate::flow::FlowMod;
+use serde_json::{Map, Value as JsonValue, json};
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+/// stable_uuid 使用的命名空间，改动会使已导入的 flow 的 id 全部变化
+pub const RSFLOW_NAMESPACE: Uuid = Uuid::from_u128(0x5f1c_9d2e_7a43_4b8e_9c61_0d3f_a2b7_e410);
+
+/// 由字符串 id 得到稳定的 UUID：同一 kind 和 id 总是得到同一个 UUID（v5）
+pub fn stable_uuid(kind: &str, id: &str) -> Uuid {
+    Uuid::new_v5(&RSFLOW_NAMESPACE, format!("{}:{}", kind, id).as_bytes())
+}
+
+/// 展开简写的 flow 文档，结果可直接反序列化为 FlowMod
+///
+/// - flow / 节点的 id 可以是任意字符串，非 UUID 时换成 stable_uuid，连线和 catch 的 scope 随之替换
+/// - 节点的 name 默认为 id，description 默认为空，config 默认为 null，output 默认为空
+/// - flow 的 `wires: ["a:0 -> b:0"]` 追加到节点的 output，端口省略时为 0
+/// - 省略 input 的节点由所有 flow 中各节点的 output 反推，跨 flow 的连线也会写入 input
+pub fn expand_compact(doc: &mut JsonValue) -> Result<(), String> {
+    let root = doc.as_object_mut().ok_or("flow file must be an object")?;
+    root.entry("node_global_config")
+        .or_insert_with(|| json!({}));
+    let Some(JsonValue::Array(flows)) = root.get_mut("flow") else {
+        return Ok(());
+    };
+
+    // 所有 flow 的节点 id，连线可以跨 flow 引用
+    let mut ids: HashMap<String, Uuid> = HashMap::new();
+    for flow in flows.iter() {
+        for node in nodes_of(flow) {
+            if let Some(id) = node.get("id").and_then(JsonValue::as_str) {
+                ids.insert(id.to_string(), to_uuid("node", id));
+            }
+        }
+    }
+    let resolve = |id: &str| -> Result<Uuid, String> {
+        ids.get(id)
+            .copied()
+            .or_else(|| Uuid::parse_str(id).ok())
+            .ok_or_else(|| format!("unknown node id \"{}\"", id))
+    };
+
+    for flow in flows.iter_mut() {
+        let Some(flow) = flow.as_object_mut() else {
+            continue;
+        };
+        if let Some(id) = flow
+            .get("id")
+            .and_then(JsonValue::as_str)
+            .map(str::to_string)
+        {
+            flow.entry("name").or_insert_with(|| json!(id));
+            flow.insert("id".into(), json!(to_uuid("flow", &id)));
+        }
+        flow.entry("description").or_insert_with(|| json!(""));
+        let wires = match flow.remove("wires") {
+            Some(JsonValue::Array(wires)) => wires,
+            Some(_) => return Err("wires must be an array of \"a:0 -> b:0\"".into()),
+            None => Vec::new(),
+        };
+        let Some(JsonValue::Array(nodes)) = flow.get_mut("nodes") else {
+            continue;
+        };
+
+        for node in nodes.iter_mut().filter_map(JsonValue::as_object_mut) {
+            if let Some(id) = node
+                .get("id")
+                .and_then(JsonValue::as_str)
+                .map(str::to_string)
+            {
+                node.entry("name").or_insert_with(|| json!(id));
+                node.insert("id".into(), json!(resolve(&id)?));
+            }
+            node.entry("description").or_insert_with(|| json!(""));
+            node.entry("config").or_insert(JsonValue::Null);
+            node.entry("output").or_insert_with(|| json!([]));
+            map_ids(node.get_mut("output"), &["nodes", "id"], &resolve)?;
+            map_ids(node.get_mut("input"), &["nodes"], &resolve)?;
+            if node.get("node_type").and_then(JsonValue::as_str) == Some(CATCH_NODE_TYPE)
+                && let Some(scope) = node.get_mut("config").and_then(|c| c.get_mut("scope"))
+            {
+                map_id_list(scope, &resolve)?;
+            }
+        }
+
+        for wire in &wires {
+            let text = wire.as_str().ok_or("wires must be strings")?;
+            let (from, from_port, to, to_port) = parse_wire(text)?;
+            let from = resolve(from)?;
+            let to = resolve(to)?;
+            let node = nodes
+                .iter_mut()
+                .filter_map(JsonValue::as_object_mut)
+                .find(|node| node.get("id") == Some(&json!(from)))
+                .ok_or_else(|| format!("wire \"{}\": source is not in this flow", text))?;
+            add_output(node, from_port, to, to_port)?;
+        }
+    }
+
+    // 由所有 flow 的 output 反推 input，目标可以在其他 flow 中
+    let mut inputs: HashMap<Uuid, BTreeMap<u8, Vec<Uuid>>> = HashMap::new();
+    for node in flows.iter().flat_map(nodes_of) {
+        let Some(from) = node.get("id").and_then(|id| id.as_str()?.parse().ok()) else {
+            continue;
+        };
+        for (_, to, to_port) in outputs_of(node) {
+            let sources = inputs.entry(to).or_default().entry(to_port).or_default();
+            if !sources.contains(&from) {
+                sources.push(from);
+            }
+        }
+    }
+    for flow in flows.iter_mut() {
+        let Some(JsonValue::Array(nodes)) = flow.get_mut("nodes") else {
+            continue;
+        };
+        for node in nodes.iter_mut().filter_map(JsonValue::as_object_mut) {
+            if node.contains_key("input") {
+                continue;
+            }
+            let id: Option<Uuid> = node.get("id").and_then(|id| id.as_str()?.parse().ok());
+            let ports = id.and_then(|id| inputs.remove(&id)).unwrap_or_default();
+            let input: Vec<JsonValue> = ports
+                .into_iter()
+                .map(|(port, nodes)| json!({ "port": port, "nodes": nodes }))
+                .collect();
+            node.insert("input".into(), json!(input));
+        }
+    }
+    Ok(())
+}
+
+/// flow 文档是否使用了简写：flow 带有 wires 或节点省略了 input
+pub fn is_compact(doc: &JsonValue) -> bool {
+    let Some(flows) = doc.get("flow").and_then(JsonValue::as_array) else {
+        return false;
+    };
+    flows.iter().any(|flow| {
+        flow.get("wires").is_some() || nodes_of(flow).any(|node| node.get("input").is_none())
+    })
+}
+
+/// 生成简写的 flow 文档：连线写成 flow 的 wires，省略 input、空的 description 和 null 的 config
+pub fn to_compact(flow_mod: &FlowMod) -> Result<JsonValue, String> {
+    let mut doc = serde_json::to_value(flow_mod).map_err(|e| e.to_string())?;
+    let Some(JsonValue::Array(flows)) = doc.get_mut("flow") else {
+        return Ok(doc);
+    };
+    for flow in flows.iter_mut().filter_map(JsonValue::as_object_mut) {
+        let mut wires = Vec::new();
+        if let Some(JsonValue::Array(nodes)) = flow.get_mut("nodes") {
+            for node in nodes.iter_mut() {
+                let id = node
+                    .get("id")
+                    .and_then(JsonValue::as_str)
+                    .unwrap_or_default();
+                for (port, to, to_port) in outputs_of(node) {
+                    wires.push(json!(format!("{}:{} -> {}:{}", id, port, to, to_port)));
+                }
+                if let Some(node) = node.as_object_mut() {
+                    node.remove("input");
+                    node.remove("output");
+                    remove_if(node, "description", &json!(""));
+                    remove_if(node, "config", &JsonValue::Null);
+                }
+            }
+        }
+        remove_if(flow, "description", &json!(""));
+        if !wires.is_empty() {
+            flow.insert("wires".into(), JsonValue::Array(wires));
+        }
+    }
+    Ok(doc)
+}
+
+fn to_uuid(kind: &str, id: &str) -> Uuid {
+    Uuid::parse_str(id).unwrap_or_else(|_| stable_uuid(kind, id))
+}
+
+fn nodes_of(flow: &JsonValue) -> impl Iterator<Item = &JsonValue> {
+    flow.get("nodes")
+        .and_then(JsonValue::as_array)
+        .into_iter()
+        .flatten()
+}
+
+// 节点 output 中的 (端口, 下游节点, 下游端口)
+fn outputs_of(node: &JsonValue) -> Vec<(u8, Uuid, u8)> {
+    let mut outputs = Vec::new();
+    let ports = node.get("output").and_then(JsonValue::as_array);
+    for port in ports.into_iter().flatten() {
+        let Some(from_port) = port_of(port) else {
+            continue;
+        };
+        let targets = port.get("nodes").and_then(JsonValue::as_array);
+        for target in targets.into_iter().flatten() {
+            let to = target.get("id").and_then(|id| id.as_str()?.parse().ok());
+            if let (Some(to), Some(to_port)) = (to, port_of(target)) {
+                outputs.push((from_port, to, to_port));
+            }
+        }
+    }
+    outputs
+}
+
+fn port_of(value: &JsonValue) -> Option<u8> {
+    value
+        .get("port")
+        .and_then(JsonValue::as_u64)
+        .and_then(|port| u8::try_from(port).ok())
+}
+
+fn add_output(
+    node: &mut Map<String, JsonValue>,
+    port: u8,
+    to: Uuid,
+    to_port: u8,
+) -> Result<(), String> {
+    let ports = node
+        .entry("output")
+        .or_insert_with(|| json!([]))
+        .as_array_mut()
+        .ok_or("node output must be an array")?;
+    let target = json!({ "id": to, "port": to_port });
+    match ports.iter_mut().find(|p| port_of(p) == Some(port)) {
+        Some(entry) => {
+            let nodes = entry
+                .get_mut("nodes")
+                .and_then(JsonValue::as_array_mut)
+                .ok_or("node output nodes must be an array")?;
+            if !nodes.contains(&target) {
+                nodes.push(target);
+            }
+        }
+        None => ports.push(json!({ "port": port, "nodes": [target] })),
+    }
+    Ok(())
+}
+
+// 按 path 找到端口列表中的 id（字符串）并替换为 UUID
+fn map_ids<F>(value: Option<&mut JsonValue>, path: &[&str], resolve: &F) -> Result<(), String>
+where
+    F: Fn(&str) -> Result<Uuid, String>,
+{
+    let Some(JsonValue::Array(ports)) = value else {
+        return Ok(());
+    };
+    for port in ports {
+        match path {
+            [key] => {
+                if let Some(list) = port.get_mut(*key) {
+                    map_id_list(list, resolve)?;
+                }
+            }
+            [key, field] => {
+                let targets = port.get_mut(*key).and_then(JsonValue::as_array_mut);
+                for target in targets.into_iter().flatten() {
+                    if let Some(id) = target.get_mut(*field) {
+                        map_id(id, resolve)?;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn map_id_list<F>(list: &mut JsonValue, resolve: &F) -> Result<(), String>
+where
+    F: Fn(&str) -> Result<Uuid, String>,
+{
+    if let JsonValue::Array(items) = list {
+        for item in items {
+            map_id(item, resolve)?;
+        }
+    }
+    Ok(())
+}
+
+fn map_id<F>(id: &mut JsonValue, resolve: &F) -> Result<(), String>
+where
+    F: Fn(&str) -> Result<Uuid, String>,
+{
+    if let Some(text) = id.as_str() {
+        *id = json!(resolve(text)?);
+    }
+    Ok(())
+}
+
+fn remove_if(object: &mut Map<String, JsonValue>, key: &str, value: &JsonValue) {
+    if object.get(key) == Some(value) {
+        object.remove(key);
+    }
+}
+
+// "a:0 -> b:1"，端口省略时为 0
+fn parse_wire(text: &str) -> Result<(&str, u8, &str, u8), String> {
+    let parsed = text.split_once("->").and_then(|(from, to)| {
+        let (from, from_port) = endpoint(from)?;
+        let (to, to_port) = endpoint(to)?;
+        Some((from, from_port, to, to_port))
+    });
+    match parsed {
+        Some(wire) if !wire.0.is_empty() && !wire.2.is_empty() => Ok(wire),
+        _ => Err(format!(
+            "invalid wire \"{}\", expected \"a:0 -> b:0\"",
+            text
+        )),
+    }
+}
+
+// 取最后一个冒号之后为端口（UUID 中没有冒号）
+fn endpoint(side: &str) -> Option<(&str, u8)> {
+    let side = side.trim();
+    match side.rsplit_once(':') {
+        Some((id, port)) => Some((id.trim(), port.trim().parse().ok()?)),
+        None => Some((side, 0)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(mut doc: JsonValue) -> FlowMod {
+        expand_compact(&mut doc).unwrap();
+        serde_json::from_value(doc).unwrap()
+    }
+
+    fn node_json<'a>(doc: &'a JsonValue, id: &str) -> &'a JsonValue {
+        let id = json!(to_uuid("node", id));
+        doc["flow"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .flat_map(nodes_of)
+            .find(|node| node["id"] == id)
+            .unwrap()
+    }
+
+    #[test]
+    fn stable_uuid_is_v5_in_rsflow_namespace() {
+        let id = stable_uuid("node", "n1");
+        assert_eq!(id.get_version_num(), 5);
+        // 固定值：命名空间或拼接方式改变时已导入的 id 会变化
+        assert_eq!(id.to_string(), "bafda37c-d37e-53f3-bd2b-76c388dabb30");
+        assert_eq!(id, stable_uuid("node", "n1"));
+        assert_ne!(id, stable_uuid("flow", "n1"));
+        assert_ne!(id, stable_uuid("node", "n2"));
+    }
+
+    #[test]
+    fn parse_wire_ports() {
+        assert_eq!(parse_wire("a:1 -> b:2"), Ok(("a", 1, "b", 2)));
+        assert_eq!(parse_wire(" a -> b "), Ok(("a", 0, "b", 0)));
+        assert_eq!(parse_wire("a:255->b"), Ok(("a", 255, "b", 0)));
+    }
+
+    #[test]
+    fn parse_wire_rejects_invalid() {
+        assert!(parse_wire("a:0 => b:0").is_err());
+        assert!(parse_wire("a:0 b:0").is_err());
+        assert!(parse_wire("a:256 -> b:0").is_err());
+        assert!(parse_wire("a:0 -> b:x").is_err());
+        assert!(parse_wire(":0 -> b:0").is_err());
+        assert!(parse_wire("a:0 -> ").is_err());
+    }
+
+    #[test]
+    fn unknown_wire_id() {
+        let mut doc = json!({
+            "config": { "msg_len": 10 },
+            "flow": [{
+                "id": "main",
+                "nodes": [{ "id": "a", "node_type": "log" }],
+                "wires": ["a -> missing"],
+            }],
+        });
+        let err = expand_compact(&mut doc).unwrap_err();
+        assert_eq!(err, "unknown node id \"missing\"");
+    }
+
+    #[test]
+    fn inputs_from_other_flows() {
+        let mut doc = json!({
+            "config": { "msg_len": 10 },
+            "flow": [
+                {
+                    "id": "first",
+                    "nodes": [{ "id": "a", "node_type": "log" }],
+                    "wires": ["a:1 -> b:2"],
+                },
+                {
+                    "id": "second",
+                    "nodes": [{ "id": "b", "node_type": "log" }],
+                },
+            ],
+        });
+        expand_compact(&mut doc).unwrap();
+        let b = node_json(&doc, "b");
+        assert_eq!(
+            b["input"],
+            json!([{ "port": 2, "nodes": [to_uuid("node", "a")] }])
+        );
+    }
+
+    #[test]
+    fn compact_round_trip() {
+        let flow_mod = expand(json!({
+            "config": { "msg_len": 10 },
+            "flow": [{
+                "id": "main",
+                "description": "demo",
+                "nodes": [
+                    { "id": "a", "node_type": "inject", "config": { "interval": 1 } },
+                    { "id": "b", "node_type": "log", "name": "logger" },
+                    { "id": "c", "node_type": "catch", "config": { "scope": ["b"] } },
+                ],
+                "wires": ["a -> b", "a:1 -> b:1", "c -> b"],
+            }],
+        }));
+        let compact = to_compact(&flow_mod).unwrap();
+        let again = expand(compact.clone());
+        assert_eq!(
+            serde_json::to_value(&again).unwrap(),
+            serde_json::to_value(&flow_mod).unwrap()
+        );
+        assert_eq!(
+            compact["flow"][0]["wires"].as_array().map(Vec::len),
+            Some(3)
+        );
+        assert!(compact["flow"][0]["nodes"][1].get("input").is_none());
+    }
+}
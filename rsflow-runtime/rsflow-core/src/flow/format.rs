@@ -0,0 +1,114 @@
+use crate::flow::FlowMod;
+use crate::flow::compact::{expand_compact, is_compact, to_compact};
+use serde_json::Value as JsonValue;
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+/// flow 文件格式，按扩展名选择
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlowFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl FlowFormat {
+    /// .yaml / .yml 为 YAML，.toml 为 TOML，其余为 JSON
+    pub fn from_path(path: &Path) -> Self {
+        let ext = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_ascii_lowercase());
+        match ext.as_deref() {
+            Some("yaml") | Some("yml") => FlowFormat::Yaml,
+            Some("toml") => FlowFormat::Toml,
+            _ => FlowFormat::Json,
+        }
+    }
+
+    /// 解析 flow 文档，支持简写（见 expand_compact）
+    pub fn parse(self, content: &str) -> Result<FlowMod, io::Error> {
+        let mut doc = self.parse_value(content)?;
+        expand_compact(&mut doc).map_err(|e| self.error("parse", &e))?;
+        serde_json::from_value(doc).map_err(|e| self.error("parse", &e))
+    }
+
+    /// flow 文档是否使用了简写（见 is_compact），无法解析时为 false
+    pub fn is_compact(self, content: &str) -> bool {
+        self.parse_value(content).is_ok_and(|doc| is_compact(&doc))
+    }
+
+    fn parse_value(self, content: &str) -> Result<JsonValue, io::Error> {
+        let doc: JsonValue = match self {
+            FlowFormat::Json => {
+                serde_json::from_str(content).map_err(|e| self.error("parse", &e))?
+            }
+            FlowFormat::Yaml => {
+                serde_yaml::from_str(content).map_err(|e| self.error("parse", &e))?
+            }
+            // toml 的错误信息已带有 "TOML parse error" 和出错位置
+            FlowFormat::Toml => toml::from_str(content)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?,
+        };
+        Ok(doc)
+    }
+
+    /// 序列化 FlowMod，compact 时输出简写（见 to_compact）
+    ///
+    /// TOML 没有 null，对象中值为 null 的字段会被省略，数组中有 null 时返回错误。
+    pub fn serialize(self, flow_mod: &FlowMod, compact: bool) -> Result<String, io::Error> {
+        let err = |e: &dyn fmt::Display| self.error("serialize", e);
+        // JSON / YAML 的完整形式直接序列化，保持字段顺序
+        match (self, compact) {
+            (FlowFormat::Json, false) => {
+                return serde_json::to_string_pretty(flow_mod).map_err(|e| err(&e));
+            }
+            (FlowFormat::Yaml, false) => {
+                return serde_yaml::to_string(flow_mod).map_err(|e| err(&e));
+            }
+            _ => {}
+        }
+        let mut doc = if compact {
+            to_compact(flow_mod).map_err(|e| err(&e))?
+        } else {
+            serde_json::to_value(flow_mod).map_err(|e| err(&e))?
+        };
+        match self {
+            FlowFormat::Json => serde_json::to_string_pretty(&doc).map_err(|e| err(&e)),
+            FlowFormat::Yaml => serde_yaml::to_string(&doc).map_err(|e| err(&e)),
+            FlowFormat::Toml => {
+                remove_nulls(&mut doc);
+                toml::to_string_pretty(&doc).map_err(|e| err(&e))
+            }
+        }
+    }
+
+    fn error(self, action: &str, e: &dyn fmt::Display) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} {} error: {}", self, action, e),
+        )
+    }
+}
+
+impl fmt::Display for FlowFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlowFormat::Json => write!(f, "JSON"),
+            FlowFormat::Yaml => write!(f, "YAML"),
+            FlowFormat::Toml => write!(f, "TOML"),
+        }
+    }
+}
+
+fn remove_nulls(value: &mut JsonValue) {
+    match value {
+        JsonValue::Object(object) => {
+            object.retain(|_, v| !v.is_null());
+            object.values_mut().for_each(remove_nulls);
+        }
+        JsonValue::Array(items) => items.iter_mut().for_each(remove_nulls),
+        _ => {}
+    }
+}
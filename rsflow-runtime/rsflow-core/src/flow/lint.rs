@@ -0,0 +1,145 @@
+use crate::core::NodeDescriptor;
+use crate::flow::{Diagnostic, FlowMod};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// 风格检查（只检查未停用的 flow），结果均为 warning：
+/// 未命名的节点、节点类型声明了但没有连线的端口、没有连线的错误端口
+///
+/// 未注册的节点类型由 validate_node_types 报告，这里跳过。
+pub fn lint_flow(
+    flow_mod: &FlowMod,
+    descriptors: &HashMap<String, NodeDescriptor>,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    // 有连线进入的 (节点, 输入端口)
+    let connected_inputs: HashSet<(Uuid, u8)> = flow_mod
+        .enabled_flows()
+        .flat_map(|flow| flow.nodes.iter())
+        .flat_map(|node| node.output.iter())
+        .flat_map(|output| output.nodes.iter().map(|item| (item.id, item.port)))
+        .collect();
+
+    for flow in flow_mod.enabled_flows() {
+        for node in &flow.nodes {
+            let location = Some((flow, node));
+            if node.name.trim().is_empty() {
+                diagnostics.push(Diagnostic::warning(
+                    "unnamed-node",
+                    location,
+                    "node has no name".to_string(),
+                ));
+            }
+
+            let Some(descriptor) = descriptors.get(&node.node_type) else {
+                continue;
+            };
+            let is_connected = |port: u8| {
+                node.output
+                    .iter()
+                    .any(|output| output.port == port && !output.nodes.is_empty())
+            };
+            for port in &descriptor.inputs {
+                if !connected_inputs.contains(&(node.id, port.port)) {
+                    diagnostics.push(Diagnostic::warning(
+                        "unused-input-port",
+                        location,
+                        format!("input port {} ({}) is not connected", port.port, port.label),
+                    ));
+                }
+            }
+            for port in &descriptor.outputs {
+                if !is_connected(port.port) {
+                    diagnostics.push(Diagnostic::warning(
+                        "unused-output-port",
+                        location,
+                        format!(
+                            "output port {} ({}) is not connected",
+                            port.port, port.label
+                        ),
+                    ));
+                }
+            }
+            if let Some(port) = node.error_port
+                && !descriptor.has_output(port)
+                && !is_connected(port)
+            {
+                diagnostics.push(Diagnostic::warning(
+                    "unused-error-port",
+                    location,
+                    format!("error port {} is not connected", port),
+                ));
+            }
+        }
+    }
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flow::{FlowBuilder, FlowModBuilder, NodeSpec};
+
+    // source 没有输入端口、两个输出端口，sink 一个输入端口、没有输出端口
+    fn descriptors() -> HashMap<String, NodeDescriptor> {
+        [
+            NodeDescriptor::new("source")
+                .inputs(&[])
+                .outputs(&["ok", "other"]),
+            NodeDescriptor::new("sink").outputs(&[]),
+        ]
+        .into_iter()
+        .map(|d| (d.node_type.clone(), d))
+        .collect()
+    }
+
+    fn codes(diagnostics: &[Diagnostic]) -> Vec<(&'static str, Option<Uuid>)> {
+        diagnostics.iter().map(|d| (d.code, d.node_id)).collect()
+    }
+
+    #[test]
+    fn reports_unnamed_nodes_and_unused_ports() {
+        let mut flow = FlowBuilder::new("main");
+        let source = flow.add(NodeSpec::new("source").error_port(2));
+        let sink = flow.add(NodeSpec::new("sink").name(" "));
+        let idle = flow.add(NodeSpec::new("sink"));
+        let unknown = flow.add(NodeSpec::new("missing"));
+        flow.wire(source, 0, sink, 0).wire(unknown, 0, idle, 0);
+        let mut off = FlowBuilder::new("off").disabled(true);
+        off.add(NodeSpec::new("sink").name(""));
+        let flow_mod = FlowModBuilder::new()
+            .flow(flow.build())
+            .flow(off.build())
+            .build();
+
+        let diagnostics = lint_flow(&flow_mod, &descriptors());
+        // 停用的 flow 和未注册的类型不检查，来自未注册类型节点的连线也算作已连接
+        assert_eq!(
+            codes(&diagnostics),
+            [
+                ("unused-output-port", Some(source.id())),
+                ("unused-error-port", Some(source.id())),
+                ("unnamed-node", Some(sink.id())),
+            ]
+        );
+        assert!(diagnostics.iter().all(|d| !d.is_error()));
+        assert!(diagnostics[0].message.contains("output port 1 (other)"));
+        assert!(diagnostics[1].message.contains("error port 2"));
+    }
+
+    #[test]
+    fn unused_input_port() {
+        let mut flow = FlowBuilder::new("main");
+        let sink = flow.add(NodeSpec::new("sink"));
+        let flow_mod = FlowModBuilder::new().flow(flow.build()).build();
+
+        let diagnostics = lint_flow(&flow_mod, &descriptors());
+        assert_eq!(
+            codes(&diagnostics),
+            [("unused-input-port", Some(sink.id()))]
+        );
+        assert!(diagnostics[0].message.contains("input port 0 (input)"));
+    }
+}
@@ -1,5 +1,17 @@
+pub mod builder;
+pub mod compact;
+pub mod format;
+pub mod lint;
 pub mod models;
 pub mod parse;
+pub mod validate;
 
-pub use models::{FlowMod, FlowNode};
-pub use parse::{parse_flow_all_nodes, parse_flow_file, parse_flow_all_node_types, validate_flow};
+pub use builder::{FlowBuilder, FlowModBuilder, NodeRef, NodeSpec};
+pub use compact::{RSFLOW_NAMESPACE, expand_compact, is_compact, stable_uuid, to_compact};
+pub use format::FlowFormat;
+pub use lint::lint_flow;
+pub use models::{Flow, FlowMod, FlowNode, PluginConfig};
+pub use parse::{
+    parse_flow_all_node_types, parse_flow_all_nodes, parse_flow_file, parse_flow_str,
+};
+pub use validate::{Diagnostic, Severity, validate_flow, validate_node_types};
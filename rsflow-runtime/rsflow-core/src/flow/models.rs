@@ -1,41 +1,70 @@
-use crate::core::{EngineConfig, Value};
-use serde::Deserialize;
+use crate::core::{EngineConfig, RetryPolicy, Value};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use uuid::Uuid;
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct FlowMod {
     pub config: EngineConfig,
+    #[serde(default)]
+    pub plugin_config: PluginConfig,
     pub node_global_config: Value,
     pub flow: Vec<Flow>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+impl FlowMod {
+    /// 未停用的 flow
+    pub fn enabled_flows(&self) -> impl Iterator<Item = &Flow> {
+        self.flow.iter().filter(|flow| !flow.disabled)
+    }
+}
+
+/// 插件配置，按插件 name() 分组
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
+#[serde(transparent)]
+pub struct PluginConfig(pub HashMap<String, Value>);
+
+impl PluginConfig {
+    /// 获取某个插件的配置子树，未配置时返回 Value::NULL
+    pub fn get(&self, name: &str) -> Value {
+        self.0.get(name).cloned().unwrap_or(Value::NULL)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &String> {
+        self.0.keys()
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct Flow {
     pub id: Uuid,
     pub name: String,
     pub description: String,
+    /// 停用的 flow 不创建节点
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub disabled: bool,
     pub nodes: Vec<FlowNode>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct FlowNodeInputPort {
     pub port: u8,
     pub nodes: Vec<Uuid>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct FlowNodeOutputPortItem {
     pub id: Uuid,
     pub port: u8,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct FlowNodeOutputPort {
     pub port: u8,
     pub nodes: Vec<FlowNodeOutputPortItem>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct FlowNode {
     pub id: Uuid,
     pub name: String,
@@ -44,4 +73,10 @@ pub struct FlowNode {
     pub config: Value,
     pub input: Vec<FlowNodeInputPort>,
     pub output: Vec<FlowNodeOutputPort>,
+    /// 错误输出端口：节点出错时 { error, msg } （见 error_value）从该端口输出，连线在 output 中声明
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_port: Option<u8>,
+    /// 重试策略，作用于 Node::input
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry: Option<RetryPolicy>,
 }
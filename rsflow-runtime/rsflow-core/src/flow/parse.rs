@@ -1,25 +1,26 @@
-use crate::flow::{FlowMod, FlowNode};
+use crate::flow::{FlowFormat, FlowMod, FlowNode};
 use std::collections::HashSet;
-use std::fs::File;
-use std::io::{self, BufReader};
+use std::io;
+use std::path::Path;
 
-// 解析flow.json文件
+// 解析 flow 文件，格式按扩展名选择（JSON / YAML / TOML）
 pub fn parse_flow_file(file_path: &str) -> Result<FlowMod, io::Error> {
-    let file = File::open(file_path)?;
-    let reader = BufReader::new(file);
-    let flow_mod = serde_json::from_reader(reader).map_err(|e| {
-        io::Error::new(
-            io::ErrorKind::InvalidData,
-            format!("JSON parse error: {}", e),
-        )
-    })?;
-    Ok(flow_mod)
+    let content = std::fs::read_to_string(file_path)?;
+    FlowFormat::from_path(Path::new(file_path)).parse(&content)
 }
 
-// 获取所有节点
+// 解析 flow.json 格式的字符串
+pub fn parse_flow_str(json: &str) -> Result<FlowMod, io::Error> {
+    FlowFormat::Json.parse(json)
+}
+
+// 获取所有节点（不含停用的 flow）
 pub fn parse_flow_all_nodes(flow_mod: FlowMod) -> Vec<FlowNode> {
     let mut nodes = Vec::new();
     for flow_item in flow_mod.flow {
+        if flow_item.disabled {
+            continue;
+        }
         nodes.extend(flow_item.nodes);
     }
     nodes
@@ -29,35 +30,3 @@ pub fn parse_flow_all_nodes(flow_mod: FlowMod) -> Vec<FlowNode> {
 pub fn parse_flow_all_node_types(rsflow_nodes: Vec<FlowNode>) -> HashSet<String> {
     rsflow_nodes.into_iter().map(|node| node.node_type).collect()
 }
-
-// 验证flow配置
-pub fn validate_flow(flow_mod: &FlowMod) -> Result<(), String> {
-    // 检查是否有重复节点ID
-    let mut node_ids = HashSet::new();
-    for flow in &flow_mod.flow {
-        for node in &flow.nodes {
-            if !node_ids.insert(node.id) {
-                return Err(format!("Duplicate node ID found: {}", node.id));
-            }
-        }
-    }
-
-    // 检查输出连接的节点是否存在
-    let all_node_ids: HashSet<_> = node_ids;
-    for flow in &flow_mod.flow {
-        for node in &flow.nodes {
-            for output in &node.output {
-                for next_item in &output.nodes {
-                    if !all_node_ids.contains(&next_item.id) {
-                        return Err(format!(
-                            "Node {} references non-existent node {}",
-                            node.id, next_item.id
-                        ));
-                    }
-                }
-            }
-        }
-    }
-
-    Ok(())
-}
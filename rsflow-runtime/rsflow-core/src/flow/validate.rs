@@ -0,0 +1,603 @@
+use crate::core::{DELAY_NODE_TYPE, NodeDescriptor};
+use crate::flow::{Flow, FlowMod, FlowNode};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use uuid::Uuid;
+
+/// 诊断级别：error 使校验失败，warning 只提示
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// flow 校验的一条诊断
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// 检查项，如 dangling-output、unknown-node-type
+    pub code: &'static str,
+    pub flow_id: Option<Uuid>,
+    pub node_id: Option<Uuid>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn new(
+        severity: Severity,
+        code: &'static str,
+        location: Option<(&Flow, &FlowNode)>,
+        message: String,
+    ) -> Self {
+        match location {
+            Some((flow, node)) => Self {
+                severity,
+                code,
+                flow_id: Some(flow.id),
+                node_id: Some(node.id),
+                message: format!(
+                    "flow \"{}\" node \"{}\" ({}): {}",
+                    flow.name, node.name, node.id, message
+                ),
+            },
+            None => Self {
+                severity,
+                code,
+                flow_id: None,
+                node_id: None,
+                message,
+            },
+        }
+    }
+
+    pub fn error(
+        code: &'static str,
+        location: Option<(&Flow, &FlowNode)>,
+        message: String,
+    ) -> Self {
+        Self::new(Severity::Error, code, location, message)
+    }
+
+    pub fn warning(
+        code: &'static str,
+        location: Option<(&Flow, &FlowNode)>,
+        message: String,
+    ) -> Self {
+        Self::new(Severity::Warning, code, location, message)
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, "{}[{}]: {}", severity, self.code, self.message)
+    }
+}
+
+// 验证flow配置：节点 id、连线、重试策略等不依赖节点类型的检查
+pub fn validate_flow(flow_mod: &FlowMod) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    // 检查是否有重复节点ID
+    let mut nodes: HashMap<Uuid, &FlowNode> = HashMap::new();
+    for flow in &flow_mod.flow {
+        for node in &flow.nodes {
+            if nodes.insert(node.id, node).is_some() {
+                diagnostics.push(Diagnostic::error(
+                    "duplicate-node-id",
+                    Some((flow, node)),
+                    "duplicate node id".to_string(),
+                ));
+            }
+        }
+    }
+
+    if let Err(e) = flow_mod.config.debug.validate() {
+        diagnostics.push(Diagnostic::error(
+            "invalid-debug",
+            None,
+            format!("invalid config.debug: {}", e),
+        ));
+    }
+
+    for flow in &flow_mod.flow {
+        for node in &flow.nodes {
+            // 检查重试策略
+            if let Some(retry) = &node.retry
+                && let Err(e) = retry.validate()
+            {
+                diagnostics.push(Diagnostic::error(
+                    "invalid-retry",
+                    Some((flow, node)),
+                    format!("invalid retry: {}", e),
+                ));
+            }
+
+            // 检查输出连接的节点是否存在，以及对方是否声明了对应的输入
+            for output in &node.output {
+                for next_item in &output.nodes {
+                    let Some(next) = nodes.get(&next_item.id) else {
+                        diagnostics.push(Diagnostic::error(
+                            "dangling-output",
+                            Some((flow, node)),
+                            format!(
+                                "output port {} references non-existent node {}",
+                                output.port, next_item.id
+                            ),
+                        ));
+                        continue;
+                    };
+                    let declared = next.input.iter().any(|input| {
+                        input.port == next_item.port && input.nodes.contains(&node.id)
+                    });
+                    if !declared {
+                        diagnostics.push(Diagnostic::warning(
+                            "input-output-mismatch",
+                            Some((flow, node)),
+                            format!(
+                                "output port {} connects to \"{}\" port {}, which does not list it as input",
+                                output.port, next.name, next_item.port
+                            ),
+                        ));
+                    }
+                }
+            }
+
+            // 检查输入来源的节点是否存在，以及对方是否声明了对应的输出
+            for input in &node.input {
+                for prev_id in &input.nodes {
+                    let Some(prev) = nodes.get(prev_id) else {
+                        diagnostics.push(Diagnostic::error(
+                            "dangling-input",
+                            Some((flow, node)),
+                            format!(
+                                "input port {} references non-existent node {}",
+                                input.port, prev_id
+                            ),
+                        ));
+                        continue;
+                    };
+                    let declared = prev.output.iter().any(|output| {
+                        output
+                            .nodes
+                            .iter()
+                            .any(|item| item.id == node.id && item.port == input.port)
+                    });
+                    if !declared {
+                        diagnostics.push(Diagnostic::warning(
+                            "input-output-mismatch",
+                            Some((flow, node)),
+                            format!(
+                                "input port {} lists \"{}\", which has no output connected to it",
+                                input.port, prev.name
+                            ),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    diagnostics
+}
+
+/// 依赖节点类型说明的检查（只检查未停用的 flow）：
+/// 未注册的节点类型、节点类型没有的端口、不可达的节点、没有延迟节点的环路
+pub fn validate_node_types(
+    flow_mod: &FlowMod,
+    descriptors: &HashMap<String, NodeDescriptor>,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let nodes: HashMap<Uuid, (&Flow, &FlowNode)> = flow_mod
+        .enabled_flows()
+        .flat_map(|flow| flow.nodes.iter().map(move |node| (node.id, (flow, node))))
+        .collect();
+
+    for (flow, node) in nodes.values() {
+        let location = Some((*flow, *node));
+        let Some(descriptor) = descriptors.get(&node.node_type) else {
+            diagnostics.push(Diagnostic::error(
+                "unknown-node-type",
+                location,
+                format!(
+                    "node type \"{}\" is not registered (missing builder)",
+                    node.node_type
+                ),
+            ));
+            continue;
+        };
+        for output in &node.output {
+            // 错误端口是节点类型之外额外的输出端口
+            if !descriptor.has_output(output.port) && node.error_port != Some(output.port) {
+                diagnostics.push(Diagnostic::error(
+                    "unknown-output-port",
+                    location,
+                    format!(
+                        "node type \"{}\" has no output port {}",
+                        node.node_type, output.port
+                    ),
+                ));
+            }
+            for item in &output.nodes {
+                let Some((_, next)) = nodes.get(&item.id) else {
+                    continue;
+                };
+                let Some(next_descriptor) = descriptors.get(&next.node_type) else {
+                    continue;
+                };
+                if !next_descriptor.inputs.iter().any(|p| p.port == item.port) {
+                    diagnostics.push(Diagnostic::error(
+                        "unknown-input-port",
+                        location,
+                        format!(
+                            "output port {} connects to \"{}\" port {}, but node type \"{}\" has no such input port",
+                            output.port, next.name, item.port, next.node_type
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    let edges = edges(&nodes);
+    check_reachable(&nodes, &edges, descriptors, &mut diagnostics);
+    check_cycles(&nodes, &edges, &mut diagnostics);
+
+    // 按 flow、节点的声明顺序输出
+    let order: HashMap<Uuid, usize> = flow_mod
+        .enabled_flows()
+        .flat_map(|flow| flow.nodes.iter())
+        .enumerate()
+        .map(|(i, node)| (node.id, i))
+        .collect();
+    diagnostics.sort_by_key(|d| d.node_id.and_then(|id| order.get(&id).copied()));
+    diagnostics
+}
+
+type Nodes<'a> = HashMap<Uuid, (&'a Flow, &'a FlowNode)>;
+
+// 节点 -> 输出连接的节点（只含存在的节点）
+fn edges(nodes: &Nodes) -> HashMap<Uuid, Vec<Uuid>> {
+    nodes
+        .iter()
+        .map(|(id, (_, node))| {
+            let mut next: Vec<Uuid> = node
+                .output
+                .iter()
+                .flat_map(|output| output.nodes.iter().map(|item| item.id))
+                .filter(|id| nodes.contains_key(id))
+                .collect();
+            next.sort();
+            next.dedup();
+            (*id, next)
+        })
+        .collect()
+}
+
+// 没有输入端口的节点（inject、http in、catch 等）是起点，从起点沿输出无法到达的节点永远不会执行
+fn check_reachable(
+    nodes: &Nodes,
+    edges: &HashMap<Uuid, Vec<Uuid>>,
+    descriptors: &HashMap<String, NodeDescriptor>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let mut reached: HashSet<Uuid> = HashSet::new();
+    let mut stack: Vec<Uuid> = nodes
+        .iter()
+        .filter(|(_, (_, node))| {
+            // 未注册的类型已单独报告，不再视为不可达
+            descriptors
+                .get(&node.node_type)
+                .is_none_or(|d| d.inputs.is_empty())
+        })
+        .map(|(id, _)| *id)
+        .collect();
+    while let Some(id) = stack.pop() {
+        if reached.insert(id) {
+            stack.extend(edges[&id].iter().copied());
+        }
+    }
+    for (id, (flow, node)) in nodes {
+        if !reached.contains(id) {
+            diagnostics.push(Diagnostic::warning(
+                "unreachable-node",
+                Some((flow, node)),
+                "not reachable from any source node, it will never run".to_string(),
+            ));
+        }
+    }
+}
+
+// 环路中没有延迟节点时消息可能无限循环，每个环路（强连通分量）报告一次
+fn check_cycles(
+    nodes: &Nodes,
+    edges: &HashMap<Uuid, Vec<Uuid>>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for component in strongly_connected(edges) {
+        let is_cycle = component.len() > 1 || edges[&component[0]].contains(&component[0]);
+        if !is_cycle
+            || component
+                .iter()
+                .any(|id| nodes[id].1.node_type == DELAY_NODE_TYPE)
+        {
+            continue;
+        }
+        let (flow, node) = nodes[&component[0]];
+        let names: Vec<String> = component
+            .iter()
+            .map(|id| format!("\"{}\"", nodes[id].1.name))
+            .collect();
+        diagnostics.push(Diagnostic::warning(
+            "cycle-without-delay",
+            Some((flow, node)),
+            format!(
+                "cycle through {} has no {} node and may loop forever",
+                names.join(", "),
+                DELAY_NODE_TYPE
+            ),
+        ));
+    }
+}
+
+// Tarjan 算法求强连通分量，分量内按节点 id 排序
+fn strongly_connected(edges: &HashMap<Uuid, Vec<Uuid>>) -> Vec<Vec<Uuid>> {
+    struct State<'a> {
+        edges: &'a HashMap<Uuid, Vec<Uuid>>,
+        index: HashMap<Uuid, usize>,
+        low: HashMap<Uuid, usize>,
+        stack: Vec<Uuid>,
+        on_stack: HashSet<Uuid>,
+        components: Vec<Vec<Uuid>>,
+    }
+
+    fn visit(state: &mut State, id: Uuid) {
+        let index = state.index.len();
+        state.index.insert(id, index);
+        state.low.insert(id, index);
+        state.stack.push(id);
+        state.on_stack.insert(id);
+        for next in &state.edges[&id] {
+            if !state.index.contains_key(next) {
+                visit(state, *next);
+                let low = state.low[&id].min(state.low[next]);
+                state.low.insert(id, low);
+            } else if state.on_stack.contains(next) {
+                let low = state.low[&id].min(state.index[next]);
+                state.low.insert(id, low);
+            }
+        }
+        if state.low[&id] == state.index[&id] {
+            let mut component = Vec::new();
+            while let Some(member) = state.stack.pop() {
+                state.on_stack.remove(&member);
+                component.push(member);
+                if member == id {
+                    break;
+                }
+            }
+            component.sort();
+            state.components.push(component);
+        }
+    }
+
+    let mut ids: Vec<Uuid> = edges.keys().copied().collect();
+    ids.sort();
+    let mut state = State {
+        edges,
+        index: HashMap::new(),
+        low: HashMap::new(),
+        stack: Vec::new(),
+        on_stack: HashSet::new(),
+        components: Vec::new(),
+    };
+    for id in ids {
+        if !state.index.contains_key(&id) {
+            visit(&mut state, id);
+        }
+    }
+    state.components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flow::models::FlowNodeInputPort;
+    use crate::flow::{FlowBuilder, FlowModBuilder, NodeRef, NodeSpec};
+
+    // source 没有输入端口，pass 和 delay 各一个输入、一个输出端口
+    fn descriptors() -> HashMap<String, NodeDescriptor> {
+        [
+            NodeDescriptor::new("source").inputs(&[]),
+            NodeDescriptor::new("pass"),
+            NodeDescriptor::new(DELAY_NODE_TYPE),
+        ]
+        .into_iter()
+        .map(|d| (d.node_type.clone(), d))
+        .collect()
+    }
+
+    fn codes(diagnostics: &[Diagnostic]) -> Vec<(&'static str, Option<Uuid>)> {
+        diagnostics.iter().map(|d| (d.code, d.node_id)).collect()
+    }
+
+    fn check_types(flow: FlowBuilder) -> Vec<Diagnostic> {
+        let flow_mod = FlowModBuilder::new().flow(flow.build()).build();
+        validate_node_types(&flow_mod, &descriptors())
+    }
+
+    // source -> a -> b，返回 (flow, a, b)
+    fn chain(name: &str, node_type: &str) -> (FlowBuilder, NodeRef, NodeRef) {
+        let mut flow = FlowBuilder::new(name);
+        let source = flow.add(NodeSpec::new("source"));
+        let a = flow.add(NodeSpec::new("pass").name("a"));
+        let b = flow.add(NodeSpec::new(node_type).name("b"));
+        flow.wire(source, 0, a, 0).wire(a, 0, b, 0);
+        (flow, a, b)
+    }
+
+    #[test]
+    fn wired_flows_are_valid() {
+        let (main, _, _) = chain("main", "pass");
+        let flow_mod = FlowModBuilder::new().flow(main.build()).build();
+        assert_eq!(validate_flow(&flow_mod), []);
+        assert_eq!(validate_node_types(&flow_mod, &descriptors()), []);
+    }
+
+    #[test]
+    fn dangling_input_and_output() {
+        let missing = Uuid::new_v4();
+        let mut flow = FlowBuilder::new("main");
+        let source = flow.add(NodeSpec::new("source"));
+        let pass = flow.add(NodeSpec::new("pass"));
+        flow.wire(source, 0, pass, 0);
+        let mut flow = flow.build();
+        flow.nodes[1].input.push(FlowNodeInputPort {
+            port: 1,
+            nodes: vec![missing],
+        });
+        flow.nodes[0].output[0].nodes[0].id = missing;
+        let flow_mod = FlowModBuilder::new().flow(flow).build();
+
+        let diagnostics = validate_flow(&flow_mod);
+        assert_eq!(
+            codes(&diagnostics),
+            [
+                ("dangling-output", Some(source.id())),
+                // pass 的 0 号输入仍列出 source，但 source 已不再连接它
+                ("input-output-mismatch", Some(pass.id())),
+                ("dangling-input", Some(pass.id())),
+            ]
+        );
+        assert!(!diagnostics[1].is_error());
+        assert!(diagnostics[2].is_error());
+        assert!(diagnostics[2].message.contains(&missing.to_string()));
+    }
+
+    #[test]
+    fn input_output_mismatch() {
+        let (flow, a, _) = chain("main", "pass");
+        let mut flow = flow.build();
+        // b 不再列出 a 作为输入
+        flow.nodes[2].input.clear();
+        let flow_mod = FlowModBuilder::new().flow(flow).build();
+
+        let diagnostics = validate_flow(&flow_mod);
+        assert_eq!(
+            codes(&diagnostics),
+            [("input-output-mismatch", Some(a.id()))]
+        );
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert!(diagnostics[0].message.contains("\"b\" port 0"));
+    }
+
+    #[test]
+    fn unknown_ports() {
+        let mut flow = FlowBuilder::new("main");
+        let source = flow.add(NodeSpec::new("source"));
+        let pass = flow.add(NodeSpec::new("pass").error_port(2));
+        let log = flow.add(NodeSpec::new("pass"));
+        flow.wire(source, 3, pass, 0)
+            .wire(source, 0, pass, 1)
+            .wire(pass, 0, log, 0)
+            .wire(pass, 2, log, 0);
+
+        let diagnostics = check_types(flow);
+        assert_eq!(
+            codes(&diagnostics),
+            [
+                ("unknown-input-port", Some(source.id())),
+                ("unknown-output-port", Some(source.id())),
+            ]
+        );
+        assert!(diagnostics.iter().all(Diagnostic::is_error));
+        assert!(diagnostics[0].message.contains("has no such input port"));
+        assert!(diagnostics[1].message.contains("has no output port 3"));
+    }
+
+    #[test]
+    fn unreachable_node() {
+        let (mut flow, _, _) = chain("main", "pass");
+        let orphan = flow.add(NodeSpec::new("pass").name("orphan"));
+        let unknown = flow.add(NodeSpec::new("missing"));
+        flow.wire(unknown, 0, orphan, 0);
+
+        let diagnostics = check_types(flow);
+        // 未注册的类型视为起点，只报告 unknown-node-type
+        assert_eq!(
+            codes(&diagnostics),
+            [("unknown-node-type", Some(unknown.id()))]
+        );
+
+        let (mut flow, _, _) = chain("main", "pass");
+        let orphan = flow.add(NodeSpec::new("pass").name("orphan"));
+        let diagnostics = check_types(flow);
+        assert_eq!(
+            codes(&diagnostics),
+            [("unreachable-node", Some(orphan.id()))]
+        );
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn cycle_without_delay() {
+        let (mut flow, a, b) = chain("main", "pass");
+        flow.wire(b, 0, a, 0);
+        let diagnostics = check_types(flow);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "cycle-without-delay");
+        assert!(diagnostics[0].message.contains("\"a\""));
+        assert!(diagnostics[0].message.contains("\"b\""));
+
+        // 自环
+        let mut flow = FlowBuilder::new("main");
+        let source = flow.add(NodeSpec::new("source"));
+        let pass = flow.add(NodeSpec::new("pass"));
+        flow.wire(source, 0, pass, 0).wire(pass, 0, pass, 0);
+        assert_eq!(
+            codes(&check_types(flow)),
+            [("cycle-without-delay", Some(pass.id()))]
+        );
+    }
+
+    #[test]
+    fn cycle_with_delay() {
+        let (mut flow, a, delay) = chain("main", DELAY_NODE_TYPE);
+        flow.wire(delay, 0, a, 0);
+        assert_eq!(check_types(flow), []);
+    }
+
+    #[test]
+    fn unknown_node_type() {
+        let mut flow = FlowBuilder::new("main");
+        let unknown = flow.add(NodeSpec::new("missing"));
+        let mut disabled = FlowBuilder::new("off").disabled(true);
+        disabled.add(NodeSpec::new("missing"));
+        let flow_mod = FlowModBuilder::new()
+            .flow(flow.build())
+            .flow(disabled.build())
+            .build();
+
+        // 停用的 flow 不检查
+        let diagnostics = validate_node_types(&flow_mod, &descriptors());
+        assert_eq!(
+            codes(&diagnostics),
+            [("unknown-node-type", Some(unknown.id()))]
+        );
+        assert!(diagnostics[0].is_error());
+        assert!(
+            diagnostics[0]
+                .to_string()
+                .starts_with("error[unknown-node-type]: flow \"main\" node \"missing\"")
+        );
+    }
+}
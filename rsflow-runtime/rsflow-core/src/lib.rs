@@ -3,9 +3,16 @@ pub mod engine;
 pub mod flow;
 
 pub use crate::engine::*;
+pub use crate::core::debug::*;
+pub use crate::core::descriptor::*;
 pub use crate::core::flow::*;
 pub use crate::core::message::*;
+pub use crate::core::metrics::*;
 pub use crate::core::node::*;
 pub use crate::core::sender::*;
 pub use crate::core::value::*;
-pub use crate::core::payload::*;
\ No newline at end of file
+pub use crate::core::payload::*;
+pub use crate::core::retry::*;
+pub use crate::core::run::*;
+pub use crate::core::stream::*;
+pub use crate::core::table::*;
\ No newline at end of file
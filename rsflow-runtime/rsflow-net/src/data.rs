@@ -0,0 +1,62 @@
+use crate::http::config_str;
+use rsflow_core::{NodeError, Value};
+use serde_json::json;
+
+/// 入站数据的表示方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Encoding {
+    /// UTF-8 字符串（非法字节按 lossy 处理）
+    Utf8,
+    /// 字节数组
+    Bytes,
+}
+
+impl Encoding {
+    /// 从节点配置 encoding 读取：utf8（默认）或 bytes
+    pub fn from_config(config: &Value) -> Result<Self, NodeError> {
+        match config_str(config, "encoding") {
+            None | Some("utf8") => Ok(Encoding::Utf8),
+            Some("bytes") => Ok(Encoding::Bytes),
+            Some(other) => Err(NodeError::InvalidConfig(format!(
+                "unknown encoding: {}",
+                other
+            ))),
+        }
+    }
+
+    /// encoding 字段的 JSON Schema
+    pub(crate) fn schema_properties() -> serde_json::Value {
+        json!({
+            "encoding": {
+                "enum": ["utf8", "bytes"],
+                "default": "utf8",
+                "description": "入站数据输出为字符串或字节数组"
+            }
+        })
+    }
+
+    pub fn decode(&self, bytes: Vec<u8>) -> Value {
+        match self {
+            Encoding::Utf8 => Value::String(String::from_utf8_lossy(&bytes).to_string()),
+            Encoding::Bytes => bytes_to_value(bytes),
+        }
+    }
+}
+
+// 字节转为字节数组
+pub(crate) fn bytes_to_value(bytes: Vec<u8>) -> Value {
+    Value::from_bytes(bytes)
+}
+
+/// 出站值转为字节，规则见 Value::to_bytes
+pub fn value_to_bytes(val: &Value) -> Vec<u8> {
+    val.to_bytes()
+}
+
+/// 取出要发送的数据：对象且包含 data 时取 data，否则取整个值
+pub fn payload_data(val: &Value) -> &Value {
+    match val {
+        Value::Object(map) => map.get("data").unwrap_or(val),
+        other => other,
+    }
+}
@@ -0,0 +1,227 @@
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, put};
+use axum::{Json, Router};
+use rsflow_core::EngineContext;
+use serde::Deserialize;
+use std::convert::Infallible;
+use tokio::sync::broadcast::error::RecvError;
+use uuid::Uuid;
+
+/// 调试接口，挂载在 /debug 下
+///
+/// - `GET /debug/events?flow=<flow_id>`：以 SSE 推送节点执行记录，可按 flow 过滤
+/// - `GET /debug/config` / `PUT /debug/config`：查看、修改记录开关和采样率
+/// - `PUT /debug/flows/:flow_id` / `DELETE /debug/flows/:flow_id`：开启、关闭单个 flow 的记录，优先于 enabled
+pub fn debug_routes<S>(engine: EngineContext) -> Router<S> {
+    Router::new()
+        .route("/debug/events", get(events))
+        .route("/debug/config", get(get_config).put(put_config))
+        .route(
+            "/debug/flows/:flow_id",
+            put(enable_flow).delete(disable_flow),
+        )
+        .with_state(engine)
+}
+
+#[derive(Debug, Deserialize)]
+struct EventsQuery {
+    flow: Option<Uuid>,
+}
+
+async fn events(State(engine): State<EngineContext>, Query(query): Query<EventsQuery>) -> Response {
+    let rx = engine.debug.subscribe();
+    let stream = futures_util::stream::unfold(rx, move |mut rx| async move {
+        loop {
+            match rx.recv().await {
+                Ok(event) if query.flow.is_none_or(|flow| flow == event.flow_id) => {
+                    let event = Event::default()
+                        .event("trace")
+                        .json_data(&event)
+                        .unwrap_or_else(|_| Event::default().event("trace"));
+                    return Some((Ok::<_, Infallible>(event), rx));
+                }
+                Ok(_) => continue,
+                // 订阅者处理不及时，告知丢失的记录数
+                Err(RecvError::Lagged(skipped)) => {
+                    let event = Event::default().event("lagged").data(skipped.to_string());
+                    return Some((Ok(event), rx));
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+    Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+async fn get_config(State(engine): State<EngineContext>) -> Response {
+    Json(engine.debug.config()).into_response()
+}
+
+#[derive(Debug, Deserialize)]
+struct ConfigUpdate {
+    enabled: Option<bool>,
+    sample_rate: Option<f64>,
+}
+
+async fn put_config(
+    State(engine): State<EngineContext>,
+    Json(update): Json<ConfigUpdate>,
+) -> Response {
+    if let Some(sample_rate) = update.sample_rate
+        && let Err(e) = engine.debug.set_sample_rate(sample_rate)
+    {
+        return (StatusCode::BAD_REQUEST, e).into_response();
+    }
+    if let Some(enabled) = update.enabled {
+        engine.debug.set_enabled(enabled);
+    }
+    Json(engine.debug.config()).into_response()
+}
+
+async fn enable_flow(State(engine): State<EngineContext>, Path(flow_id): Path<Uuid>) -> Response {
+    engine.debug.set_flow(flow_id, true);
+    Json(engine.debug.config()).into_response()
+}
+
+async fn disable_flow(State(engine): State<EngineContext>, Path(flow_id): Path<Uuid>) -> Response {
+    engine.debug.set_flow(flow_id, false);
+    Json(engine.debug.config()).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{
+        FnNodeBuilder, connect, engine_builder, free_port, http_request, start_at, start_engine,
+        value,
+    };
+    use rsflow_core::engine::EngineHandle;
+    use rsflow_core::flow::{FlowBuilder, FlowModBuilder, NodeRef, NodeSpec};
+    use rsflow_core::{NodeOutput, Value};
+    use serde_json::json;
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    // 两个 flow，各有一个 pass 节点
+    async fn debug_server() -> (u16, EngineHandle, [(Uuid, NodeRef); 2]) {
+        let port = free_port();
+        let mut nodes = Vec::new();
+        let mut builder = FlowModBuilder::new().plugin_config(
+            "net",
+            value(json!({ "http": { "http_port": port, "host": "127.0.0.1", "debug": true } })),
+        );
+        for name in ["main", "other"] {
+            let flow_id = Uuid::new_v4();
+            let mut flow = FlowBuilder::new(name).id(flow_id);
+            nodes.push((flow_id, flow.add(NodeSpec::new("pass"))));
+            builder = builder.flow(flow.build());
+        }
+        let pass = FnNodeBuilder::new("pass", |msg, _| Ok(NodeOutput::One((0, msg))));
+        let (handle, _) = start_engine(engine_builder().register_node(pass), builder.build()).await;
+        (port, handle, [nodes[0], nodes[1]])
+    }
+
+    async fn json_request(port: u16, method: &str, path: &str, body: &str) -> serde_json::Value {
+        let reply = http_request(
+            port,
+            method,
+            path,
+            &[("Content-Type", "application/json")],
+            body,
+        )
+        .await;
+        assert_eq!(
+            reply.status,
+            200,
+            "{}",
+            String::from_utf8_lossy(&reply.body)
+        );
+        serde_json::from_slice(&reply.body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn flow_switch_overrides_enabled() {
+        let (port, handle, [(flow_id, _), _]) = debug_server().await;
+        let path = format!("/debug/flows/{}", flow_id);
+
+        let config = json_request(port, "PUT", "/debug/config", r#"{"enabled":true}"#).await;
+        assert_eq!(config["enabled"], json!(true));
+        // 全部开启时仍可单独关闭
+        let config = json_request(port, "DELETE", &path, "").await;
+        assert_eq!(config["flows"], json!([]));
+        assert_eq!(config["disabled_flows"], json!([flow_id]));
+        let debug = &handle.context().debug;
+        let _rx = debug.subscribe();
+        assert!(!debug.is_traced(flow_id, Uuid::new_v4()));
+
+        let config = json_request(port, "PUT", &path, "").await;
+        assert_eq!(config["flows"], json!([flow_id]));
+        assert_eq!(config["disabled_flows"], json!([]));
+        assert!(debug.is_traced(flow_id, Uuid::new_v4()));
+
+        let reply = http_request(
+            port,
+            "PUT",
+            "/debug/config",
+            &[("Content-Type", "application/json")],
+            r#"{"sample_rate":2}"#,
+        )
+        .await;
+        assert_eq!(reply.status, 400);
+        handle.shutdown();
+    }
+
+    #[tokio::test]
+    async fn events_stream_filtered_by_flow() {
+        let (port, handle, [(flow_id, main), (_, other)]) = debug_server().await;
+        json_request(port, "PUT", "/debug/config", r#"{"enabled":true}"#).await;
+
+        let mut stream = connect(port).await;
+        let request = format!(
+            "GET /debug/events?flow={} HTTP/1.1\r\nHost: localhost\r\n\r\n",
+            flow_id
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+
+        // 订阅建立之前的运行不会被记录，持续运行两个 flow 直到收到记录
+        let ctx = handle.context();
+        let read = async {
+            let mut received = String::new();
+            let mut buf = [0u8; 4096];
+            loop {
+                tokio::select! {
+                    n = stream.read(&mut buf) => {
+                        received.push_str(&String::from_utf8_lossy(&buf[..n.unwrap()]));
+                        if let Some(start) = received.find("event:trace\ndata:") {
+                            let data = &received[start + "event:trace\ndata:".len()..];
+                            if let Some(end) = data.find('\n') {
+                                return (received.clone(), data[..end].to_string());
+                            }
+                        }
+                    }
+                    _ = tokio::time::sleep(Duration::from_millis(20)) => {
+                        ctx.run_flow(start_at(other.id(), Value::Int(1))).await;
+                        ctx.run_flow(start_at(main.id(), Value::Int(2))).await;
+                    }
+                }
+            }
+        };
+        let (head, data) = tokio::time::timeout(Duration::from_secs(5), read)
+            .await
+            .expect("no trace event");
+        assert!(head.starts_with("HTTP/1.1 200"));
+        assert!(head.contains("text/event-stream"));
+
+        let event: serde_json::Value = serde_json::from_str(&data).unwrap();
+        assert_eq!(event["flow_id"], json!(flow_id));
+        assert_eq!(event["node_id"], json!(main.id()));
+        assert_eq!(event["input"], json!("2"));
+        assert_eq!(event["output_ports"], json!([0]));
+        handle.shutdown();
+    }
+}
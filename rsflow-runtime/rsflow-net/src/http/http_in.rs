@@ -0,0 +1,128 @@
+use crate::http::server::{HttpRoute, HttpServer};
+use crate::http::{config_schema, config_str, config_u64};
+use rsflow_core::{
+    EngineContext, FlowContext, Node, NodeBuilder, NodeDescriptor, NodeError, NodeFactory,
+    NodeInfo, NodeInput, NodeOutput, Payload, Value,
+};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// http in 节点：每个请求启动一次 flow
+///
+/// 输出 Payload 的值为 { req_id, method, path, query, headers, body }，
+/// 下游需要保留 req_id 才能由 http response 节点完成响应。
+pub struct HttpInNode {
+    info: NodeInfo,
+    server: Arc<HttpServer>,
+    method: String,
+    url: String,
+    timeout: Duration,
+}
+
+#[async_trait::async_trait]
+impl Node for HttpInNode {
+    fn info(&self) -> NodeInfo {
+        self.info.clone()
+    }
+
+    async fn engine_start(&self, sender: EngineContext) {
+        self.server.add_route(
+            &self.method,
+            &self.url,
+            HttpRoute {
+                node_id: self.info.id,
+                engine: sender,
+                timeout: self.timeout,
+            },
+        );
+    }
+
+    async fn engine_stop(&self) {
+        self.server
+            .remove_route(&self.method, &self.url, self.info.id);
+    }
+
+    async fn event(&self, _: &str, _: Payload, _: &FlowContext) -> Result<(), NodeError> {
+        Ok(())
+    }
+
+    async fn input(&self, node_input: NodeInput, _: &FlowContext) -> Result<NodeOutput, NodeError> {
+        Ok(NodeOutput::One((0, node_input.msg)))
+    }
+}
+
+// NodeFactory
+pub struct HttpInNodeFactory {
+    server: Arc<HttpServer>,
+}
+
+#[async_trait::async_trait]
+impl NodeFactory for HttpInNodeFactory {
+    async fn create(&self, node_info: NodeInfo) -> Result<Arc<dyn Node + Send + Sync>, NodeError> {
+        let url = config_str(&node_info.config, "url")
+            .ok_or_else(|| NodeError::InvalidConfig("http in requires config.url".to_string()))?;
+        if !url.starts_with('/') {
+            return Err(NodeError::InvalidConfig(format!(
+                "http in url must start with '/': {}",
+                url
+            )));
+        }
+        let url = url.to_string();
+        let method = config_str(&node_info.config, "method")
+            .unwrap_or("GET")
+            .to_uppercase();
+        // 等待 http response 的超时时间，默认30秒
+        let timeout = config_u64(&node_info.config, "timeout").unwrap_or(30);
+
+        Ok(Arc::new(HttpInNode {
+            info: node_info,
+            server: self.server.clone(),
+            method,
+            url,
+            timeout: Duration::from_secs(timeout),
+        }))
+    }
+}
+
+// NodeBuilder
+pub struct HttpInNodeBuilder {
+    server: Arc<HttpServer>,
+}
+
+impl HttpInNodeBuilder {
+    pub fn new(server: Arc<HttpServer>) -> Self {
+        Self { server }
+    }
+}
+
+#[async_trait::async_trait]
+impl NodeBuilder for HttpInNodeBuilder {
+    fn node_type(&self) -> &str {
+        "http in"
+    }
+
+    fn descriptor(&self) -> NodeDescriptor {
+        NodeDescriptor::new(self.node_type())
+            .display_name("HTTP In")
+            .category("network")
+            .icon("GlobalOutlined")
+            .help("为每个匹配的 HTTP 请求启动一次 flow，输出 { req_id, method, path, query, headers, body }，需连接 http response 返回响应。")
+            .inputs(&[])
+            .outputs(&["request"])
+            .config_schema(config_schema(
+                vec![json!({
+                    "url": { "type": "string", "pattern": "^/", "description": "路由路径" },
+                    "method": { "type": "string", "default": "GET" },
+                    "timeout": { "type": "integer", "minimum": 0, "default": 30, "description": "等待 http response 的超时（秒）" }
+                })],
+                &["url"],
+            ))
+    }
+
+    async fn register(&self, _: &Value) -> Result<Box<dyn NodeFactory>, NodeError> {
+        Ok(Box::new(HttpInNodeFactory {
+            server: self.server.clone(),
+        }))
+    }
+}
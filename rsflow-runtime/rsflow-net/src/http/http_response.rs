@@ -0,0 +1,137 @@
+use crate::http::{config_schema, config_u64};
+use rsflow_core::{
+    EngineContext, FlowContext, FlowEventKey, Node, NodeBuilder, NodeDescriptor, NodeError,
+    NodeFactory, NodeInfo, NodeInput, NodeOutput, Payload, Value,
+};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// http response 节点：通过 FlowEventKey::Response(req_id) 完成对应的请求
+///
+/// 输入值需包含 http in 产生的 req_id，可选 status / response_headers / body；
+/// 未提供时使用配置中的 status（默认200）和 headers。
+/// 请求头位于 headers 字段，不会被回写到响应中。
+/// 没有 body 且 Payload 带有 Stream 时，以流的内容作为分块响应体。
+pub struct HttpResponseNode {
+    info: NodeInfo,
+    status: u64,
+    headers: HashMap<String, Value>,
+}
+
+#[async_trait::async_trait]
+impl Node for HttpResponseNode {
+    fn info(&self) -> NodeInfo {
+        self.info.clone()
+    }
+
+    async fn engine_start(&self, _: EngineContext) {}
+
+    async fn event(&self, _: &str, _: Payload, _: &FlowContext) -> Result<(), NodeError> {
+        Ok(())
+    }
+
+    async fn input(&self, node_input: NodeInput, ctx: &FlowContext) -> Result<NodeOutput, NodeError> {
+        let stream = node_input.msg.streams.first().copied();
+        let Value::Object(map) = node_input.msg.value else {
+            return Err(NodeError::InvalidInput(
+                "http response expects an object payload with req_id".to_string(),
+            ));
+        };
+
+        let req_id = match map.get("req_id") {
+            Some(Value::String(s)) => Uuid::parse_str(s)
+                .map_err(|e| NodeError::InvalidInput(format!("invalid req_id {}: {}", s, e)))?,
+            _ => {
+                return Err(NodeError::InvalidInput(
+                    "http response payload has no req_id".to_string(),
+                ));
+            }
+        };
+
+        let status = match map.get("status") {
+            Some(Value::Int(code)) => Value::Int(*code),
+            _ => Value::Int(self.status as i32),
+        };
+        let mut headers = self.headers.clone();
+        if let Some(Value::Object(custom)) = map.get("response_headers") {
+            headers.extend(custom.clone());
+        }
+        let body = map.get("body").cloned().unwrap_or(Value::NULL);
+
+        let mut response = HashMap::new();
+        response.insert("status".to_string(), status);
+        response.insert("headers".to_string(), Value::Object(headers));
+        if let (Value::NULL, Some(stream)) = (&body, stream) {
+            // 流须在发出响应时仍在流表中，由请求处理在回调中取出
+            ctx.get_stream(&stream)?;
+            response.insert("stream".to_string(), Value::String(stream.0.to_string()));
+        }
+        response.insert("body".to_string(), body);
+
+        ctx.listeners
+            .emit(&FlowEventKey::Response(req_id), Value::Object(response))
+            .await;
+        Ok(NodeOutput::None)
+    }
+}
+
+// NodeFactory
+pub struct HttpResponseNodeFactory;
+
+#[async_trait::async_trait]
+impl NodeFactory for HttpResponseNodeFactory {
+    async fn create(&self, node_info: NodeInfo) -> Result<Arc<dyn Node + Send + Sync>, NodeError> {
+        let status = config_u64(&node_info.config, "status").unwrap_or(200);
+        if !(100..=999).contains(&status) {
+            return Err(NodeError::InvalidConfig(format!(
+                "http response status out of range: {}",
+                status
+            )));
+        }
+        let headers = match &node_info.config {
+            Value::Object(map) => match map.get("headers") {
+                Some(Value::Object(headers)) => headers.clone(),
+                _ => HashMap::new(),
+            },
+            _ => HashMap::new(),
+        };
+
+        Ok(Arc::new(HttpResponseNode {
+            info: node_info,
+            status,
+            headers,
+        }))
+    }
+}
+
+// NodeBuilder
+pub struct HttpResponseNodeBuilder;
+
+#[async_trait::async_trait]
+impl NodeBuilder for HttpResponseNodeBuilder {
+    fn node_type(&self) -> &str {
+        "http response"
+    }
+
+    fn descriptor(&self) -> NodeDescriptor {
+        NodeDescriptor::new(self.node_type())
+            .display_name("HTTP Response")
+            .category("network")
+            .icon("GlobalOutlined")
+            .help("返回 http in 请求的响应。输入值需包含 req_id，可带 status / response_headers / body 覆盖配置。")
+            .outputs(&[])
+            .config_schema(config_schema(
+                vec![json!({
+                    "status": { "type": "integer", "minimum": 100, "maximum": 999, "default": 200 },
+                    "headers": { "type": "object", "description": "响应头" }
+                })],
+                &[],
+            ))
+    }
+
+    async fn register(&self, _: &Value) -> Result<Box<dyn NodeFactory>, NodeError> {
+        Ok(Box::new(HttpResponseNodeFactory))
+    }
+}
@@ -0,0 +1,25 @@
+use axum::Router;
+use axum::extract::State;
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use rsflow_core::EngineContext;
+
+/// GET /metrics：Prometheus 格式的引擎指标
+pub fn metrics_routes<S>(engine: EngineContext) -> Router<S> {
+    Router::new()
+        .route("/metrics", get(metrics))
+        .with_state(engine)
+}
+
+// Prometheus 文本格式 0.0.4
+async fn metrics(State(engine): State<EngineContext>) -> Response {
+    (
+        [(
+            header::CONTENT_TYPE,
+            "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        engine.render_metrics(),
+    )
+        .into_response()
+}
@@ -0,0 +1,56 @@
+mod debug;
+mod http_in;
+mod http_response;
+mod metrics;
+mod server;
+
+pub use debug::debug_routes;
+pub use http_in::{HttpInNode, HttpInNodeBuilder, HttpInNodeFactory};
+pub use http_response::{HttpResponseNode, HttpResponseNodeBuilder, HttpResponseNodeFactory};
+pub use metrics::metrics_routes;
+pub use server::{HttpConfig, HttpRoute, HttpServer};
+
+use rsflow_core::Value;
+use serde_json::json;
+
+// 读取配置中的字符串字段
+pub(crate) fn config_str<'a>(config: &'a Value, key: &str) -> Option<&'a str> {
+    match config {
+        Value::Object(map) => match map.get(key) {
+            Some(Value::String(s)) => Some(s.as_str()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// 读取配置中的整数字段
+pub(crate) fn config_u64(config: &Value, key: &str) -> Option<u64> {
+    match config {
+        Value::Object(map) => match map.get(key) {
+            Some(Value::Int(i)) if *i >= 0 => Some(*i as u64),
+            Some(Value::Long(l)) if *l >= 0 => Some(*l as u64),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// 节点配置的 JSON Schema：合并各组字段，不允许未列出的字段
+pub(crate) fn config_schema(
+    properties: Vec<serde_json::Value>,
+    required: &[&str],
+) -> serde_json::Value {
+    let mut merged = serde_json::Map::new();
+    for group in properties {
+        if let serde_json::Value::Object(map) = group {
+            merged.extend(map);
+        }
+    }
+    json!({
+        "type": "object",
+        "properties": merged,
+        "required": required,
+        "additionalProperties": false
+    })
+}
@@ -0,0 +1,534 @@
+use axum::Router;
+use axum::body::{Bytes, StreamBody};
+use axum::extract::ws::WebSocketUpgrade;
+use axum::extract::{Query, State};
+use axum::http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode, Uri, header};
+use axum::response::{IntoResponse, Response};
+use rsflow_core::{
+    EngineContext, FlowCallback, FlowContext, FlowEventKey, NodeInput, NodeRunItem, Payload,
+    Stream, StreamId, Value,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::http::{debug_routes, metrics_routes};
+use crate::ws::{WsHub, serve_socket};
+
+/// plugin_config.net.http
+#[derive(Debug, Clone, Deserialize)]
+pub struct HttpConfig {
+    pub http_port: u16,
+    #[serde(default = "default_host")]
+    pub host: String,
+    /// 在 /metrics 提供 Prometheus 格式的引擎指标
+    #[serde(default)]
+    pub metrics: bool,
+    /// 在 /debug 下提供节点执行记录的订阅和开关接口
+    #[serde(default)]
+    pub debug: bool,
+}
+
+fn default_host() -> String {
+    "0.0.0.0".to_string()
+}
+
+impl HttpConfig {
+    pub fn validate(&self) -> Result<(), rsflow_core::NodeError> {
+        if self.http_port == 0 {
+            return Err(rsflow_core::NodeError::InvalidConfig(
+                "http.http_port must be between 1 and 65535".to_string(),
+            ));
+        }
+        self.addr().map(|_| ())
+    }
+
+    pub fn addr(&self) -> Result<SocketAddr, rsflow_core::NodeError> {
+        format!("{}:{}", self.host, self.http_port)
+            .parse()
+            .map_err(|e| {
+                rsflow_core::NodeError::InvalidConfig(format!("http.host {}: {}", self.host, e))
+            })
+    }
+}
+
+/// http in 节点注册的路由
+#[derive(Clone)]
+pub struct HttpRoute {
+    pub node_id: Uuid,
+    pub engine: EngineContext,
+    pub timeout: Duration,
+}
+
+type RouteKey = (String, String);
+
+/// 所有 http in / websocket 节点共享的 HTTP 服务
+pub struct HttpServer {
+    routes: RwLock<HashMap<RouteKey, HttpRoute>>,
+    ws: Arc<WsHub>,
+}
+
+impl HttpServer {
+    pub fn new() -> Self {
+        Self {
+            routes: RwLock::new(HashMap::new()),
+            ws: Arc::new(WsHub::new()),
+        }
+    }
+
+    /// websocket 连接表
+    pub fn ws(&self) -> &Arc<WsHub> {
+        &self.ws
+    }
+
+    /// 注册路由，同一 method + path 后注册的覆盖先注册的
+    pub fn add_route(&self, method: &str, path: &str, route: HttpRoute) {
+        let key = (method.to_uppercase(), path.to_string());
+        if let Some(old) = self.routes.write().unwrap().insert(key, route) {
+            warn!("HTTP route {} {} overridden (node {})", method, path, old.node_id);
+        }
+    }
+
+    /// 移除路由，只移除属于该节点的路由（热重载时新节点可能已注册）
+    pub fn remove_route(&self, method: &str, path: &str, node_id: Uuid) {
+        let key = (method.to_uppercase(), path.to_string());
+        let mut routes = self.routes.write().unwrap();
+        if routes.get(&key).is_some_and(|route| route.node_id == node_id) {
+            routes.remove(&key);
+        }
+    }
+
+    fn route(&self, method: &Method, path: &str) -> Option<HttpRoute> {
+        self.routes
+            .read()
+            .unwrap()
+            .get(&(method.as_str().to_string(), path.to_string()))
+            .cloned()
+    }
+
+    /// 启动 HTTP 服务，引擎停止后优雅退出
+    pub async fn serve(
+        self: Arc<Self>,
+        config: HttpConfig,
+        engine: EngineContext,
+    ) -> Result<(), String> {
+        let addr = config.addr().map_err(|e| format!("{:?}", e))?;
+        let shutdown = engine.shutdown.clone();
+        let mut app = Router::new();
+        if config.debug {
+            app = app.merge(debug_routes(engine.clone()));
+        }
+        if config.metrics {
+            app = app.merge(metrics_routes(engine));
+        }
+        let app = app.fallback(handle_request).with_state(self);
+
+        info!("HTTP server starting on {}", addr);
+        axum::Server::try_bind(&addr)
+            .map_err(|e| format!("bind {}: {}", addr, e))?
+            .serve(app.into_make_service())
+            .with_graceful_shutdown(async move { shutdown.cancelled().await })
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+impl Default for HttpServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+async fn handle_request(
+    State(server): State<Arc<HttpServer>>,
+    method: Method,
+    uri: Uri,
+    Query(query): Query<HashMap<String, String>>,
+    ws: Option<WebSocketUpgrade>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    // websocket 升级请求
+    if let Some(ws) = ws {
+        let path = uri.path().to_string();
+        if !server.ws.has_path(&path) {
+            return StatusCode::NOT_FOUND.into_response();
+        }
+        let hub = server.ws.clone();
+        return ws.on_upgrade(move |socket| serve_socket(hub, path, socket));
+    }
+
+    let Some(route) = server.route(&method, uri.path()) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    let req_id = Uuid::new_v4();
+    let ctx = FlowContext::new(req_id);
+    let streams = ctx.streams.clone();
+
+    // http response 节点通过 FlowEventKey::Response(req_id) 回写响应；
+    // 响应流在回调中取出，发出响应的分支随后结束、释放流的表项也不影响响应体
+    let (tx, rx) = oneshot::channel::<(Value, Option<Stream>)>();
+    let tx = Arc::new(Mutex::new(Some(tx)));
+    let callback: FlowCallback = Arc::new(move |val| {
+        let tx = tx.clone();
+        let stream = response_stream(&val).and_then(|id| streams.get(&id));
+        Box::pin(async move {
+            if let Some(tx) = tx.lock().unwrap().take() {
+                let _ = tx.send((val, stream));
+            }
+        })
+    });
+    ctx.listeners
+        .once(FlowEventKey::Response(req_id), callback)
+        .await;
+
+    let handle = ctx.handle();
+    let msg = Payload::new(request_value(req_id, &method, &uri, query, &headers, &body));
+    let started = route
+        .engine
+        .run_flow_with_ctx(
+            ctx,
+            NodeRunItem {
+                node_id: route.node_id,
+                node_input: NodeInput { port: 0, msg },
+            },
+        )
+        .await;
+    if !started {
+        return StatusCode::SERVICE_UNAVAILABLE.into_response();
+    }
+
+    // 等待 http response；回调随 flow 的最后一个分支释放，
+    // 此时仍未响应则记录节点错误并返回 500
+    let finished = handle.clone().wait();
+    let waited = tokio::time::timeout(route.timeout, async {
+        if let Ok(response) = rx.await {
+            return Some(response);
+        }
+        for failure in finished.await.errors {
+            warn!(
+                "HTTP {} {}: node {} failed: {}",
+                method,
+                uri.path(),
+                failure.node_name,
+                failure.message
+            );
+        }
+        None
+    })
+    .await;
+    match waited {
+        Ok(Some((val, stream))) => response_from_value(val, stream),
+        Ok(None) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+        Err(_) => {
+            handle.cancel();
+            StatusCode::GATEWAY_TIMEOUT.into_response()
+        }
+    }
+}
+
+// 将请求转换为 Payload 的值
+fn request_value(
+    req_id: Uuid,
+    method: &Method,
+    uri: &Uri,
+    query: HashMap<String, String>,
+    headers: &HeaderMap,
+    body: &Bytes,
+) -> Value {
+    let header_map: HashMap<String, Value> = headers
+        .iter()
+        .map(|(name, value)| {
+            (
+                name.as_str().to_string(),
+                Value::String(String::from_utf8_lossy(value.as_bytes()).to_string()),
+            )
+        })
+        .collect();
+
+    let is_json = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    let body = if body.is_empty() {
+        Value::NULL
+    } else if is_json {
+        serde_json::from_slice::<Value>(body)
+            .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(body).to_string()))
+    } else {
+        Value::String(String::from_utf8_lossy(body).to_string())
+    };
+
+    let mut map = HashMap::new();
+    map.insert("req_id".to_string(), Value::String(req_id.to_string()));
+    map.insert("method".to_string(), Value::String(method.to_string()));
+    map.insert("path".to_string(), Value::String(uri.path().to_string()));
+    map.insert(
+        "query".to_string(),
+        Value::Object(
+            query
+                .into_iter()
+                .map(|(k, v)| (k, Value::String(v)))
+                .collect(),
+        ),
+    );
+    map.insert("headers".to_string(), Value::Object(header_map));
+    map.insert("body".to_string(), body);
+    Value::Object(map)
+}
+
+// http response 节点发出的值中的流 id
+fn response_stream(val: &Value) -> Option<StreamId> {
+    match val {
+        Value::Object(map) => match map.get("stream") {
+            Some(Value::String(id)) => Uuid::parse_str(id).ok().map(StreamId),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// 将 http response 节点发出的值转换为 HTTP 响应：{ status, headers, body, stream }，
+// stream 为发出时从流表中取出的响应流
+fn response_from_value(val: Value, stream: Option<Stream>) -> Response {
+    let Value::Object(mut map) = val else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+
+    let status = match map.get("status") {
+        Some(Value::Int(code)) => u16::try_from(*code)
+            .ok()
+            .and_then(|code| StatusCode::from_u16(code).ok())
+            .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+        _ => StatusCode::OK,
+    };
+
+    let mut headers = HeaderMap::new();
+    apply_headers(&mut headers, map.get("headers"));
+
+    // 以 Stream 作为分块响应体
+    if map.contains_key("stream") {
+        let Some(stream) = stream else {
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        };
+        if !headers.contains_key(header::CONTENT_TYPE) {
+            headers.insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/octet-stream"),
+            );
+        }
+        return (status, headers, StreamBody::new(stream_body(stream))).into_response();
+    }
+
+    let (content_type, body) = match map.remove("body").unwrap_or(Value::NULL) {
+        Value::NULL => (None, Vec::new()),
+        Value::String(s) => (Some("text/plain; charset=utf-8"), s.into_bytes()),
+        other => (
+            Some("application/json"),
+            serde_json::to_vec(&other).unwrap_or_default(),
+        ),
+    };
+    if let Some(content_type) = content_type
+        && !headers.contains_key(header::CONTENT_TYPE)
+    {
+        headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+    }
+
+    (status, headers, body).into_response()
+}
+
+// 逐块读取 Stream，读到结束或出错时结束响应体
+fn stream_body(
+    stream: Stream,
+) -> impl futures_util::Stream<Item = Result<Bytes, std::io::Error>> + Send {
+    futures_util::stream::unfold(Some(stream), |stream| async move {
+        let stream = stream?;
+        match stream.read().await {
+            Ok(Some(chunk)) => Some((Ok(Bytes::from(chunk)), Some(stream))),
+            Ok(None) => None,
+            Err(e) => Some((Err(std::io::Error::other(format!("{:?}", e))), None)),
+        }
+    })
+}
+
+// 写入 http response 节点给出的自定义响应头
+fn apply_headers(headers: &mut HeaderMap, custom: Option<&Value>) {
+    if let Some(Value::Object(custom)) = custom {
+        for (name, value) in custom {
+            let value = match value {
+                Value::String(s) => s.clone(),
+                other => serde_json::to_string(other).unwrap_or_default(),
+            };
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(&value),
+            ) {
+                headers.insert(name, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{
+        FnNodeBuilder, engine_builder, free_port, http_request, start_engine, value,
+    };
+    use rsflow_core::engine::{EngineBuilder, EngineHandle};
+    use rsflow_core::flow::{FlowBuilder, FlowModBuilder, NodeRef, NodeSpec};
+    use rsflow_core::{ChannelStream, NodeError, NodeOutput, StreamTrait};
+    use serde_json::json;
+
+    // 把请求转为响应：body 原样返回，x-method 为请求方法，query.status 作为状态码
+    fn respond() -> FnNodeBuilder {
+        FnNodeBuilder::new("respond", |msg, _| {
+            let Value::Object(request) = msg.value else {
+                return Err(NodeError::InvalidInput("expected request".to_string()));
+            };
+            let mut response = HashMap::new();
+            response.insert("req_id".to_string(), request["req_id"].clone());
+            response.insert("body".to_string(), request["body"].clone());
+            response.insert(
+                "response_headers".to_string(),
+                Value::Object(HashMap::from([(
+                    "x-method".to_string(),
+                    request["method"].clone(),
+                )])),
+            );
+            if let Value::Object(query) = &request["query"]
+                && let Some(Value::String(status)) = query.get("status")
+            {
+                response.insert("status".to_string(), Value::Int(status.parse().unwrap()));
+            }
+            Ok(NodeOutput::One((0, Payload::new(Value::Object(response)))))
+        })
+    }
+
+    // 插入一个读取端流并在后台写入 "hello " "world"
+    fn streamer() -> FnNodeBuilder {
+        FnNodeBuilder::new("streamer", |msg, ctx| {
+            let (writer, reader) = ChannelStream::pipe(1);
+            let id = StreamId(Uuid::new_v4());
+            ctx.streams.insert(id, Arc::new(reader));
+            tokio::spawn(async move {
+                for part in ["hello ", "world"] {
+                    writer.send(Value::String(part.to_string())).await.unwrap();
+                }
+                writer.close().await.unwrap();
+            });
+            Ok(NodeOutput::One((0, Payload::new_stream(msg.value, id))))
+        })
+    }
+
+    async fn serve(
+        builder: EngineBuilder,
+        build: impl FnOnce(&mut FlowBuilder) -> NodeRef,
+    ) -> (u16, EngineHandle) {
+        let port = free_port();
+        let mut flow = FlowBuilder::new("http");
+        build(&mut flow);
+        let flow_mod = FlowModBuilder::new()
+            .plugin_config(
+                "net",
+                value(json!({ "http": { "http_port": port, "host": "127.0.0.1" } })),
+            )
+            .flow(flow.build())
+            .build();
+        let (handle, _) = start_engine(builder, flow_mod).await;
+        (port, handle)
+    }
+
+    fn http_in(method: &str, url: &str) -> NodeSpec {
+        NodeSpec::new("http in").config(value(json!({ "method": method, "url": url })))
+    }
+
+    async fn echo_server() -> (u16, EngineHandle) {
+        serve(engine_builder().register_node(respond()), |flow| {
+            let input = flow.add(http_in("POST", "/echo"));
+            let respond = flow.add(NodeSpec::new("respond"));
+            let output = flow.add(NodeSpec::new("http response").config(value(
+                json!({ "status": 201, "headers": { "x-config": "1" } }),
+            )));
+            flow.wire(input, 0, respond, 0).wire(respond, 0, output, 0);
+            input
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn json_round_trip() {
+        let (port, handle) = echo_server().await;
+        let body = r#"{"items":[1,2],"name":"rsflow"}"#;
+        let reply = http_request(
+            port,
+            "POST",
+            "/echo",
+            &[("Content-Type", "application/json")],
+            body,
+        )
+        .await;
+
+        assert_eq!(reply.status, 201);
+        assert_eq!(reply.headers["content-type"], "application/json");
+        assert_eq!(reply.headers["x-method"], "POST");
+        assert_eq!(reply.headers["x-config"], "1");
+        let json: serde_json::Value = serde_json::from_slice(&reply.body).unwrap();
+        assert_eq!(json, json!({ "items": [1, 2], "name": "rsflow" }));
+        handle.shutdown();
+    }
+
+    #[tokio::test]
+    async fn status_headers_and_text_body() {
+        let (port, handle) = echo_server().await;
+        let reply = http_request(port, "POST", "/echo?status=404", &[], "plain text").await;
+        assert_eq!(reply.status, 404);
+        assert_eq!(reply.headers["content-type"], "text/plain; charset=utf-8");
+        assert_eq!(reply.headers["x-config"], "1");
+        assert_eq!(reply.body, b"plain text");
+
+        // 没有匹配的路由
+        assert_eq!(
+            http_request(port, "GET", "/echo", &[], "").await.status,
+            404
+        );
+        assert_eq!(
+            http_request(port, "POST", "/other", &[], "").await.status,
+            404
+        );
+        handle.shutdown();
+    }
+
+    #[tokio::test]
+    async fn stream_body_from_fan_out_branch() {
+        let builder = engine_builder()
+            .register_node(streamer())
+            .register_node(FnNodeBuilder::new("noop", |_, _| Ok(NodeOutput::None)));
+        let (port, handle) = serve(builder, |flow| {
+            let input = flow.add(http_in("GET", "/stream"));
+            let noop = flow.add(NodeSpec::new("noop"));
+            let streamer = flow.add(NodeSpec::new("streamer"));
+            let output = flow.add(NodeSpec::new("http response"));
+            // 流由派发出去的分支插入，响应后该分支立即结束
+            flow.wire(input, 0, noop, 0)
+                .wire(input, 0, streamer, 0)
+                .wire(streamer, 0, output, 0);
+            input
+        })
+        .await;
+
+        for _ in 0..20 {
+            let reply = http_request(port, "GET", "/stream", &[], "").await;
+            assert_eq!(reply.status, 200);
+            assert_eq!(reply.headers["content-type"], "application/octet-stream");
+            assert_eq!(reply.body, b"hello world");
+        }
+        handle.shutdown();
+    }
+}
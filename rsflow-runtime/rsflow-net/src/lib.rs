@@ -1 +1,122 @@
-use rsflow_core::engine::EnginePlugin;
\ No newline at end of file
+pub mod data;
+pub mod http;
+pub mod tcp;
+pub mod udp;
+pub mod ws;
+
+#[cfg(test)]
+mod testing;
+
+pub use http::{
+    HttpConfig, HttpInNodeBuilder, HttpResponseNodeBuilder, HttpServer, debug_routes,
+    metrics_routes,
+};
+pub use tcp::{TcpInNodeBuilder, TcpOutNodeBuilder, TcpRequestNodeBuilder};
+pub use udp::{UdpInNodeBuilder, UdpOutNodeBuilder};
+pub use ws::{WsHub, WsInNodeBuilder, WsOutNodeBuilder};
+
+use crate::ws::WsEndpoint;
+use rsflow_core::engine::{EnginePlugin, NodeBuilderMap};
+use rsflow_core::flow::FlowMod;
+use rsflow_core::{EngineContext, NodeBuilder, NodeError, Value};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{error, warn};
+
+/// plugin_config.net
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct NetConfig {
+    #[serde(default)]
+    pub http: Option<HttpConfig>,
+}
+
+/// 网络插件：共享的 HTTP / WebSocket 服务以及 TCP / UDP 节点
+pub struct NetPlugin {
+    config: NetConfig,
+    http: Arc<HttpServer>,
+}
+
+impl NetPlugin {
+    pub fn new() -> Self {
+        Self {
+            config: NetConfig::default(),
+            http: Arc::new(HttpServer::new()),
+        }
+    }
+}
+
+impl Default for NetPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// 插件先于节点启动，路由尚未注册，按 flow 中的节点判断是否需要 HTTP 服务
+fn uses_http(flow_mod: &FlowMod) -> bool {
+    flow_mod
+        .enabled_flows()
+        .flat_map(|flow| flow.nodes.iter())
+        .any(|node| match node.node_type.as_str() {
+            "http in" => true,
+            "websocket in" | "websocket out" => matches!(
+                WsEndpoint::from_config(&node.config),
+                Ok(WsEndpoint::Server { .. })
+            ),
+            _ => false,
+        })
+}
+
+fn insert_builder<B: NodeBuilder + 'static>(map: &mut NodeBuilderMap, builder: B) {
+    map.insert(builder.node_type().to_string(), Box::new(builder));
+}
+
+#[async_trait::async_trait]
+impl EnginePlugin for NetPlugin {
+    fn name(&self) -> &'static str {
+        "net"
+    }
+
+    async fn configure(&mut self, config: &Value) -> Result<(), NodeError> {
+        if *config == Value::NULL {
+            return Ok(());
+        }
+        let config: NetConfig = config
+            .deserialize_into()
+            .map_err(|e| NodeError::InvalidConfig(e.to_string()))?;
+        if let Some(http) = &config.http {
+            http.validate()?;
+        }
+        self.config = config;
+        Ok(())
+    }
+
+    fn internal_nodes(&self) -> NodeBuilderMap {
+        let mut nodes: NodeBuilderMap = HashMap::new();
+        insert_builder(&mut nodes, HttpInNodeBuilder::new(self.http.clone()));
+        insert_builder(&mut nodes, HttpResponseNodeBuilder);
+        insert_builder(&mut nodes, WsInNodeBuilder::new(self.http.ws().clone()));
+        insert_builder(&mut nodes, WsOutNodeBuilder::new(self.http.ws().clone()));
+        insert_builder(&mut nodes, TcpInNodeBuilder);
+        insert_builder(&mut nodes, TcpOutNodeBuilder);
+        insert_builder(&mut nodes, TcpRequestNodeBuilder);
+        insert_builder(&mut nodes, UdpInNodeBuilder);
+        insert_builder(&mut nodes, UdpOutNodeBuilder);
+        nodes
+    }
+
+    async fn engine_start(&self, ctx: EngineContext) {
+        if let Some(http) = self.config.http.clone() {
+            let server = self.http.clone();
+            tokio::spawn(async move {
+                if let Err(e) = server.serve(http, ctx).await {
+                    error!("HTTP server error: {}", e);
+                }
+            });
+        } else if uses_http(&ctx.get_mod()) {
+            warn!(
+                "http in / websocket nodes found but plugin_config.net.http is not set, HTTP server not started"
+            );
+        }
+    }
+}
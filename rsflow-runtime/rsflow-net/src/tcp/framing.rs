@@ -0,0 +1,318 @@
+use crate::http::{config_str, config_u64};
+use rsflow_core::{NodeError, Value};
+use serde_json::json;
+use std::io;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+// 单帧最大字节数，防止对端不发送分隔符或声明超长帧时无限缓存
+const DEFAULT_MAX_FRAME: usize = 1024 * 1024;
+
+fn frame_too_large(length: u64) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!(
+            "frame of {} bytes exceeds {} bytes",
+            length, DEFAULT_MAX_FRAME
+        ),
+    )
+}
+
+/// TCP 分帧方式
+#[derive(Debug, Clone, PartialEq)]
+pub enum Framing {
+    /// 按分隔符切分，帧内容不含分隔符
+    Delimiter(Vec<u8>),
+    /// 固定长度
+    Fixed(usize),
+    /// 大端长度前缀，前缀字节数为 1 / 2 / 4 / 8
+    LengthPrefixed(usize),
+    /// 不分帧，收到多少输出多少
+    Stream,
+}
+
+impl Framing {
+    /// 从节点配置读取：
+    /// framing = "stream"（默认）| "delimiter"（delimiter，默认 "\n"）
+    /// | "fixed"（length）| "length"（prefix_bytes，默认 4）
+    pub fn from_config(config: &Value) -> Result<Self, NodeError> {
+        match config_str(config, "framing").unwrap_or("stream") {
+            "stream" => Ok(Framing::Stream),
+            "delimiter" => {
+                let delimiter = config_str(config, "delimiter").unwrap_or("\n");
+                if delimiter.is_empty() {
+                    return Err(NodeError::InvalidConfig(
+                        "delimiter must not be empty".to_string(),
+                    ));
+                }
+                Ok(Framing::Delimiter(delimiter.as_bytes().to_vec()))
+            }
+            "fixed" => match config_u64(config, "length") {
+                Some(length) if length > DEFAULT_MAX_FRAME as u64 => {
+                    Err(NodeError::InvalidConfig(format!(
+                        "fixed framing length must not exceed {} bytes",
+                        DEFAULT_MAX_FRAME
+                    )))
+                }
+                Some(length) if length > 0 => Ok(Framing::Fixed(length as usize)),
+                _ => Err(NodeError::InvalidConfig(
+                    "fixed framing requires a positive length".to_string(),
+                )),
+            },
+            "length" => match config_u64(config, "prefix_bytes").unwrap_or(4) {
+                n @ (1 | 2 | 4 | 8) => Ok(Framing::LengthPrefixed(n as usize)),
+                n => Err(NodeError::InvalidConfig(format!(
+                    "prefix_bytes must be 1, 2, 4 or 8: {}",
+                    n
+                ))),
+            },
+            other => Err(NodeError::InvalidConfig(format!(
+                "unknown framing: {}",
+                other
+            ))),
+        }
+    }
+
+    /// 分帧字段的 JSON Schema
+    pub(crate) fn schema_properties() -> serde_json::Value {
+        json!({
+            "framing": {
+                "enum": ["stream", "delimiter", "fixed", "length"],
+                "default": "stream",
+                "description": "分帧方式：不分帧、分隔符、固定长度、长度前缀"
+            },
+            "delimiter": { "type": "string", "minLength": 1, "default": "\n" },
+            "length": { "type": "integer", "minimum": 1, "description": "fixed 分帧的帧长度" },
+            "prefix_bytes": { "enum": [1, 2, 4, 8], "default": 4, "description": "长度前缀字节数（大端）" }
+        })
+    }
+
+    /// 从缓冲区中取出一帧，数据不足时返回 None，长度前缀超过单帧上限时返回错误
+    pub fn decode(&self, buf: &mut Vec<u8>) -> io::Result<Option<Vec<u8>>> {
+        match self {
+            Framing::Delimiter(delimiter) => {
+                let Some(pos) = buf
+                    .windows(delimiter.len())
+                    .position(|window| window == delimiter.as_slice())
+                else {
+                    return Ok(None);
+                };
+                let frame = buf[..pos].to_vec();
+                buf.drain(..pos + delimiter.len());
+                Ok(Some(frame))
+            }
+            Framing::Fixed(length) => {
+                if buf.len() < *length {
+                    return Ok(None);
+                }
+                Ok(Some(buf.drain(..*length).collect()))
+            }
+            Framing::LengthPrefixed(prefix) => {
+                if buf.len() < *prefix {
+                    return Ok(None);
+                }
+                // 长度来自对端，先检查上限再与缓冲区比较
+                let length = buf[..*prefix]
+                    .iter()
+                    .fold(0u64, |acc, b| (acc << 8) | *b as u64);
+                if length > DEFAULT_MAX_FRAME as u64 {
+                    return Err(frame_too_large(length));
+                }
+                let end = prefix
+                    .checked_add(length as usize)
+                    .ok_or_else(|| frame_too_large(length))?;
+                if buf.len() < end {
+                    return Ok(None);
+                }
+                let frame = buf[*prefix..end].to_vec();
+                buf.drain(..end);
+                Ok(Some(frame))
+            }
+            Framing::Stream => {
+                if buf.is_empty() {
+                    return Ok(None);
+                }
+                Ok(Some(std::mem::take(buf)))
+            }
+        }
+    }
+
+    /// 连接关闭时剩余的数据，分隔符和流模式下作为最后一帧
+    pub fn finish(&self, buf: &mut Vec<u8>) -> Option<Vec<u8>> {
+        match self {
+            Framing::Delimiter(_) | Framing::Stream if !buf.is_empty() => {
+                Some(std::mem::take(buf))
+            }
+            _ => None,
+        }
+    }
+
+    /// 按分帧方式编码待发送的数据
+    pub fn encode(&self, data: &[u8]) -> Result<Vec<u8>, NodeError> {
+        match self {
+            Framing::Delimiter(delimiter) => {
+                let mut out = data.to_vec();
+                out.extend_from_slice(delimiter);
+                Ok(out)
+            }
+            Framing::Fixed(length) => {
+                if data.len() != *length {
+                    return Err(NodeError::InvalidInput(format!(
+                        "fixed framing expects {} bytes, got {}",
+                        length,
+                        data.len()
+                    )));
+                }
+                Ok(data.to_vec())
+            }
+            Framing::LengthPrefixed(prefix) => {
+                let max = if *prefix == 8 {
+                    u64::MAX
+                } else {
+                    (1u64 << (prefix * 8)) - 1
+                };
+                if data.len() as u64 > max {
+                    return Err(NodeError::InvalidInput(format!(
+                        "frame of {} bytes exceeds {}-byte length prefix",
+                        data.len(),
+                        prefix
+                    )));
+                }
+                let mut out = (data.len() as u64).to_be_bytes()[8 - prefix..].to_vec();
+                out.extend_from_slice(data);
+                Ok(out)
+            }
+            Framing::Stream => Ok(data.to_vec()),
+        }
+    }
+
+    /// 从读取端读出下一帧，对端关闭且没有剩余数据时返回 None
+    pub async fn read_frame<R: AsyncRead + Unpin>(
+        &self,
+        reader: &mut R,
+        buf: &mut Vec<u8>,
+    ) -> io::Result<Option<Vec<u8>>> {
+        let mut chunk = [0u8; 4096];
+        loop {
+            if let Some(frame) = self.decode(buf)? {
+                return Ok(Some(frame));
+            }
+            if buf.len() > DEFAULT_MAX_FRAME {
+                return Err(frame_too_large(buf.len() as u64));
+            }
+            let n = reader.read(&mut chunk).await?;
+            if n == 0 {
+                return Ok(self.finish(buf));
+            }
+            buf.extend_from_slice(&chunk[..n]);
+        }
+    }
+
+    /// 读取到对端关闭为止（stream 分帧的响应），超过单帧上限时返回错误
+    pub async fn read_to_close<R: AsyncRead + Unpin>(reader: &mut R) -> io::Result<Vec<u8>> {
+        let mut out = Vec::new();
+        reader
+            .take(DEFAULT_MAX_FRAME as u64 + 1)
+            .read_to_end(&mut out)
+            .await?;
+        if out.len() > DEFAULT_MAX_FRAME {
+            return Err(frame_too_large(out.len() as u64));
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_all(framing: &Framing, buf: &mut Vec<u8>) -> Vec<Vec<u8>> {
+        let mut frames = Vec::new();
+        while let Some(frame) = framing.decode(buf).unwrap() {
+            frames.push(frame);
+        }
+        frames
+    }
+
+    #[test]
+    fn length_prefixed_partial_frames() {
+        let framing = Framing::LengthPrefixed(2);
+        // 前缀不完整
+        let mut buf = vec![0];
+        assert_eq!(framing.decode(&mut buf).unwrap(), None);
+        // 帧体不完整
+        buf.extend_from_slice(&[3, b'a', b'b']);
+        assert_eq!(framing.decode(&mut buf).unwrap(), None);
+        assert_eq!(buf, vec![0, 3, b'a', b'b']);
+        buf.push(b'c');
+        assert_eq!(framing.decode(&mut buf).unwrap(), Some(b"abc".to_vec()));
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn length_prefixed_multiple_frames() {
+        let framing = Framing::LengthPrefixed(4);
+        let mut buf = Vec::new();
+        for data in [&b"one"[..], b"", b"three"] {
+            buf.extend(framing.encode(data).unwrap());
+        }
+        // 第三帧之后跟着下一帧的一部分
+        buf.extend_from_slice(&[0, 0, 0, 9, b'x']);
+        assert_eq!(
+            decode_all(&framing, &mut buf),
+            vec![b"one".to_vec(), Vec::new(), b"three".to_vec()]
+        );
+        assert_eq!(buf, vec![0, 0, 0, 9, b'x']);
+    }
+
+    #[test]
+    fn length_prefixed_rejects_oversized_length() {
+        let over = (DEFAULT_MAX_FRAME as u64 + 1).to_be_bytes();
+        let mut buf = over[4..].to_vec();
+        assert!(Framing::LengthPrefixed(4).decode(&mut buf).is_err());
+
+        // 8 字节前缀的最大长度不能溢出
+        let mut buf = vec![0xff; 8];
+        let err = Framing::LengthPrefixed(8).decode(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let mut buf = DEFAULT_MAX_FRAME.to_be_bytes()[4..].to_vec();
+        assert_eq!(Framing::LengthPrefixed(4).decode(&mut buf).unwrap(), None);
+    }
+
+    #[test]
+    fn delimiter_and_fixed_multiple_frames() {
+        let framing = Framing::Delimiter(b"\r\n".to_vec());
+        let mut buf = b"a\r\nbc\r\nd".to_vec();
+        assert_eq!(
+            decode_all(&framing, &mut buf),
+            vec![b"a".to_vec(), b"bc".to_vec()]
+        );
+        assert_eq!(framing.finish(&mut buf), Some(b"d".to_vec()));
+
+        let framing = Framing::Fixed(2);
+        let mut buf = b"abcde".to_vec();
+        assert_eq!(
+            decode_all(&framing, &mut buf),
+            vec![b"ab".to_vec(), b"cd".to_vec()]
+        );
+        assert_eq!(framing.finish(&mut buf), None);
+    }
+
+    #[tokio::test]
+    async fn read_frame_stops_at_max_frame() {
+        let data = vec![b'x'; DEFAULT_MAX_FRAME + 1];
+        let mut buf = Vec::new();
+        let err = Framing::Delimiter(b"\n".to_vec())
+            .read_frame(&mut data.as_slice(), &mut buf)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        assert!(Framing::read_to_close(&mut data.as_slice()).await.is_err());
+        let data = b"response".to_vec();
+        assert_eq!(
+            Framing::read_to_close(&mut data.as_slice()).await.unwrap(),
+            data
+        );
+    }
+}
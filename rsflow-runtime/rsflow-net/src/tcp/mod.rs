@@ -0,0 +1,182 @@
+mod framing;
+mod stream;
+mod tcp_in;
+mod tcp_out;
+mod tcp_request;
+
+pub use framing::Framing;
+pub use stream::TcpConnStream;
+pub use tcp_in::{TcpInNode, TcpInNodeBuilder, TcpInNodeFactory};
+pub use tcp_out::{TcpOutNode, TcpOutNodeBuilder, TcpOutNodeFactory};
+pub use tcp_request::{TcpRequestNode, TcpRequestNodeBuilder, TcpRequestNodeFactory};
+
+use crate::http::{config_str, config_u64};
+use rsflow_core::{NodeError, Value};
+use serde_json::json;
+
+/// 从节点配置读取 host:port，未配置 host 时使用 default_host
+pub(crate) fn config_addr(config: &Value, default_host: Option<&str>) -> Result<String, NodeError> {
+    let host = config_str(config, "host")
+        .or(default_host)
+        .ok_or_else(|| NodeError::InvalidConfig("missing config.host".to_string()))?;
+    match config_u64(config, "port") {
+        Some(port) if (1..=65535).contains(&port) => Ok(format!("{}:{}", host, port)),
+        Some(port) => Err(NodeError::InvalidConfig(format!(
+            "port out of range: {}",
+            port
+        ))),
+        None => Err(NodeError::InvalidConfig("missing config.port".to_string())),
+    }
+}
+
+/// 可选的 host:port，两者都未配置时返回 None
+pub(crate) fn optional_addr(config: &Value) -> Result<Option<String>, NodeError> {
+    if config_str(config, "host").is_none() && config_u64(config, "port").is_none() {
+        return Ok(None);
+    }
+    config_addr(config, None).map(Some)
+}
+
+/// host、port 字段的 JSON Schema
+pub(crate) fn addr_schema_properties(default_host: Option<&str>) -> serde_json::Value {
+    let mut host = json!({ "type": "string" });
+    if let Some(default_host) = default_host {
+        host["default"] = json!(default_host);
+    }
+    json!({
+        "host": host,
+        "port": { "type": "integer", "minimum": 1, "maximum": 65535 }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{engine_builder, free_port, start_at, start_engine, value};
+    use rsflow_core::flow::{FlowBuilder, FlowModBuilder, NodeSpec};
+    use rsflow_core::{FlowStatus, Value};
+    use serde_json::json;
+    use std::time::Duration;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    fn flow_mod(flow: FlowBuilder) -> rsflow_core::flow::FlowMod {
+        FlowModBuilder::new().flow(flow.build()).build()
+    }
+
+    // 逐行读取的本机 TCP 服务，每行转为大写并加换行回复
+    async fn upper_server() -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let (reader, mut writer) = socket.into_split();
+                    let mut lines = BufReader::new(reader).lines();
+                    while let Ok(Some(line)) = lines.next_line().await {
+                        let reply = format!("{}\n", line.to_uppercase());
+                        if writer.write_all(reply.as_bytes()).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+        port
+    }
+
+    #[tokio::test]
+    async fn tcp_in_replies_through_tcp_out_and_releases_port() {
+        let port = free_port();
+        let config = value(json!({ "host": "127.0.0.1", "port": port, "framing": "delimiter" }));
+        let mut flow = FlowBuilder::new("tcp");
+        let input = flow.add(NodeSpec::new("tcp in").config(config));
+        let output = flow.add(NodeSpec::new("tcp out"));
+        flow.wire(input, 0, output, 0);
+        let (handle, task) = start_engine(engine_builder(), flow_mod(flow)).await;
+
+        let socket = crate::testing::connect(port).await;
+        let (reader, mut writer) = socket.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        for line in ["ping", "pong"] {
+            writer
+                .write_all(format!("{}\n", line).as_bytes())
+                .await
+                .unwrap();
+            let reply = tokio::time::timeout(Duration::from_secs(5), lines.next_line())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(reply.as_deref(), Some(line));
+        }
+
+        // 停止后端口立即可以重新绑定（热重载时重新监听同一端口）
+        handle.shutdown();
+        task.await.unwrap();
+        TcpListener::bind(("127.0.0.1", port)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn tcp_out_client_reuses_connection() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let mut flow = FlowBuilder::new("tcp");
+        let output = flow.add(NodeSpec::new("tcp out").config(value(json!({
+            "host": "127.0.0.1", "port": port, "framing": "delimiter"
+        }))));
+        let (handle, _) = start_engine(engine_builder(), flow_mod(flow)).await;
+        let engine = handle.context();
+
+        for line in ["one", "two"] {
+            let result = engine
+                .run_flow(start_at(output.id(), Value::String(line.to_string())))
+                .await
+                .wait()
+                .await;
+            assert_eq!(result.status, FlowStatus::Completed);
+        }
+
+        // 两次发送复用同一个连接
+        let (socket, _) = listener.accept().await.unwrap();
+        let mut lines = BufReader::new(socket).lines();
+        assert_eq!(lines.next_line().await.unwrap().as_deref(), Some("one"));
+        assert_eq!(lines.next_line().await.unwrap().as_deref(), Some("two"));
+        let second = tokio::time::timeout(Duration::from_millis(100), listener.accept()).await;
+        assert!(second.is_err());
+    }
+
+    #[tokio::test]
+    async fn tcp_request_returns_response_frame() {
+        let port = upper_server().await;
+        let mut flow = FlowBuilder::new("tcp");
+        let request = flow.add(NodeSpec::new("tcp request").config(value(json!({
+            "host": "127.0.0.1", "port": port, "framing": "delimiter"
+        }))));
+        let (handle, _) = start_engine(engine_builder(), flow_mod(flow)).await;
+
+        let result = handle
+            .context()
+            .run_flow(start_at(request.id(), value(json!({ "data": "hello" }))))
+            .await
+            .wait()
+            .await;
+        assert_eq!(result.status, FlowStatus::Completed);
+        assert_eq!(
+            result.outputs[0].payload.value,
+            value(json!({ "remote": format!("127.0.0.1:{}", port), "data": "HELLO" }))
+        );
+
+        // 没有服务监听时报错
+        let mut flow = FlowBuilder::new("tcp");
+        let request = flow.add(NodeSpec::new("tcp request").config(value(json!({
+            "host": "127.0.0.1", "port": free_port()
+        }))));
+        let (handle, _) = start_engine(engine_builder(), flow_mod(flow)).await;
+        let result = handle
+            .context()
+            .run_flow(start_at(request.id(), Value::String("x".to_string())))
+            .await
+            .wait()
+            .await;
+        assert_eq!(result.status, FlowStatus::Failed);
+    }
+}
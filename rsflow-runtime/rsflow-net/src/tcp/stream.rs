@@ -0,0 +1,62 @@
+use crate::tcp::Framing;
+use rsflow_core::{NodeError, StreamTrait};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::io::AsyncWriteExt;
+use tokio::net::tcp::OwnedWriteHalf;
+use tokio::sync::Mutex;
+
+/// TCP 连接的写入端，按连接的分帧方式编码后写入
+pub struct TcpConnStream {
+    writer: Mutex<OwnedWriteHalf>,
+    framing: Framing,
+    remote: SocketAddr,
+    closed: AtomicBool,
+}
+
+impl TcpConnStream {
+    pub fn new(writer: OwnedWriteHalf, framing: Framing, remote: SocketAddr) -> Self {
+        Self {
+            writer: Mutex::new(writer),
+            framing,
+            remote,
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    pub fn remote(&self) -> SocketAddr {
+        self.remote
+    }
+}
+
+#[async_trait::async_trait]
+impl StreamTrait for TcpConnStream {
+    async fn write(&self, data: &[u8]) -> Result<(), NodeError> {
+        if self.is_closed() {
+            return Err(NodeError::StreamClosed);
+        }
+        let bytes = self.framing.encode(data)?;
+        self.writer
+            .lock()
+            .await
+            .write_all(&bytes)
+            .await
+            .map_err(NodeError::Io)
+    }
+
+    async fn close(&self) -> Result<(), NodeError> {
+        if self.closed.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+        self.writer
+            .lock()
+            .await
+            .shutdown()
+            .await
+            .map_err(NodeError::Io)
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+}
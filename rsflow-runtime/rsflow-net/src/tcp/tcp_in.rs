@@ -0,0 +1,193 @@
+use crate::data::Encoding;
+use crate::http::config_schema;
+use crate::tcp::{Framing, TcpConnStream, addr_schema_properties, config_addr};
+use rsflow_core::{
+    EngineContext, FlowContext, Node, NodeBuilder, NodeDescriptor, NodeError, NodeFactory,
+    NodeInfo, NodeInput, NodeOutput, NodeRunItem, Payload, Stream, StreamId, Value,
+};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// tcp in 节点：监听端口，每个完整帧启动一次 flow
+///
+/// 输出值为 { conn_id, remote, data }，连接以 Stream 形式登记在
+/// FlowContext::streams 中（Payload::streams[0]），下游可在同一连接上回复。
+pub struct TcpInNode {
+    info: NodeInfo,
+    addr: String,
+    framing: Framing,
+    encoding: Encoding,
+    // accept 任务及其取消令牌，engine_stop 时等待任务结束以释放端口
+    task: Mutex<Option<(CancellationToken, JoinHandle<()>)>>,
+}
+
+#[async_trait::async_trait]
+impl Node for TcpInNode {
+    fn info(&self) -> NodeInfo {
+        self.info.clone()
+    }
+
+    async fn engine_start(&self, sender: EngineContext) {
+        let listener = match TcpListener::bind(&self.addr).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                error!("tcp in {} bind {} failed: {}", self.info.name, self.addr, e);
+                return;
+            }
+        };
+        info!("TCP listening on {}", self.addr);
+
+        let cancel = CancellationToken::new();
+        let node_id = self.info.id;
+        let framing = self.framing.clone();
+        let encoding = self.encoding;
+        let stop = cancel.clone();
+        let task = tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = cancel.cancelled() => break,
+                    _ = sender.stopped() => break,
+                    accepted = listener.accept() => match accepted {
+                        Ok((socket, remote)) => {
+                            tokio::spawn(handle_connection(
+                                socket,
+                                remote,
+                                node_id,
+                                sender.clone(),
+                                framing.clone(),
+                                encoding,
+                                cancel.clone(),
+                            ));
+                        }
+                        Err(e) => warn!("tcp accept failed: {}", e),
+                    },
+                }
+            }
+        });
+        *self.task.lock().unwrap() = Some((stop, task));
+    }
+
+    async fn engine_stop(&self) {
+        let task = self.task.lock().unwrap().take();
+        if let Some((cancel, task)) = task {
+            cancel.cancel();
+            let _ = task.await;
+        }
+    }
+
+    async fn event(&self, _: &str, _: Payload, _: &FlowContext) -> Result<(), NodeError> {
+        Ok(())
+    }
+
+    async fn input(&self, node_input: NodeInput, _: &FlowContext) -> Result<NodeOutput, NodeError> {
+        Ok(NodeOutput::One((0, node_input.msg)))
+    }
+}
+
+// 读取一个连接上的帧，直到对端关闭或节点停止
+async fn handle_connection(
+    socket: TcpStream,
+    remote: SocketAddr,
+    node_id: Uuid,
+    engine: EngineContext,
+    framing: Framing,
+    encoding: Encoding,
+    cancel: CancellationToken,
+) {
+    let (mut reader, writer) = socket.into_split();
+    let stream_id = StreamId(Uuid::new_v4());
+    let stream: Stream = Arc::new(TcpConnStream::new(writer, framing.clone(), remote));
+    let mut buf = Vec::new();
+
+    loop {
+        let frame = tokio::select! {
+            _ = cancel.cancelled() => break,
+            frame = framing.read_frame(&mut reader, &mut buf) => frame,
+        };
+        match frame {
+            Ok(Some(frame)) => {
+                let mut map = HashMap::new();
+                map.insert("conn_id".to_string(), Value::String(stream_id.0.to_string()));
+                map.insert("remote".to_string(), Value::String(remote.to_string()));
+                map.insert("data".to_string(), encoding.decode(frame));
+
+                let ctx = FlowContext::new(Uuid::new_v4());
+                ctx.streams.insert(stream_id, stream.clone());
+                engine
+                    .run_flow_with_ctx(
+                        ctx,
+                        NodeRunItem {
+                            node_id,
+                            node_input: NodeInput {
+                                port: 0,
+                                msg: Payload::new_stream(Value::Object(map), stream_id),
+                            },
+                        },
+                    )
+                    .await;
+            }
+            Ok(None) => break,
+            Err(e) => {
+                warn!("tcp connection {} error: {}", remote, e);
+                break;
+            }
+        }
+    }
+}
+
+// NodeFactory
+pub struct TcpInNodeFactory;
+
+#[async_trait::async_trait]
+impl NodeFactory for TcpInNodeFactory {
+    async fn create(&self, node_info: NodeInfo) -> Result<Arc<dyn Node + Send + Sync>, NodeError> {
+        let addr = config_addr(&node_info.config, Some("0.0.0.0"))?;
+        let framing = Framing::from_config(&node_info.config)?;
+        let encoding = Encoding::from_config(&node_info.config)?;
+        Ok(Arc::new(TcpInNode {
+            info: node_info,
+            addr,
+            framing,
+            encoding,
+            task: Mutex::new(None),
+        }))
+    }
+}
+
+// NodeBuilder
+pub struct TcpInNodeBuilder;
+
+#[async_trait::async_trait]
+impl NodeBuilder for TcpInNodeBuilder {
+    fn node_type(&self) -> &str {
+        "tcp in"
+    }
+
+    fn descriptor(&self) -> NodeDescriptor {
+        NodeDescriptor::new(self.node_type())
+            .display_name("TCP In")
+            .category("network")
+            .icon("ApiOutlined")
+            .help("监听 TCP 端口，每收到一帧启动一次 flow；消息带有连接的 Stream，可由 tcp out 回复。")
+            .inputs(&[])
+            .outputs(&["data"])
+            .config_schema(config_schema(
+                vec![
+                    addr_schema_properties(Some("0.0.0.0")),
+                    Framing::schema_properties(),
+                    Encoding::schema_properties(),
+                ],
+                &["port"],
+            ))
+    }
+
+    async fn register(&self, _: &Value) -> Result<Box<dyn NodeFactory>, NodeError> {
+        Ok(Box::new(TcpInNodeFactory))
+    }
+}
@@ -0,0 +1,152 @@
+use crate::data::{payload_data, value_to_bytes};
+use crate::http::config_schema;
+use crate::tcp::{Framing, TcpConnStream, addr_schema_properties, optional_addr};
+use rsflow_core::{
+    EngineContext, FlowContext, Node, NodeBuilder, NodeDescriptor, NodeError, NodeFactory,
+    NodeInfo, NodeInput, NodeOutput, Payload, StreamTrait, Value,
+};
+use serde_json::json;
+use std::sync::Arc;
+use tokio::net::TcpStream;
+use tokio::sync::Mutex;
+
+/// tcp out 节点
+///
+/// Payload 带有 Stream 时在该连接上回复（使用连接自身的分帧方式）；
+/// 否则连接到配置的 host:port（客户端模式，连接复用，断开后下次发送时重连）。
+/// 配置 close 为 true 时写入后关闭连接。
+pub struct TcpOutNode {
+    info: NodeInfo,
+    framing: Framing,
+    target: Option<String>,
+    close: bool,
+    client: Mutex<Option<TcpConnStream>>,
+}
+
+impl TcpOutNode {
+    async fn connect(&self, addr: &str) -> Result<TcpConnStream, NodeError> {
+        let socket = TcpStream::connect(addr).await.map_err(NodeError::Io)?;
+        let remote = socket.peer_addr().map_err(NodeError::Io)?;
+        let (_, writer) = socket.into_split();
+        Ok(TcpConnStream::new(writer, self.framing.clone(), remote))
+    }
+
+    async fn send_client(&self, addr: &str, data: &[u8]) -> Result<(), NodeError> {
+        let mut client = self.client.lock().await;
+        // 复用已有连接，失败时重连一次
+        if let Some(conn) = client.as_ref() {
+            if conn.write(data).await.is_ok() {
+                return self.finish(&mut client).await;
+            }
+            client.take();
+        }
+        let conn = self.connect(addr).await?;
+        conn.write(data).await?;
+        *client = Some(conn);
+        self.finish(&mut client).await
+    }
+
+    async fn finish(&self, client: &mut Option<TcpConnStream>) -> Result<(), NodeError> {
+        if self.close
+            && let Some(conn) = client.take()
+        {
+            conn.close().await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Node for TcpOutNode {
+    fn info(&self) -> NodeInfo {
+        self.info.clone()
+    }
+
+    async fn engine_start(&self, _: EngineContext) {}
+
+    async fn engine_stop(&self) {
+        if let Some(conn) = self.client.lock().await.take() {
+            let _ = conn.close().await;
+        }
+    }
+
+    async fn event(&self, _: &str, _: Payload, _: &FlowContext) -> Result<(), NodeError> {
+        Ok(())
+    }
+
+    async fn input(&self, node_input: NodeInput, ctx: &FlowContext) -> Result<NodeOutput, NodeError> {
+        let data = value_to_bytes(payload_data(&node_input.msg.value));
+
+        if let Some(stream_id) = node_input.msg.streams.first() {
+            let stream = ctx.get_stream(stream_id)?;
+            stream.write(&data).await?;
+            if self.close {
+                stream.close().await?;
+            }
+            return Ok(NodeOutput::None);
+        }
+
+        let Some(addr) = &self.target else {
+            return Err(NodeError::InvalidInput(
+                "tcp out: payload has no stream and no host/port configured".to_string(),
+            ));
+        };
+        self.send_client(addr, &data).await?;
+        Ok(NodeOutput::None)
+    }
+}
+
+// NodeFactory
+pub struct TcpOutNodeFactory;
+
+#[async_trait::async_trait]
+impl NodeFactory for TcpOutNodeFactory {
+    async fn create(&self, node_info: NodeInfo) -> Result<Arc<dyn Node + Send + Sync>, NodeError> {
+        let target = optional_addr(&node_info.config)?;
+        let framing = Framing::from_config(&node_info.config)?;
+        let close = match &node_info.config {
+            Value::Object(map) => matches!(map.get("close"), Some(Value::Bool(true))),
+            _ => false,
+        };
+        Ok(Arc::new(TcpOutNode {
+            info: node_info,
+            framing,
+            target,
+            close,
+            client: Mutex::new(None),
+        }))
+    }
+}
+
+// NodeBuilder
+pub struct TcpOutNodeBuilder;
+
+#[async_trait::async_trait]
+impl NodeBuilder for TcpOutNodeBuilder {
+    fn node_type(&self) -> &str {
+        "tcp out"
+    }
+
+    fn descriptor(&self) -> NodeDescriptor {
+        NodeDescriptor::new(self.node_type())
+            .display_name("TCP Out")
+            .category("network")
+            .icon("ApiOutlined")
+            .help("消息带有 tcp in 的 Stream 时在该连接上回复，否则发送到配置的 host:port。")
+            .outputs(&[])
+            .config_schema(config_schema(
+                vec![
+                    addr_schema_properties(None),
+                    Framing::schema_properties(),
+                    json!({
+                        "close": { "type": "boolean", "default": false, "description": "发送后关闭连接" }
+                    }),
+                ],
+                &[],
+            ))
+    }
+
+    async fn register(&self, _: &Value) -> Result<Box<dyn NodeFactory>, NodeError> {
+        Ok(Box::new(TcpOutNodeFactory))
+    }
+}
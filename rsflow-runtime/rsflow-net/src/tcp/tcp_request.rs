@@ -0,0 +1,131 @@
+use crate::data::{Encoding, payload_data, value_to_bytes};
+use crate::http::{config_schema, config_u64};
+use crate::tcp::{Framing, addr_schema_properties, config_addr};
+use rsflow_core::{
+    EngineContext, FlowContext, Node, NodeBuilder, NodeDescriptor, NodeError, NodeFactory,
+    NodeInfo, NodeInput, NodeOutput, Payload, Value,
+};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+/// tcp request 节点：连接、发送一帧并等待一帧响应，输出 { remote, data }
+///
+/// stream 分帧时读取到对端关闭连接为止，响应超过单帧上限（1 MiB）时报错。
+pub struct TcpRequestNode {
+    info: NodeInfo,
+    addr: String,
+    framing: Framing,
+    encoding: Encoding,
+    timeout: Duration,
+}
+
+impl TcpRequestNode {
+    async fn request(&self, data: &[u8]) -> Result<Vec<u8>, NodeError> {
+        let mut socket = TcpStream::connect(&self.addr).await.map_err(NodeError::Io)?;
+        let bytes = self.framing.encode(data)?;
+        socket.write_all(&bytes).await.map_err(NodeError::Io)?;
+
+        if self.framing == Framing::Stream {
+            return Framing::read_to_close(&mut socket)
+                .await
+                .map_err(NodeError::Io);
+        }
+
+        let mut buf = Vec::new();
+        self.framing
+            .read_frame(&mut socket, &mut buf)
+            .await
+            .map_err(NodeError::Io)?
+            .ok_or_else(|| {
+                NodeError::Io(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    "connection closed before a full response frame",
+                ))
+            })
+    }
+}
+
+#[async_trait::async_trait]
+impl Node for TcpRequestNode {
+    fn info(&self) -> NodeInfo {
+        self.info.clone()
+    }
+
+    async fn engine_start(&self, _: EngineContext) {}
+
+    async fn event(&self, _: &str, _: Payload, _: &FlowContext) -> Result<(), NodeError> {
+        Ok(())
+    }
+
+    async fn input(&self, node_input: NodeInput, _: &FlowContext) -> Result<NodeOutput, NodeError> {
+        let data = value_to_bytes(payload_data(&node_input.msg.value));
+        let response = timeout(self.timeout, self.request(&data))
+            .await
+            .map_err(|_| NodeError::Timeout)??;
+
+        let mut map = HashMap::new();
+        map.insert("remote".to_string(), Value::String(self.addr.clone()));
+        map.insert("data".to_string(), self.encoding.decode(response));
+        Ok(NodeOutput::One((0, Payload::new(Value::Object(map)))))
+    }
+}
+
+// NodeFactory
+pub struct TcpRequestNodeFactory;
+
+#[async_trait::async_trait]
+impl NodeFactory for TcpRequestNodeFactory {
+    async fn create(&self, node_info: NodeInfo) -> Result<Arc<dyn Node + Send + Sync>, NodeError> {
+        let addr = config_addr(&node_info.config, None)?;
+        let framing = Framing::from_config(&node_info.config)?;
+        let encoding = Encoding::from_config(&node_info.config)?;
+        // 超时时间，默认5秒
+        let timeout = config_u64(&node_info.config, "timeout").unwrap_or(5);
+        Ok(Arc::new(TcpRequestNode {
+            info: node_info,
+            addr,
+            framing,
+            encoding,
+            timeout: Duration::from_secs(timeout),
+        }))
+    }
+}
+
+// NodeBuilder
+pub struct TcpRequestNodeBuilder;
+
+#[async_trait::async_trait]
+impl NodeBuilder for TcpRequestNodeBuilder {
+    fn node_type(&self) -> &str {
+        "tcp request"
+    }
+
+    fn descriptor(&self) -> NodeDescriptor {
+        NodeDescriptor::new(self.node_type())
+            .display_name("TCP Request")
+            .category("network")
+            .icon("ApiOutlined")
+            .help("连接 host:port 发送请求，读取一帧响应后从端口 0 输出。")
+            .outputs(&["response"])
+            .config_schema(config_schema(
+                vec![
+                    addr_schema_properties(None),
+                    Framing::schema_properties(),
+                    Encoding::schema_properties(),
+                    json!({
+                        "timeout": { "type": "integer", "minimum": 0, "default": 5, "description": "超时（秒）" }
+                    }),
+                ],
+                &["host", "port"],
+            ))
+    }
+
+    async fn register(&self, _: &Value) -> Result<Box<dyn NodeFactory>, NodeError> {
+        Ok(Box::new(TcpRequestNodeFactory))
+    }
+}
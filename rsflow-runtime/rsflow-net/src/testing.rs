@@ -0,0 +1,207 @@
+//! 网络节点测试用的引擎、节点和客户端
+use crate::NetPlugin;
+use rsflow_core::engine::{EngineBuilder, EngineHandle};
+use rsflow_core::flow::FlowMod;
+use rsflow_core::{
+    EngineContext, FlowContext, Node, NodeBuilder, NodeError, NodeFactory, NodeInfo, NodeInput,
+    NodeOutput, NodeRunItem, Payload, Value,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use uuid::Uuid;
+
+type InputFn = Arc<dyn Fn(Payload, &FlowContext) -> Result<NodeOutput, NodeError> + Send + Sync>;
+
+/// 由 JSON 得到 Value
+pub(crate) fn value(json: serde_json::Value) -> Value {
+    serde_json::from_value(json).unwrap_or(Value::NULL)
+}
+
+/// 本机当前空闲的端口
+pub(crate) fn free_port() -> u16 {
+    std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+/// 以闭包处理输入的节点
+#[derive(Clone)]
+pub(crate) struct FnNodeBuilder {
+    node_type: &'static str,
+    input: InputFn,
+}
+
+impl FnNodeBuilder {
+    pub fn new<F>(node_type: &'static str, input: F) -> Self
+    where
+        F: Fn(Payload, &FlowContext) -> Result<NodeOutput, NodeError> + Send + Sync + 'static,
+    {
+        Self {
+            node_type,
+            input: Arc::new(input),
+        }
+    }
+}
+
+struct FnNode {
+    info: NodeInfo,
+    input: InputFn,
+}
+
+#[async_trait::async_trait]
+impl Node for FnNode {
+    fn info(&self) -> NodeInfo {
+        self.info.clone()
+    }
+
+    async fn engine_start(&self, _: EngineContext) {}
+
+    async fn event(&self, _: &str, _: Payload, _: &FlowContext) -> Result<(), NodeError> {
+        Ok(())
+    }
+
+    async fn input(&self, input: NodeInput, ctx: &FlowContext) -> Result<NodeOutput, NodeError> {
+        (self.input)(input.msg, ctx)
+    }
+}
+
+#[async_trait::async_trait]
+impl NodeFactory for FnNodeBuilder {
+    async fn create(&self, info: NodeInfo) -> Result<Arc<dyn Node + Send + Sync>, NodeError> {
+        Ok(Arc::new(FnNode {
+            info,
+            input: Arc::clone(&self.input),
+        }))
+    }
+}
+
+#[async_trait::async_trait]
+impl NodeBuilder for FnNodeBuilder {
+    fn node_type(&self) -> &str {
+        self.node_type
+    }
+
+    async fn register(&self, _: &Value) -> Result<Box<dyn NodeFactory>, NodeError> {
+        Ok(Box::new(self.clone()))
+    }
+}
+
+/// 注册了 NetPlugin 的引擎构建器
+pub(crate) fn engine_builder() -> EngineBuilder {
+    EngineBuilder::new().register_engine_plugin(NetPlugin::new())
+}
+
+/// 构建并在后台启动引擎
+pub(crate) async fn start_engine(
+    builder: EngineBuilder,
+    flow_mod: FlowMod,
+) -> (EngineHandle, tokio::task::JoinHandle<()>) {
+    let handle = builder.build_from_mod(flow_mod).await.unwrap();
+    let engine = handle.clone();
+    let task = tokio::spawn(async move { engine.start().await });
+    (handle, task)
+}
+
+/// 从节点的 0 号端口启动 flow
+pub(crate) fn start_at(node_id: Uuid, value: Value) -> NodeRunItem {
+    NodeRunItem {
+        node_id,
+        node_input: NodeInput {
+            port: 0,
+            msg: Payload::new(value),
+        },
+    }
+}
+
+/// 连接本机端口，服务尚未开始监听时重试，最长 5 秒
+pub(crate) async fn connect(port: u16) -> TcpStream {
+    for _ in 0..500 {
+        if let Ok(stream) = TcpStream::connect(("127.0.0.1", port)).await {
+            return stream;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("port {} is not listening", port);
+}
+
+/// HTTP 响应，头名为小写，分块的响应体已合并
+pub(crate) struct HttpReply {
+    pub status: u16,
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+/// 发送一个 HTTP/1.1 请求（Connection: close）并读取完整响应
+pub(crate) async fn http_request(
+    port: u16,
+    method: &str,
+    path: &str,
+    headers: &[(&str, &str)],
+    body: &str,
+) -> HttpReply {
+    let mut request = format!(
+        "{} {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Length: {}\r\n",
+        method,
+        path,
+        body.len()
+    );
+    for (name, value) in headers {
+        request.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    request.push_str("\r\n");
+    request.push_str(body);
+
+    let mut stream = connect(port).await;
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut raw = Vec::new();
+    tokio::time::timeout(Duration::from_secs(5), stream.read_to_end(&mut raw))
+        .await
+        .expect("HTTP response timed out")
+        .unwrap();
+
+    let split = raw
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .expect("incomplete HTTP response");
+    let head = String::from_utf8_lossy(&raw[..split]).to_string();
+    let mut lines = head.split("\r\n");
+    let status = lines
+        .next()
+        .and_then(|line| line.split(' ').nth(1))
+        .and_then(|code| code.parse().ok())
+        .expect("invalid status line");
+    let headers: HashMap<String, String> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+        .collect();
+    let mut body = raw[split + 4..].to_vec();
+    if headers.get("transfer-encoding").map(String::as_str) == Some("chunked") {
+        body = dechunk(&body);
+    }
+    HttpReply {
+        status,
+        headers,
+        body,
+    }
+}
+
+// 合并分块传输的响应体
+fn dechunk(mut data: &[u8]) -> Vec<u8> {
+    let mut body = Vec::new();
+    while let Some(end) = data.windows(2).position(|w| w == b"\r\n") {
+        let size = usize::from_str_radix(String::from_utf8_lossy(&data[..end]).trim(), 16)
+            .expect("invalid chunk size");
+        if size == 0 {
+            break;
+        }
+        let start = end + 2;
+        body.extend_from_slice(&data[start..start + size]);
+        data = &data[start + size + 2..];
+    }
+    body
+}
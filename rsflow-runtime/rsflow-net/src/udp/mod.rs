@@ -0,0 +1,111 @@
+mod udp_in;
+mod udp_out;
+
+pub use udp_in::{UdpInNode, UdpInNodeBuilder, UdpInNodeFactory, UdpReplyStream};
+pub use udp_out::{UdpOutNode, UdpOutNodeBuilder, UdpOutNodeFactory};
+
+// 单个数据报最大字节数
+pub(crate) const MAX_DATAGRAM: usize = 65535;
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{engine_builder, start_at, start_engine, value};
+    use rsflow_core::flow::{FlowBuilder, FlowModBuilder, NodeSpec};
+    use rsflow_core::{FlowStatus, Value};
+    use serde_json::json;
+    use std::time::Duration;
+    use tokio::net::UdpSocket;
+
+    // 本机空闲的 UDP 端口
+    fn free_udp_port() -> u16 {
+        std::net::UdpSocket::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port()
+    }
+
+    async fn recv(socket: &UdpSocket) -> Vec<u8> {
+        let mut buf = [0u8; 1024];
+        let (n, _) = tokio::time::timeout(Duration::from_secs(5), socket.recv_from(&mut buf))
+            .await
+            .expect("no datagram received")
+            .unwrap();
+        buf[..n].to_vec()
+    }
+
+    #[tokio::test]
+    async fn udp_in_replies_through_udp_out_and_releases_port() {
+        let port = free_udp_port();
+        let mut flow = FlowBuilder::new("udp");
+        let input = flow.add(
+            NodeSpec::new("udp in").config(value(json!({ "host": "127.0.0.1", "port": port }))),
+        );
+        let output = flow.add(NodeSpec::new("udp out"));
+        flow.wire(input, 0, output, 0);
+        let flow_mod = FlowModBuilder::new().flow(flow.build()).build();
+        let (handle, task) = start_engine(engine_builder(), flow_mod).await;
+
+        let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        // 监听任务启动前发送的数据报会丢失，重发直到收到回复
+        let reply = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                client.send_to(b"ping", ("127.0.0.1", port)).await.unwrap();
+                let mut buf = [0u8; 1024];
+                if let Ok(Ok((n, from))) =
+                    tokio::time::timeout(Duration::from_millis(50), client.recv_from(&mut buf))
+                        .await
+                {
+                    return (buf[..n].to_vec(), from.port());
+                }
+            }
+        })
+        .await
+        .unwrap();
+        // 从监听端口回复来源地址
+        assert_eq!(reply, (b"ping".to_vec(), port));
+
+        handle.shutdown();
+        task.await.unwrap();
+        UdpSocket::bind(("127.0.0.1", port)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn udp_out_sends_to_target_or_remote() {
+        let target = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let target_addr = target.local_addr().unwrap();
+        let mut flow = FlowBuilder::new("udp");
+        let configured = flow.add(NodeSpec::new("udp out").config(value(json!({
+            "host": "127.0.0.1", "port": target_addr.port()
+        }))));
+        let by_remote = flow.add(NodeSpec::new("udp out"));
+        let flow_mod = FlowModBuilder::new().flow(flow.build()).build();
+        let (handle, _) = start_engine(engine_builder(), flow_mod).await;
+        let engine = handle.context();
+
+        let result = engine
+            .run_flow(start_at(configured.id(), Value::String("a".to_string())))
+            .await
+            .wait()
+            .await;
+        assert_eq!(result.status, FlowStatus::Completed);
+        assert_eq!(recv(&target).await, b"a");
+
+        let msg = value(json!({ "remote": target_addr.to_string(), "data": "b" }));
+        let result = engine
+            .run_flow(start_at(by_remote.id(), msg))
+            .await
+            .wait()
+            .await;
+        assert_eq!(result.status, FlowStatus::Completed);
+        assert_eq!(recv(&target).await, b"b");
+
+        // 既没有配置也没有 remote 时报错
+        let result = engine
+            .run_flow(start_at(by_remote.id(), Value::String("c".to_string())))
+            .await
+            .wait()
+            .await;
+        assert_eq!(result.status, FlowStatus::Failed);
+    }
+}
@@ -0,0 +1,177 @@
+use crate::data::Encoding;
+use crate::http::config_schema;
+use crate::tcp::{addr_schema_properties, config_addr};
+use crate::udp::MAX_DATAGRAM;
+use rsflow_core::{
+    EngineContext, FlowContext, Node, NodeBuilder, NodeDescriptor, NodeError, NodeFactory,
+    NodeInfo, NodeInput, NodeOutput, NodeRunItem, Payload, Stream, StreamId, StreamTrait, Value,
+};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::net::UdpSocket;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// 通过监听 socket 回复到数据报的来源地址
+pub struct UdpReplyStream {
+    socket: Arc<UdpSocket>,
+    remote: SocketAddr,
+}
+
+#[async_trait::async_trait]
+impl StreamTrait for UdpReplyStream {
+    async fn write(&self, data: &[u8]) -> Result<(), NodeError> {
+        self.socket
+            .send_to(data, self.remote)
+            .await
+            .map(|_| ())
+            .map_err(NodeError::Io)
+    }
+
+    async fn close(&self) -> Result<(), NodeError> {
+        Ok(())
+    }
+}
+
+/// udp in 节点：每个数据报启动一次 flow，输出 { remote, data }
+///
+/// Payload::streams[0] 为回复来源地址的 Stream，udp out 可用它从同一端口回复。
+pub struct UdpInNode {
+    info: NodeInfo,
+    addr: String,
+    encoding: Encoding,
+    // recv 任务及其取消令牌，engine_stop 时等待任务结束以释放端口
+    task: Mutex<Option<(CancellationToken, JoinHandle<()>)>>,
+}
+
+#[async_trait::async_trait]
+impl Node for UdpInNode {
+    fn info(&self) -> NodeInfo {
+        self.info.clone()
+    }
+
+    async fn engine_start(&self, sender: EngineContext) {
+        let socket = match UdpSocket::bind(&self.addr).await {
+            Ok(socket) => Arc::new(socket),
+            Err(e) => {
+                error!("udp in {} bind {} failed: {}", self.info.name, self.addr, e);
+                return;
+            }
+        };
+        info!("UDP listening on {}", self.addr);
+
+        let cancel = CancellationToken::new();
+        let node_id = self.info.id;
+        let encoding = self.encoding;
+        let stop = cancel.clone();
+        let task = tokio::spawn(async move {
+            let mut buf = vec![0u8; MAX_DATAGRAM];
+            loop {
+                let received = tokio::select! {
+                    _ = cancel.cancelled() => break,
+                    _ = sender.stopped() => break,
+                    received = socket.recv_from(&mut buf) => received,
+                };
+                let (n, remote) = match received {
+                    Ok(received) => received,
+                    Err(e) => {
+                        warn!("udp recv failed: {}", e);
+                        continue;
+                    }
+                };
+
+                let mut map = HashMap::new();
+                map.insert("remote".to_string(), Value::String(remote.to_string()));
+                map.insert("data".to_string(), encoding.decode(buf[..n].to_vec()));
+
+                let stream_id = StreamId(Uuid::new_v4());
+                let stream: Stream = Arc::new(UdpReplyStream {
+                    socket: socket.clone(),
+                    remote,
+                });
+                let ctx = FlowContext::new(Uuid::new_v4());
+                ctx.streams.insert(stream_id, stream);
+                sender
+                    .run_flow_with_ctx(
+                        ctx,
+                        NodeRunItem {
+                            node_id,
+                            node_input: NodeInput {
+                                port: 0,
+                                msg: Payload::new_stream(Value::Object(map), stream_id),
+                            },
+                        },
+                    )
+                    .await;
+            }
+        });
+        *self.task.lock().unwrap() = Some((stop, task));
+    }
+
+    async fn engine_stop(&self) {
+        let task = self.task.lock().unwrap().take();
+        if let Some((cancel, task)) = task {
+            cancel.cancel();
+            let _ = task.await;
+        }
+    }
+
+    async fn event(&self, _: &str, _: Payload, _: &FlowContext) -> Result<(), NodeError> {
+        Ok(())
+    }
+
+    async fn input(&self, node_input: NodeInput, _: &FlowContext) -> Result<NodeOutput, NodeError> {
+        Ok(NodeOutput::One((0, node_input.msg)))
+    }
+}
+
+// NodeFactory
+pub struct UdpInNodeFactory;
+
+#[async_trait::async_trait]
+impl NodeFactory for UdpInNodeFactory {
+    async fn create(&self, node_info: NodeInfo) -> Result<Arc<dyn Node + Send + Sync>, NodeError> {
+        let addr = config_addr(&node_info.config, Some("0.0.0.0"))?;
+        let encoding = Encoding::from_config(&node_info.config)?;
+        Ok(Arc::new(UdpInNode {
+            info: node_info,
+            addr,
+            encoding,
+            task: Mutex::new(None),
+        }))
+    }
+}
+
+// NodeBuilder
+pub struct UdpInNodeBuilder;
+
+#[async_trait::async_trait]
+impl NodeBuilder for UdpInNodeBuilder {
+    fn node_type(&self) -> &str {
+        "udp in"
+    }
+
+    fn descriptor(&self) -> NodeDescriptor {
+        NodeDescriptor::new(self.node_type())
+            .display_name("UDP In")
+            .category("network")
+            .icon("ApiOutlined")
+            .help("监听 UDP 端口，每个数据报启动一次 flow；消息带有回复用的 Stream，可由 udp out 回复来源地址。")
+            .inputs(&[])
+            .outputs(&["data"])
+            .config_schema(config_schema(
+                vec![
+                    addr_schema_properties(Some("0.0.0.0")),
+                    Encoding::schema_properties(),
+                ],
+                &["port"],
+            ))
+    }
+
+    async fn register(&self, _: &Value) -> Result<Box<dyn NodeFactory>, NodeError> {
+        Ok(Box::new(UdpInNodeFactory))
+    }
+}
@@ -0,0 +1,112 @@
+use crate::data::{payload_data, value_to_bytes};
+use crate::http::config_schema;
+use crate::tcp::{addr_schema_properties, optional_addr};
+use crate::udp::MAX_DATAGRAM;
+use rsflow_core::{
+    EngineContext, FlowContext, Node, NodeBuilder, NodeDescriptor, NodeError, NodeFactory,
+    NodeInfo, NodeInput, NodeOutput, Payload, Value,
+};
+use std::sync::Arc;
+use tokio::net::UdpSocket;
+use tokio::sync::OnceCell;
+
+/// udp out 节点
+///
+/// Payload 带有 udp in 的 Stream 时从监听端口回复来源地址；
+/// 否则发送到配置的 host:port，未配置时发送到输入值中的 remote。
+pub struct UdpOutNode {
+    info: NodeInfo,
+    target: Option<String>,
+    socket: OnceCell<UdpSocket>,
+}
+
+#[async_trait::async_trait]
+impl Node for UdpOutNode {
+    fn info(&self) -> NodeInfo {
+        self.info.clone()
+    }
+
+    async fn engine_start(&self, _: EngineContext) {}
+
+    async fn event(&self, _: &str, _: Payload, _: &FlowContext) -> Result<(), NodeError> {
+        Ok(())
+    }
+
+    async fn input(&self, node_input: NodeInput, ctx: &FlowContext) -> Result<NodeOutput, NodeError> {
+        let data = value_to_bytes(payload_data(&node_input.msg.value));
+        if data.len() > MAX_DATAGRAM {
+            return Err(NodeError::InvalidInput(format!(
+                "udp datagram too large: {} bytes",
+                data.len()
+            )));
+        }
+
+        if let Some(stream_id) = node_input.msg.streams.first() {
+            ctx.get_stream(stream_id)?.write(&data).await?;
+            return Ok(NodeOutput::None);
+        }
+
+        let remote = match &node_input.msg.value {
+            Value::Object(map) => match map.get("remote") {
+                Some(Value::String(remote)) => Some(remote.clone()),
+                _ => None,
+            },
+            _ => None,
+        };
+        let Some(target) = self.target.clone().or(remote) else {
+            return Err(NodeError::InvalidInput(
+                "udp out: no host/port configured and payload has no remote".to_string(),
+            ));
+        };
+
+        let socket = self
+            .socket
+            .get_or_try_init(|| UdpSocket::bind("0.0.0.0:0"))
+            .await
+            .map_err(NodeError::Io)?;
+        socket
+            .send_to(&data, target.as_str())
+            .await
+            .map_err(NodeError::Io)?;
+        Ok(NodeOutput::None)
+    }
+}
+
+// NodeFactory
+pub struct UdpOutNodeFactory;
+
+#[async_trait::async_trait]
+impl NodeFactory for UdpOutNodeFactory {
+    async fn create(&self, node_info: NodeInfo) -> Result<Arc<dyn Node + Send + Sync>, NodeError> {
+        let target = optional_addr(&node_info.config)?;
+        Ok(Arc::new(UdpOutNode {
+            info: node_info,
+            target,
+            socket: OnceCell::new(),
+        }))
+    }
+}
+
+// NodeBuilder
+pub struct UdpOutNodeBuilder;
+
+#[async_trait::async_trait]
+impl NodeBuilder for UdpOutNodeBuilder {
+    fn node_type(&self) -> &str {
+        "udp out"
+    }
+
+    fn descriptor(&self) -> NodeDescriptor {
+        NodeDescriptor::new(self.node_type())
+            .display_name("UDP Out")
+            .category("network")
+            .icon("ApiOutlined")
+            .help("消息带有 udp in 的 Stream 时回复来源地址，否则发送到配置的 host:port。")
+            .outputs(&[])
+            .config_schema(config_schema(vec![addr_schema_properties(None)], &[]))
+    }
+
+    async fn register(&self, _: &Value) -> Result<Box<dyn NodeFactory>, NodeError> {
+        Ok(Box::new(UdpOutNodeFactory))
+    }
+}
@@ -0,0 +1,115 @@
+use crate::ws::hub::WsHub;
+use crate::data::bytes_to_value;
+use crate::ws::socket::value_to_text;
+use futures_util::{SinkExt, StreamExt};
+use rsflow_core::Value;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+/// 客户端连接任务：连接远端 URL，断开后等待 reconnect 再重连，直到取消
+pub(crate) async fn run_client(
+    hub: Arc<WsHub>,
+    url: String,
+    reconnect: Duration,
+    cancel: CancellationToken,
+) {
+    loop {
+        let connected = tokio::select! {
+            _ = cancel.cancelled() => return,
+            res = connect_async(url.as_str()) => res,
+        };
+
+        match connected {
+            Ok((socket, _)) => {
+                info!("WebSocket client connected: {}", url);
+                let mut session = hub.open(&url).await;
+                let conn_id = session.conn_id;
+                let (mut sink, mut stream) = socket.split();
+
+                loop {
+                    tokio::select! {
+                        _ = cancel.cancelled() => {
+                            let _ = sink.send(Message::Close(None)).await;
+                            break;
+                        }
+                        _ = session.kicked.cancelled() => {
+                            let _ = sink.send(Message::Close(None)).await;
+                            break;
+                        }
+                        Some(val) = session.rx.recv() => {
+                            if sink.send(Message::Text(value_to_text(val))).await.is_err() {
+                                break;
+                            }
+                        }
+                        frame = stream.next() => match frame {
+                            Some(Ok(Message::Text(text))) => hub.frame(conn_id, &url, Value::String(text)).await,
+                            Some(Ok(Message::Binary(bytes))) => hub.frame(conn_id, &url, bytes_to_value(bytes)).await,
+                            Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                            Some(Ok(_)) => {}
+                        },
+                    }
+                }
+
+                hub.close(conn_id).await;
+                info!("WebSocket client disconnected: {}", url);
+            }
+            Err(e) => {
+                warn!("WebSocket client connect {} failed: {}", url, e);
+            }
+        }
+
+        tokio::select! {
+            _ = cancel.cancelled() => return,
+            _ = tokio::time::sleep(reconnect) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::accept_async;
+
+    #[tokio::test]
+    async fn reconnects_after_server_closes() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let hub = Arc::new(WsHub::new());
+        hub.acquire_client(&url, Duration::from_millis(50));
+
+        // 第一个连接由服务端关闭
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut first = accept_async(stream).await.unwrap();
+        first.close(None).await.unwrap();
+
+        let (stream, _) = tokio::time::timeout(Duration::from_secs(5), listener.accept())
+            .await
+            .expect("client did not reconnect")
+            .unwrap();
+        let mut second = accept_async(stream).await.unwrap();
+
+        // 重连后的连接注册在同一个 URL 下
+        let sent = async {
+            loop {
+                if hub.broadcast(&url, Value::String("again".to_string())) == 1 {
+                    return;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(5), sent)
+            .await
+            .unwrap();
+        match second.next().await {
+            Some(Ok(Message::Text(text))) => assert_eq!(text, "again"),
+            other => panic!("unexpected frame: {:?}", other),
+        }
+
+        hub.release_client(&url);
+    }
+}
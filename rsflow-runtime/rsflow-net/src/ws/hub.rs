@@ -0,0 +1,349 @@
+use crate::ws::client::run_client;
+use rsflow_core::{
+    EngineContext, FlowCallback, FlowContext, FlowEventKey, NodeInput, NodeRunItem, Payload,
+    Value,
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio_util::sync::CancellationToken;
+use tracing::warn;
+use uuid::Uuid;
+
+/// websocket in 节点端口：0 收到的帧，1 连接事件（open / close）
+pub const FRAME_PORT: u8 = 0;
+pub const STATUS_PORT: u8 = 1;
+
+// 每个连接待发送的帧数上限，客户端读取过慢写满时断开该连接
+const SEND_QUEUE_LEN: usize = 256;
+
+/// 监听某个路径（服务端）或 URL（客户端）的 websocket in 节点
+#[derive(Clone)]
+pub struct WsListener {
+    pub node_id: Uuid,
+    pub engine: EngineContext,
+}
+
+#[derive(Clone)]
+struct WsConnection {
+    conn_id: Uuid,
+    path: String,
+    tx: mpsc::Sender<Value>,
+    // 发送队列写满时取消，连接任务随之关闭连接
+    kick: CancellationToken,
+}
+
+impl WsConnection {
+    fn push(&self, data: Value) -> bool {
+        match self.tx.try_send(data) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => {
+                if !self.kick.is_cancelled() {
+                    warn!(
+                        "WebSocket connection {} on {} is too slow, disconnecting",
+                        self.conn_id, self.path
+                    );
+                    self.kick.cancel();
+                }
+                false
+            }
+            Err(TrySendError::Closed(_)) => false,
+        }
+    }
+}
+
+/// WsHub::open 返回的连接，由连接任务持有
+pub(crate) struct WsSession {
+    pub conn_id: Uuid,
+    /// 待发送到该连接的值
+    pub rx: mpsc::Receiver<Value>,
+    /// 发送队列写满时取消，连接任务应关闭连接并调用 WsHub::close
+    pub kicked: CancellationToken,
+}
+
+struct WsClient {
+    refs: usize,
+    cancel: CancellationToken,
+}
+
+/// websocket 连接表，服务端和客户端连接共用
+///
+/// 服务端连接以路由路径为键，客户端连接以远端 URL 为键。
+pub struct WsHub {
+    connections: RwLock<HashMap<Uuid, WsConnection>>,
+    listeners: RwLock<HashMap<String, Vec<WsListener>>>,
+    // 服务端路径引用计数（in / out 节点都会注册）
+    paths: Mutex<HashMap<String, usize>>,
+    clients: Mutex<HashMap<String, WsClient>>,
+}
+
+impl WsHub {
+    pub fn new() -> Self {
+        Self {
+            connections: RwLock::new(HashMap::new()),
+            listeners: RwLock::new(HashMap::new()),
+            paths: Mutex::new(HashMap::new()),
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn add_listener(&self, key: &str, listener: WsListener) {
+        self.listeners
+            .write()
+            .unwrap()
+            .entry(key.to_string())
+            .or_default()
+            .push(listener);
+    }
+
+    pub fn remove_listener(&self, key: &str, node_id: Uuid) {
+        let mut listeners = self.listeners.write().unwrap();
+        if let Some(list) = listeners.get_mut(key) {
+            list.retain(|l| l.node_id != node_id);
+            if list.is_empty() {
+                listeners.remove(key);
+            }
+        }
+    }
+
+    fn listeners_of(&self, key: &str) -> Vec<WsListener> {
+        self.listeners
+            .read()
+            .unwrap()
+            .get(key)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// 注册服务端路径
+    pub fn acquire_path(&self, path: &str) {
+        *self.paths.lock().unwrap().entry(path.to_string()).or_default() += 1;
+    }
+
+    pub fn release_path(&self, path: &str) {
+        let mut paths = self.paths.lock().unwrap();
+        if let Some(refs) = paths.get_mut(path) {
+            *refs -= 1;
+            if *refs == 0 {
+                paths.remove(path);
+            }
+        }
+    }
+
+    pub fn has_path(&self, path: &str) -> bool {
+        self.paths.lock().unwrap().contains_key(path)
+    }
+
+    /// 引用客户端连接，首次引用时启动连接任务（断线自动重连）
+    pub fn acquire_client(self: &Arc<Self>, url: &str, reconnect: Duration) {
+        let mut clients = self.clients.lock().unwrap();
+        if let Some(client) = clients.get_mut(url) {
+            client.refs += 1;
+            return;
+        }
+        let cancel = CancellationToken::new();
+        clients.insert(
+            url.to_string(),
+            WsClient {
+                refs: 1,
+                cancel: cancel.clone(),
+            },
+        );
+        tokio::spawn(run_client(self.clone(), url.to_string(), reconnect, cancel));
+    }
+
+    pub fn release_client(&self, url: &str) {
+        let mut clients = self.clients.lock().unwrap();
+        if let Some(client) = clients.get_mut(url) {
+            client.refs -= 1;
+            if client.refs == 0 {
+                client.cancel.cancel();
+                clients.remove(url);
+            }
+        }
+    }
+
+    /// 连接建立
+    pub(crate) async fn open(&self, key: &str) -> WsSession {
+        let conn_id = Uuid::new_v4();
+        let (tx, rx) = mpsc::channel(SEND_QUEUE_LEN);
+        let kick = CancellationToken::new();
+        self.connections.write().unwrap().insert(
+            conn_id,
+            WsConnection {
+                conn_id,
+                path: key.to_string(),
+                tx,
+                kick: kick.clone(),
+            },
+        );
+        self.notify(conn_id, key, "open").await;
+        WsSession {
+            conn_id,
+            rx,
+            kicked: kick,
+        }
+    }
+
+    /// 连接关闭
+    pub async fn close(&self, conn_id: Uuid) {
+        let removed = self.connections.write().unwrap().remove(&conn_id);
+        if let Some(conn) = removed {
+            self.notify(conn_id, &conn.path, "close").await;
+        }
+    }
+
+    // 通过 Node::event 通知 websocket in 节点连接状态变化
+    async fn notify(&self, conn_id: Uuid, key: &str, event_type: &str) {
+        for listener in self.listeners_of(key) {
+            listener
+                .engine
+                .node_send(
+                    listener.node_id,
+                    FlowContext::new(Uuid::new_v4()),
+                    event_type.to_string(),
+                    Payload::new(frame_value(conn_id, key, Value::NULL)),
+                )
+                .await;
+        }
+    }
+
+    /// 收到一帧数据，为每个监听节点启动一次 flow
+    pub async fn frame(&self, conn_id: Uuid, key: &str, data: Value) {
+        for listener in self.listeners_of(key) {
+            let ctx = self.reply_context(conn_id).await;
+            listener
+                .engine
+                .run_flow_with_ctx(
+                    ctx,
+                    NodeRunItem {
+                        node_id: listener.node_id,
+                        node_input: NodeInput {
+                            port: FRAME_PORT,
+                            msg: Payload::new(frame_value(conn_id, key, data.clone())),
+                        },
+                    },
+                )
+                .await;
+        }
+    }
+
+    /// 创建 flow 上下文，并监听 FlowEventKey::Message(conn_id) 回写到该连接
+    pub async fn reply_context(&self, conn_id: Uuid) -> FlowContext {
+        let ctx = FlowContext::new(Uuid::new_v4());
+        let conn = self.connections.read().unwrap().get(&conn_id).cloned();
+        if let Some(conn) = conn {
+            let callback: FlowCallback = Arc::new(move |val| {
+                conn.push(val);
+                Box::pin(async {})
+            });
+            ctx.listeners
+                .on(FlowEventKey::Message(conn_id), callback)
+                .await;
+        }
+        ctx
+    }
+
+    /// 直接写入某个连接
+    pub fn send(&self, conn_id: Uuid, data: Value) -> bool {
+        match self.connections.read().unwrap().get(&conn_id) {
+            Some(conn) => conn.push(data),
+            None => false,
+        }
+    }
+
+    /// 广播到某个路径（或客户端 URL）上的所有连接，返回写入的连接数
+    pub fn broadcast(&self, key: &str, data: Value) -> usize {
+        self.connections
+            .read()
+            .unwrap()
+            .values()
+            .filter(|conn| conn.path == key)
+            .filter(|conn| conn.push(data.clone()))
+            .count()
+    }
+}
+
+impl Default for WsHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// websocket in 输出的值：{ conn_id, path, data }
+pub(crate) fn frame_value(conn_id: Uuid, key: &str, data: Value) -> Value {
+    let mut map = HashMap::new();
+    map.insert("conn_id".to_string(), Value::String(conn_id.to_string()));
+    map.insert("path".to_string(), Value::String(key.to_string()));
+    map.insert("data".to_string(), data);
+    Value::Object(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(s: &str) -> Value {
+        Value::String(s.to_string())
+    }
+
+    #[tokio::test]
+    async fn send_and_broadcast() {
+        let hub = WsHub::new();
+        let mut a = hub.open("/a").await;
+        let mut b = hub.open("/a").await;
+        let mut other = hub.open("/b").await;
+
+        assert!(hub.send(a.conn_id, text("direct")));
+        assert_eq!(a.rx.recv().await, Some(text("direct")));
+
+        assert_eq!(hub.broadcast("/a", text("all")), 2);
+        assert_eq!(a.rx.recv().await, Some(text("all")));
+        assert_eq!(b.rx.recv().await, Some(text("all")));
+        assert!(other.rx.try_recv().is_err());
+
+        // 关闭后不再写入
+        hub.close(a.conn_id).await;
+        assert!(!hub.send(a.conn_id, text("late")));
+        assert_eq!(hub.broadcast("/a", text("again")), 1);
+        assert_eq!(b.rx.recv().await, Some(text("again")));
+    }
+
+    #[tokio::test]
+    async fn reply_context_routes_to_connection() {
+        let hub = WsHub::new();
+        let mut session = hub.open("/a").await;
+        let ctx = hub.reply_context(session.conn_id).await;
+        let key = FlowEventKey::Message(session.conn_id);
+        assert_eq!(ctx.listeners.emit(&key, text("reply")).await, 1);
+        // 监听可多次触发
+        assert_eq!(ctx.listeners.emit(&key, text("again")).await, 1);
+        assert_eq!(session.rx.recv().await, Some(text("reply")));
+        assert_eq!(session.rx.recv().await, Some(text("again")));
+
+        // 已关闭的连接没有回写监听
+        hub.close(session.conn_id).await;
+        let ctx = hub.reply_context(session.conn_id).await;
+        assert_eq!(ctx.listeners.emit(&key, text("lost")).await, 0);
+    }
+
+    #[tokio::test]
+    async fn full_queue_kicks_connection() {
+        let hub = WsHub::new();
+        let session = hub.open("/a").await;
+        let mut fast = hub.open("/a").await;
+        for i in 0..SEND_QUEUE_LEN {
+            assert!(hub.send(session.conn_id, Value::Int(i as i32)));
+        }
+        assert!(!session.kicked.is_cancelled());
+
+        // 队列已满：写入失败并断开该连接，其他连接不受影响
+        assert!(!hub.send(session.conn_id, text("overflow")));
+        assert!(session.kicked.is_cancelled());
+        assert!(!fast.kicked.is_cancelled());
+        assert_eq!(hub.broadcast("/a", text("next")), 1);
+        assert_eq!(fast.rx.recv().await, Some(text("next")));
+    }
+}
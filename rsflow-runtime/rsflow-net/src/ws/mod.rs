@@ -0,0 +1,92 @@
+mod client;
+mod hub;
+mod socket;
+mod ws_in;
+mod ws_out;
+
+pub use hub::{FRAME_PORT, STATUS_PORT, WsHub, WsListener};
+pub use ws_in::{WsInNode, WsInNodeBuilder, WsInNodeFactory};
+pub use ws_out::{WsOutNode, WsOutNodeBuilder, WsOutNodeFactory};
+
+pub(crate) use socket::serve_socket;
+
+use crate::http::{config_schema, config_str, config_u64};
+use rsflow_core::{NodeError, Value};
+use serde_json::json;
+use std::time::Duration;
+
+/// 节点连接目标：服务端路径或客户端 URL
+#[derive(Debug, Clone)]
+pub enum WsEndpoint {
+    Server { path: String },
+    Client { url: String, reconnect: Duration },
+}
+
+impl WsEndpoint {
+    /// 从节点配置解析：`path` 为服务端模式，`url`（ws:// 或 wss://）为客户端模式
+    pub fn from_config(config: &Value) -> Result<Self, NodeError> {
+        if let Some(url) = config_str(config, "url") {
+            if !url.starts_with("ws://") && !url.starts_with("wss://") {
+                return Err(NodeError::InvalidConfig(format!(
+                    "websocket url must start with ws:// or wss://: {}",
+                    url
+                )));
+            }
+            // 断线重连间隔，默认3秒
+            let reconnect = config_u64(config, "reconnect").unwrap_or(3);
+            return Ok(WsEndpoint::Client {
+                url: url.to_string(),
+                reconnect: Duration::from_secs(reconnect),
+            });
+        }
+        match config_str(config, "path") {
+            Some(path) if path.starts_with('/') => Ok(WsEndpoint::Server {
+                path: path.to_string(),
+            }),
+            Some(path) => Err(NodeError::InvalidConfig(format!(
+                "websocket path must start with '/': {}",
+                path
+            ))),
+            None => Err(NodeError::InvalidConfig(
+                "websocket node requires config.path (server) or config.url (client)".to_string(),
+            )),
+        }
+    }
+
+    /// 节点配置的 JSON Schema：path 或 url 二选一，extra 为节点自身的字段
+    pub(crate) fn config_schema(extra: serde_json::Value) -> serde_json::Value {
+        let endpoint = json!({
+            "path": { "type": "string", "pattern": "^/", "description": "服务端模式的路径" },
+            "url": { "type": "string", "pattern": "^wss?://", "description": "客户端模式连接的地址" },
+            "reconnect": { "type": "integer", "minimum": 0, "default": 3, "description": "客户端断线重连间隔（秒）" }
+        });
+        let mut schema = config_schema(vec![endpoint, extra], &[]);
+        schema["anyOf"] = json!([{ "required": ["path"] }, { "required": ["url"] }]);
+        schema
+    }
+
+    /// 连接表中的键
+    pub fn key(&self) -> &str {
+        match self {
+            WsEndpoint::Server { path } => path,
+            WsEndpoint::Client { url, .. } => url,
+        }
+    }
+}
+
+impl WsHub {
+    /// 引用节点的连接目标：服务端注册路径，客户端启动连接
+    pub fn acquire_endpoint(self: &std::sync::Arc<Self>, endpoint: &WsEndpoint) {
+        match endpoint {
+            WsEndpoint::Server { path } => self.acquire_path(path),
+            WsEndpoint::Client { url, reconnect } => self.acquire_client(url, *reconnect),
+        }
+    }
+
+    pub fn release_endpoint(&self, endpoint: &WsEndpoint) {
+        match endpoint {
+            WsEndpoint::Server { path } => self.release_path(path),
+            WsEndpoint::Client { url, .. } => self.release_client(url),
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use crate::data::bytes_to_value;
+use crate::ws::hub::WsHub;
+use axum::extract::ws::{Message, WebSocket};
+use futures_util::{SinkExt, StreamExt};
+use rsflow_core::Value;
+use std::sync::Arc;
+
+// 出站值转为文本帧：字符串原样发送，其余序列化为 JSON
+pub(crate) fn value_to_text(val: Value) -> String {
+    match val {
+        Value::String(s) => s,
+        other => serde_json::to_string(&other).unwrap_or_default(),
+    }
+}
+
+/// 驱动一个服务端连接，直到任一方关闭
+pub(crate) async fn serve_socket(hub: Arc<WsHub>, path: String, socket: WebSocket) {
+    let mut session = hub.open(&path).await;
+    let conn_id = session.conn_id;
+    let (mut sink, mut stream) = socket.split();
+
+    loop {
+        tokio::select! {
+            _ = session.kicked.cancelled() => {
+                let _ = sink.send(Message::Close(None)).await;
+                break;
+            }
+            Some(val) = session.rx.recv() => {
+                if sink.send(Message::Text(value_to_text(val))).await.is_err() {
+                    break;
+                }
+            }
+            frame = stream.next() => match frame {
+                Some(Ok(Message::Text(text))) => hub.frame(conn_id, &path, Value::String(text)).await,
+                Some(Ok(Message::Binary(bytes))) => hub.frame(conn_id, &path, bytes_to_value(bytes)).await,
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+
+    hub.close(conn_id).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{connect, engine_builder, free_port, start_engine, value};
+    use futures_util::{SinkExt, StreamExt};
+    use rsflow_core::flow::{FlowBuilder, FlowModBuilder, NodeSpec};
+    use serde_json::json;
+    use std::time::Duration;
+    use tokio::net::TcpStream;
+    use tokio_tungstenite::tungstenite::Message;
+    use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async};
+
+    type Client = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+    async fn next_text(client: &mut Client) -> Option<String> {
+        let frame = tokio::time::timeout(Duration::from_millis(500), client.next()).await;
+        match frame {
+            Ok(Some(Ok(Message::Text(text)))) => Some(text),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn replies_to_sending_connection() {
+        let port = free_port();
+        let mut flow = FlowBuilder::new("ws");
+        let input = flow.add(NodeSpec::new("websocket in").config(value(json!({ "path": "/ws" }))));
+        let output =
+            flow.add(NodeSpec::new("websocket out").config(value(json!({ "path": "/ws" }))));
+        flow.wire(input, 0, output, 0);
+        let flow_mod = FlowModBuilder::new()
+            .plugin_config(
+                "net",
+                value(json!({ "http": { "http_port": port, "host": "127.0.0.1" } })),
+            )
+            .flow(flow.build())
+            .build();
+        let (_handle, _) = start_engine(engine_builder(), flow_mod).await;
+        connect(port).await;
+
+        let url = format!("ws://127.0.0.1:{}/ws", port);
+        let (mut a, _) = connect_async(url.as_str()).await.unwrap();
+        let (mut b, _) = connect_async(url.as_str()).await.unwrap();
+        a.send(Message::Text("from a".to_string())).await.unwrap();
+        b.send(Message::Text("from b".to_string())).await.unwrap();
+
+        // 回写只到达发送帧的连接
+        assert_eq!(next_text(&mut a).await.as_deref(), Some("from a"));
+        assert_eq!(next_text(&mut b).await.as_deref(), Some("from b"));
+        assert_eq!(next_text(&mut a).await, None);
+    }
+}
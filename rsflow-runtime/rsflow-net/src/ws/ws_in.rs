@@ -0,0 +1,146 @@
+use crate::ws::hub::{STATUS_PORT, WsHub, WsListener};
+use crate::ws::WsEndpoint;
+use rsflow_core::{
+    EngineContext, FlowContext, Node, NodeBuilder, NodeDescriptor, NodeError, NodeFactory,
+    NodeInfo, NodeInput, NodeOutput, NodeRunItem, Payload, Value,
+};
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// websocket in 节点：每个入站帧启动一次 flow
+///
+/// 端口 0 输出 { conn_id, path, data }；端口 1 输出连接事件
+/// { conn_id, path, event }，event 为 open / close。
+pub struct WsInNode {
+    info: NodeInfo,
+    hub: Arc<WsHub>,
+    endpoint: WsEndpoint,
+    engine: Mutex<Option<EngineContext>>,
+}
+
+#[async_trait::async_trait]
+impl Node for WsInNode {
+    fn info(&self) -> NodeInfo {
+        self.info.clone()
+    }
+
+    async fn engine_start(&self, sender: EngineContext) {
+        *self.engine.lock().unwrap() = Some(sender.clone());
+        self.hub.add_listener(
+            self.endpoint.key(),
+            WsListener {
+                node_id: self.info.id,
+                engine: sender,
+            },
+        );
+        self.hub.acquire_endpoint(&self.endpoint);
+    }
+
+    async fn engine_stop(&self) {
+        self.hub.remove_listener(self.endpoint.key(), self.info.id);
+        self.hub.release_endpoint(&self.endpoint);
+        self.engine.lock().unwrap().take();
+    }
+
+    /// 连接 open / close 事件，从端口 1 启动 flow
+    async fn event(
+        &self,
+        event_type: &str,
+        payload: Payload,
+        _: &FlowContext,
+    ) -> Result<(), NodeError> {
+        let Some(engine) = self.engine.lock().unwrap().clone() else {
+            return Ok(());
+        };
+        let Value::Object(mut map) = payload.value else {
+            return Err(NodeError::InvalidInput(
+                "websocket event payload must be an object".to_string(),
+            ));
+        };
+        map.remove("data");
+        map.insert("event".to_string(), Value::String(event_type.to_string()));
+
+        // open 事件的 flow 可以直接回写到该连接
+        let conn_id = match map.get("conn_id") {
+            Some(Value::String(s)) => Uuid::parse_str(s).ok(),
+            _ => None,
+        };
+        let ctx = match conn_id {
+            Some(conn_id) => self.hub.reply_context(conn_id).await,
+            None => FlowContext::new(Uuid::new_v4()),
+        };
+
+        engine
+            .run_flow_with_ctx(
+                ctx,
+                NodeRunItem {
+                    node_id: self.info.id,
+                    node_input: NodeInput {
+                        port: STATUS_PORT,
+                        msg: Payload::new(Value::Object(map)),
+                    },
+                },
+            )
+            .await;
+        Ok(())
+    }
+
+    async fn input(&self, node_input: NodeInput, _: &FlowContext) -> Result<NodeOutput, NodeError> {
+        // 输入端口与输出端口一一对应
+        Ok(NodeOutput::One((node_input.port, node_input.msg)))
+    }
+}
+
+// NodeFactory
+pub struct WsInNodeFactory {
+    hub: Arc<WsHub>,
+}
+
+#[async_trait::async_trait]
+impl NodeFactory for WsInNodeFactory {
+    async fn create(&self, node_info: NodeInfo) -> Result<Arc<dyn Node + Send + Sync>, NodeError> {
+        let endpoint = WsEndpoint::from_config(&node_info.config)?;
+        Ok(Arc::new(WsInNode {
+            info: node_info,
+            hub: self.hub.clone(),
+            endpoint,
+            engine: Mutex::new(None),
+        }))
+    }
+}
+
+// NodeBuilder
+pub struct WsInNodeBuilder {
+    hub: Arc<WsHub>,
+}
+
+impl WsInNodeBuilder {
+    pub fn new(hub: Arc<WsHub>) -> Self {
+        Self { hub }
+    }
+}
+
+#[async_trait::async_trait]
+impl NodeBuilder for WsInNodeBuilder {
+    fn node_type(&self) -> &str {
+        "websocket in"
+    }
+
+    fn descriptor(&self) -> NodeDescriptor {
+        NodeDescriptor::new(self.node_type())
+            .display_name("WebSocket In")
+            .category("network")
+            .icon("ApiOutlined")
+            .help("为每个入站帧启动一次 flow，端口 0 输出 { conn_id, path, data }，端口 1 输出连接事件。配置 path 为服务端模式，url 为客户端模式。")
+            .inputs(&[])
+            .outputs(&["message", "status"])
+            .config_schema(WsEndpoint::config_schema(json!({})))
+    }
+
+    async fn register(&self, _: &Value) -> Result<Box<dyn NodeFactory>, NodeError> {
+        Ok(Box::new(WsInNodeFactory {
+            hub: self.hub.clone(),
+        }))
+    }
+}
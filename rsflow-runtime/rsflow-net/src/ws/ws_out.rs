@@ -0,0 +1,133 @@
+use crate::ws::WsEndpoint;
+use crate::ws::hub::WsHub;
+use rsflow_core::{
+    EngineContext, FlowContext, FlowEventKey, Node, NodeBuilder, NodeDescriptor, NodeError,
+    NodeFactory, NodeInfo, NodeInput, NodeOutput, Payload, Value,
+};
+use serde_json::json;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// websocket out 节点
+///
+/// 输入值带 conn_id 时通过 FlowEventKey::Message(conn_id) 写回该连接；
+/// 没有 conn_id 或配置 broadcast 为 true 时广播到 path / url 上的所有连接。
+/// 输入值为对象且包含 data 时只发送 data。
+pub struct WsOutNode {
+    info: NodeInfo,
+    hub: Arc<WsHub>,
+    endpoint: WsEndpoint,
+    broadcast: bool,
+}
+
+#[async_trait::async_trait]
+impl Node for WsOutNode {
+    fn info(&self) -> NodeInfo {
+        self.info.clone()
+    }
+
+    async fn engine_start(&self, _: EngineContext) {
+        self.hub.acquire_endpoint(&self.endpoint);
+    }
+
+    async fn engine_stop(&self) {
+        self.hub.release_endpoint(&self.endpoint);
+    }
+
+    async fn event(&self, _: &str, _: Payload, _: &FlowContext) -> Result<(), NodeError> {
+        Ok(())
+    }
+
+    async fn input(&self, node_input: NodeInput, ctx: &FlowContext) -> Result<NodeOutput, NodeError> {
+        let (conn_id, data) = match node_input.msg.value {
+            Value::Object(mut map) => {
+                let conn_id = match map.get("conn_id") {
+                    Some(Value::String(s)) => Uuid::parse_str(s).ok(),
+                    _ => None,
+                };
+                let data = match map.remove("data") {
+                    Some(data) => data,
+                    None => Value::Object(map),
+                };
+                (conn_id, data)
+            }
+            other => (None, other),
+        };
+
+        match conn_id {
+            Some(conn_id) if !self.broadcast => {
+                let key = FlowEventKey::Message(conn_id);
+                if ctx.listeners.emit(&key, data.clone()).await == 0
+                    && !self.hub.send(conn_id, data)
+                {
+                    return Err(NodeError::InvalidInput(format!(
+                        "websocket connection {} is closed",
+                        conn_id
+                    )));
+                }
+            }
+            _ => {
+                self.hub.broadcast(self.endpoint.key(), data);
+            }
+        }
+        Ok(NodeOutput::None)
+    }
+}
+
+// NodeFactory
+pub struct WsOutNodeFactory {
+    hub: Arc<WsHub>,
+}
+
+#[async_trait::async_trait]
+impl NodeFactory for WsOutNodeFactory {
+    async fn create(&self, node_info: NodeInfo) -> Result<Arc<dyn Node + Send + Sync>, NodeError> {
+        let endpoint = WsEndpoint::from_config(&node_info.config)?;
+        let broadcast = match &node_info.config {
+            Value::Object(map) => matches!(map.get("broadcast"), Some(Value::Bool(true))),
+            _ => false,
+        };
+        Ok(Arc::new(WsOutNode {
+            info: node_info,
+            hub: self.hub.clone(),
+            endpoint,
+            broadcast,
+        }))
+    }
+}
+
+// NodeBuilder
+pub struct WsOutNodeBuilder {
+    hub: Arc<WsHub>,
+}
+
+impl WsOutNodeBuilder {
+    pub fn new(hub: Arc<WsHub>) -> Self {
+        Self { hub }
+    }
+}
+
+#[async_trait::async_trait]
+impl NodeBuilder for WsOutNodeBuilder {
+    fn node_type(&self) -> &str {
+        "websocket out"
+    }
+
+    fn descriptor(&self) -> NodeDescriptor {
+        NodeDescriptor::new(self.node_type())
+            .display_name("WebSocket Out")
+            .category("network")
+            .icon("ApiOutlined")
+            .help("输入值带 conn_id 时写回该连接，否则广播到 path / url 上的所有连接；值包含 data 时只发送 data。")
+            .outputs(&[])
+            .config_schema(WsEndpoint::config_schema(json!({
+                "broadcast": { "type": "boolean", "default": false, "description": "忽略 conn_id，发送到所有连接" }
+            })))
+    }
+
+    async fn register(&self, _: &Value) -> Result<Box<dyn NodeFactory>, NodeError> {
+        Ok(Box::new(WsOutNodeFactory {
+            hub: self.hub.clone(),
+        }))
+    }
+}
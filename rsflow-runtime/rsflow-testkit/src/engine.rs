@@ -0,0 +1,153 @@
+use rsflow_core::flow::{FlowMod, PluginConfig};
+use rsflow_core::{
+    DebugTap, EngineConfig, EngineContext, EngineMessage, EngineMetrics, FlowContext, NodeRunItem,
+    Payload, Value,
+};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// 节点通过 EngineContext 发起的调用
+pub enum EngineCall {
+    /// run_flow / run_flow_with_ctx
+    RunFlow {
+        ctx: FlowContext,
+        start_node: NodeRunItem,
+    },
+    /// node_send
+    NodeEvent {
+        node_id: Uuid,
+        ctx: FlowContext,
+        event_type: String,
+        payload: Payload,
+    },
+}
+
+impl EngineCall {
+    /// 目标节点
+    pub fn node_id(&self) -> Uuid {
+        match self {
+            EngineCall::RunFlow { start_node, .. } => start_node.node_id,
+            EngineCall::NodeEvent { node_id, .. } => *node_id,
+        }
+    }
+
+    pub fn payload(&self) -> &Payload {
+        match self {
+            EngineCall::RunFlow { start_node, .. } => &start_node.node_input.msg,
+            EngineCall::NodeEvent { payload, .. } => payload,
+        }
+    }
+
+    pub fn ctx(&self) -> &FlowContext {
+        match self {
+            EngineCall::RunFlow { ctx, .. } | EngineCall::NodeEvent { ctx, .. } => ctx,
+        }
+    }
+}
+
+/// 不执行任何 flow 的引擎，记录节点通过 EngineContext 发起的调用
+///
+/// 记录中保留 FlowContext，丢弃记录后对应运行的 FlowHandle 才会结束（状态为 Rejected）。
+/// 消息队列长度为 config.msg_len，节点发送较多消息时需要及时取出。
+pub struct FakeEngine {
+    context: EngineContext,
+    receiver: mpsc::Receiver<EngineMessage>,
+}
+
+impl FakeEngine {
+    pub fn new() -> Self {
+        Self::with_flow_mod(empty_flow_mod())
+    }
+
+    /// 使用指定的 FlowMod（EngineContext::flow_mod）
+    pub fn with_flow_mod(flow_mod: FlowMod) -> Self {
+        let (sender, receiver) = mpsc::channel(flow_mod.config.msg_len.max(1));
+        Self {
+            context: EngineContext {
+                flow_mod: Arc::new(RwLock::new(Arc::new(flow_mod))),
+                sender,
+                shutdown: CancellationToken::new(),
+                debug: Arc::new(DebugTap::default()),
+                metrics: Arc::new(EngineMetrics::new()),
+            },
+            receiver,
+        }
+    }
+
+    /// 传给 Node::engine_start 或插件的上下文
+    pub fn context(&self) -> EngineContext {
+        self.context.clone()
+    }
+
+    /// 取出已记录的调用，不等待
+    pub fn take_calls(&mut self) -> Vec<EngineCall> {
+        let mut calls = Vec::new();
+        while let Ok(msg) = self.receiver.try_recv() {
+            calls.extend(Self::call(msg));
+        }
+        calls
+    }
+
+    /// 等待下一次调用，超时返回 None
+    pub async fn next_call(&mut self, timeout: Duration) -> Option<EngineCall> {
+        tokio::time::timeout(timeout, async {
+            while let Some(msg) = self.receiver.recv().await {
+                if let Some(call) = Self::call(msg) {
+                    return Some(call);
+                }
+            }
+            None
+        })
+        .await
+        .ok()
+        .flatten()
+    }
+
+    /// 触发引擎停止信号，EngineContext::stopped 返回
+    pub fn shutdown(&self) {
+        self.context.shutdown.cancel();
+    }
+
+    fn call(msg: EngineMessage) -> Option<EngineCall> {
+        match msg {
+            EngineMessage::RunFlow { ctx, start_node } => {
+                Some(EngineCall::RunFlow { ctx, start_node })
+            }
+            EngineMessage::NodeEvent {
+                node_id,
+                ctx,
+                event_type,
+                payload,
+            } => Some(EngineCall::NodeEvent {
+                node_id,
+                ctx,
+                event_type,
+                payload,
+            }),
+            EngineMessage::Stop => None,
+        }
+    }
+}
+
+impl Default for FakeEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 没有 flow 的 FlowMod
+pub fn empty_flow_mod() -> FlowMod {
+    FlowMod {
+        config: EngineConfig {
+            msg_len: 1000,
+            shutdown_timeout: 10,
+            debug: Default::default(),
+        },
+        plugin_config: PluginConfig::default(),
+        node_global_config: Value::NULL,
+        flow: Vec::new(),
+    }
+}
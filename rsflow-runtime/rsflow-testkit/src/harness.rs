@@ -0,0 +1,78 @@
+use crate::FakeEngine;
+use rsflow_core::{
+    FlowContext, Node, NodeBuilder, NodeError, NodeInfo, NodeInput, NodeOutput, Payload, Value,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// 与引擎相同的方式创建节点：register(info.global_config) 后由工厂创建
+pub async fn create_node(
+    builder: &dyn NodeBuilder,
+    info: NodeInfo,
+) -> Result<Arc<dyn Node + Send + Sync>, NodeError> {
+    let factory = builder.register(&info.global_config).await?;
+    factory.create(info).await
+}
+
+/// 单个节点的测试驱动：节点、FakeEngine 和同一次运行共用的 FlowContext
+pub struct NodeHarness {
+    node: Arc<dyn Node + Send + Sync>,
+    engine: FakeEngine,
+    ctx: FlowContext,
+}
+
+impl NodeHarness {
+    pub fn new(node: Arc<dyn Node + Send + Sync>) -> Self {
+        Self {
+            node,
+            engine: FakeEngine::new(),
+            ctx: FlowContext::new(Uuid::new_v4()),
+        }
+    }
+
+    pub async fn from_builder(
+        builder: &dyn NodeBuilder,
+        info: NodeInfo,
+    ) -> Result<Self, NodeError> {
+        Ok(Self::new(create_node(builder, info).await?))
+    }
+
+    pub fn node(&self) -> &Arc<dyn Node + Send + Sync> {
+        &self.node
+    }
+
+    /// input / event 使用的上下文，可预先放入资源和流
+    pub fn ctx(&self) -> &FlowContext {
+        &self.ctx
+    }
+
+    /// 节点发起的 run_flow / node_send 调用
+    pub fn engine(&mut self) -> &mut FakeEngine {
+        &mut self.engine
+    }
+
+    /// 调用 Node::engine_start，传入 FakeEngine 的上下文
+    pub async fn start(&self) {
+        self.node.engine_start(self.engine.context()).await;
+    }
+
+    /// 触发停止信号后调用 Node::engine_stop
+    pub async fn stop(&self) {
+        self.engine.shutdown();
+        self.node.engine_stop().await;
+    }
+
+    pub async fn input(&self, port: u8, value: Value) -> Result<NodeOutput, NodeError> {
+        self.input_payload(port, Payload::new(value)).await
+    }
+
+    pub async fn input_payload(&self, port: u8, msg: Payload) -> Result<NodeOutput, NodeError> {
+        self.node.input(NodeInput { port, msg }, &self.ctx).await
+    }
+
+    pub async fn event(&self, event_type: &str, value: Value) -> Result<(), NodeError> {
+        self.node
+            .event(event_type, Payload::new(value), &self.ctx)
+            .await
+    }
+}
@@ -0,0 +1,87 @@
+use rsflow_core::{NodeInfo, RetryPolicy, Value};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// NodeInfo 构建器：默认随机 id，名称与类型相同，配置为 NULL，没有连线
+pub struct NodeInfoBuilder {
+    info: NodeInfo,
+}
+
+impl NodeInfoBuilder {
+    pub fn new(node_type: &str) -> Self {
+        Self {
+            info: NodeInfo {
+                id: Uuid::new_v4(),
+                name: node_type.to_string(),
+                node_type: node_type.to_string(),
+                description: String::new(),
+                config: Value::NULL,
+                input_ports: HashMap::new(),
+                output_ports: HashMap::new(),
+                global_config: Value::NULL,
+                error_port: None,
+                retry: None,
+            },
+        }
+    }
+
+    pub fn id(mut self, id: Uuid) -> Self {
+        self.info.id = id;
+        self
+    }
+
+    pub fn name(mut self, name: &str) -> Self {
+        self.info.name = name.to_string();
+        self
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.info.description = description.to_string();
+        self
+    }
+
+    pub fn config(mut self, config: Value) -> Self {
+        self.info.config = config;
+        self
+    }
+
+    /// node_global_config 中该类型的子树
+    pub fn global_config(mut self, global_config: Value) -> Self {
+        self.info.global_config = global_config;
+        self
+    }
+
+    /// 输入端口的上游节点，可多次调用
+    pub fn input(mut self, port: u8, from: &[Uuid]) -> Self {
+        self.info
+            .input_ports
+            .entry(port)
+            .or_default()
+            .extend_from_slice(from);
+        self
+    }
+
+    /// 输出端口连接的下游 (节点, 端口)，可多次调用
+    pub fn output(mut self, port: u8, to: &[(Uuid, u8)]) -> Self {
+        self.info
+            .output_ports
+            .entry(port)
+            .or_default()
+            .extend_from_slice(to);
+        self
+    }
+
+    pub fn error_port(mut self, port: u8) -> Self {
+        self.info.error_port = Some(port);
+        self
+    }
+
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.info.retry = Some(retry);
+        self
+    }
+
+    pub fn build(self) -> NodeInfo {
+        self.info
+    }
+}
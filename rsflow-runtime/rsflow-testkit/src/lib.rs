@@ -0,0 +1,35 @@
+//! 节点单元测试工具：NodeInfo 构建器、记录调用的 EngineContext、节点驱动和输出断言，
+//! 以及在代码中搭建节点图运行的 MiniEngine
+//!
+//! ```
+//! # use flow_node_shell::ShellNodeBuilder;
+//! # use rsflow_core::Value;
+//! # use rsflow_testkit::{NodeHarness, NodeInfoBuilder, NodeOutputExt, value};
+//! # use serde_json::json;
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() -> Result<(), rsflow_core::NodeError> {
+//! let info = NodeInfoBuilder::new("shell").config(value(json!({ "command": "echo hi" }))).build();
+//! let harness = NodeHarness::from_builder(&ShellNodeBuilder, info).await?;
+//! harness.input(0, Value::NULL).await?.assert_one(0, &value(json!("hi\n")));
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod engine;
+pub mod harness;
+pub mod info;
+pub mod mini;
+pub mod output;
+
+pub use engine::{EngineCall, FakeEngine, empty_flow_mod};
+pub use harness::{NodeHarness, create_node};
+pub use info::NodeInfoBuilder;
+pub use mini::{MiniEngine, MiniFlow, MiniRun};
+pub use output::{NodeOutputExt, assert_error, assert_ok};
+
+use rsflow_core::Value;
+
+/// 由 JSON 得到 Value，便于配合 serde_json::json! 书写配置和消息
+pub fn value(json: serde_json::Value) -> Value {
+    serde_json::from_value(json).unwrap_or(Value::NULL)
+}
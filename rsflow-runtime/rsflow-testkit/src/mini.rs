@@ -0,0 +1,225 @@
+use crate::{FakeEngine, create_node};
+use rsflow_core::{
+    FlowContext, Node, NodeBuilder, NodeError, NodeInfo, NodeInput, NodeOutput, Payload, Value,
+    error_value,
+};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// 运行超过该步数视为死循环
+const MAX_STEPS: usize = 10_000;
+
+/// 在代码中搭建的小型节点图，不需要 flow.json
+///
+/// ```
+/// # use flow_node_log::LogNodeBuilder;
+/// # use flow_node_shell::ShellNodeBuilder;
+/// # use rsflow_testkit::{MiniEngine, NodeInfoBuilder, value};
+/// # use serde_json::json;
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> Result<(), rsflow_core::NodeError> {
+/// let mut graph = MiniEngine::new();
+/// let shell = graph.add(ShellNodeBuilder, NodeInfoBuilder::new("shell").build());
+/// let log = graph.add(LogNodeBuilder, NodeInfoBuilder::new("log").build());
+/// graph.wire(shell, 0, log, 0);
+/// let flow = graph.build().await?;
+/// let run = flow.run(shell, 0, value(json!({ "command": "echo hi" }))).await;
+/// assert_eq!(run.received_by(log), vec![&value(json!("hi\n"))]);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// 与 Engine 不同，分支按端口和连线顺序依次执行，没有 catch 路由和重试；
+/// 节点出错时只转交已连线的错误端口，否则记入 MiniRun::errors。
+pub struct MiniEngine {
+    nodes: Vec<(Arc<dyn NodeBuilder>, NodeInfo)>,
+    wires: Vec<(Uuid, u8, Uuid, u8)>,
+}
+
+impl MiniEngine {
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            wires: Vec::new(),
+        }
+    }
+
+    /// 添加节点，返回节点 id；info 中的连线会被 wire 的结果替换
+    pub fn add<B>(&mut self, builder: B, info: NodeInfo) -> Uuid
+    where
+        B: NodeBuilder + 'static,
+    {
+        let id = info.id;
+        self.nodes.push((Arc::new(builder), info));
+        id
+    }
+
+    /// 连接 from 的输出端口到 to 的输入端口
+    pub fn wire(&mut self, from: Uuid, from_port: u8, to: Uuid, to_port: u8) -> &mut Self {
+        self.wires.push((from, from_port, to, to_port));
+        self
+    }
+
+    /// 按连线填写各节点的端口并创建节点
+    pub async fn build(self) -> Result<MiniFlow, NodeError> {
+        let mut nodes = HashMap::new();
+        for (builder, mut info) in self.nodes {
+            info.input_ports.clear();
+            info.output_ports.clear();
+            for (from, from_port, to, to_port) in &self.wires {
+                if *from == info.id {
+                    info.output_ports
+                        .entry(*from_port)
+                        .or_default()
+                        .push((*to, *to_port));
+                }
+                if *to == info.id {
+                    info.input_ports.entry(*to_port).or_default().push(*from);
+                }
+            }
+            nodes.insert(info.id, create_node(builder.as_ref(), info).await?);
+        }
+        Ok(MiniFlow {
+            nodes,
+            engine: FakeEngine::new(),
+        })
+    }
+}
+
+impl Default for MiniEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 已创建节点的图
+pub struct MiniFlow {
+    nodes: HashMap<Uuid, Arc<dyn Node + Send + Sync>>,
+    engine: FakeEngine,
+}
+
+impl MiniFlow {
+    pub fn node(&self, id: Uuid) -> Option<&Arc<dyn Node + Send + Sync>> {
+        self.nodes.get(&id)
+    }
+
+    /// 节点在 engine_start 后发起的 run_flow / node_send 调用
+    pub fn engine(&mut self) -> &mut FakeEngine {
+        &mut self.engine
+    }
+
+    /// 调用所有节点的 engine_start
+    pub async fn start(&self) {
+        for node in self.nodes.values() {
+            node.engine_start(self.engine.context()).await;
+        }
+    }
+
+    /// 触发停止信号后调用所有节点的 engine_stop
+    pub async fn stop(&self) {
+        self.engine.shutdown();
+        for node in self.nodes.values() {
+            node.engine_stop().await;
+        }
+    }
+
+    /// 从指定节点和端口注入消息，运行到所有分支结束
+    pub async fn run(&self, node_id: Uuid, port: u8, value: Value) -> MiniRun {
+        self.run_payload(node_id, port, Payload::new(value)).await
+    }
+
+    pub async fn run_payload(&self, node_id: Uuid, port: u8, msg: Payload) -> MiniRun {
+        let ctx = FlowContext::new(Uuid::new_v4());
+        let mut run = MiniRun::default();
+        let mut queue = VecDeque::from([(node_id, NodeInput { port, msg })]);
+
+        while let Some((node_id, input)) = queue.pop_front() {
+            if run.received.len() >= MAX_STEPS {
+                run.errors.push((
+                    node_id,
+                    NodeError::InvalidInput(format!(
+                        "more than {} steps, possible loop",
+                        MAX_STEPS
+                    )),
+                ));
+                break;
+            }
+            let Some(node) = self.nodes.get(&node_id) else {
+                run.errors.push((
+                    node_id,
+                    NodeError::InvalidInput(format!("node {} not found", node_id)),
+                ));
+                continue;
+            };
+            let info = node.info();
+            run.received.push((node_id, input.port, input.msg.clone()));
+            let original = input.msg.clone();
+
+            let msgs = match node.input(input, &ctx).await {
+                Ok(NodeOutput::None) => Vec::new(),
+                Ok(NodeOutput::One(msg)) => vec![msg],
+                Ok(NodeOutput::Many(msgs)) => msgs,
+                Err(err) => match info.error_port {
+                    Some(port) if info.output_ports.get(&port).is_some_and(|t| !t.is_empty()) => {
+                        let mut msg = original;
+                        msg.value = error_value(&info, &err, msg.value);
+                        vec![(port, msg)]
+                    }
+                    _ => {
+                        run.errors.push((node_id, err));
+                        Vec::new()
+                    }
+                },
+            };
+            for (port, msg) in msgs {
+                match info.output_ports.get(&port) {
+                    Some(targets) if !targets.is_empty() => {
+                        for (target, target_port) in targets {
+                            queue.push_back((
+                                *target,
+                                NodeInput {
+                                    port: *target_port,
+                                    msg: msg.clone(),
+                                },
+                            ));
+                        }
+                    }
+                    _ => run.outputs.push((node_id, port, msg)),
+                }
+            }
+        }
+        run
+    }
+}
+
+/// 一次运行的记录
+#[derive(Default)]
+pub struct MiniRun {
+    /// 按执行顺序，每个节点收到的 (节点, 输入端口, 消息)
+    pub received: Vec<(Uuid, u8, Payload)>,
+    /// 输出到没有连线的端口的 (节点, 端口, 消息)
+    pub outputs: Vec<(Uuid, u8, Payload)>,
+    /// 没有转交错误端口的节点错误
+    pub errors: Vec<(Uuid, NodeError)>,
+}
+
+impl MiniRun {
+    /// 节点收到的消息值，按执行顺序
+    pub fn received_by(&self, node_id: Uuid) -> Vec<&Value> {
+        self.received
+            .iter()
+            .filter(|(id, _, _)| *id == node_id)
+            .map(|(_, _, msg)| &msg.value)
+            .collect()
+    }
+
+    /// 节点输出到没有连线的端口的消息值
+    pub fn outputs_of(&self, node_id: Uuid) -> Vec<(u8, &Value)> {
+        self.outputs
+            .iter()
+            .filter(|(id, _, _)| *id == node_id)
+            .map(|(_, port, msg)| (*port, &msg.value))
+            .collect()
+    }
+}
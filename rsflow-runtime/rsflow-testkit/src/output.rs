@@ -0,0 +1,87 @@
+use rsflow_core::{NodeError, NodeOutput, Payload, Value};
+
+/// NodeOutput 的断言，失败时 panic 并给出实际输出
+pub trait NodeOutputExt {
+    /// 所有输出消息 (端口, Payload)
+    fn messages(&self) -> Vec<(u8, &Payload)>;
+
+    /// 没有输出
+    fn assert_none(&self);
+
+    /// 只有一条消息，且端口和值一致，返回该消息
+    fn assert_one(&self, port: u8, value: &Value) -> &Payload;
+
+    /// 按顺序比较所有消息的端口和值
+    fn assert_messages(&self, expected: &[(u8, Value)]);
+}
+
+impl NodeOutputExt for NodeOutput {
+    fn messages(&self) -> Vec<(u8, &Payload)> {
+        match self {
+            NodeOutput::None => Vec::new(),
+            NodeOutput::One((port, msg)) => vec![(*port, msg)],
+            NodeOutput::Many(msgs) => msgs.iter().map(|(port, msg)| (*port, msg)).collect(),
+        }
+    }
+
+    fn assert_none(&self) {
+        let messages = self.messages();
+        assert!(
+            messages.is_empty(),
+            "expected no output, got {:?}",
+            describe(&messages)
+        );
+    }
+
+    fn assert_one(&self, port: u8, value: &Value) -> &Payload {
+        let messages = self.messages();
+        match messages.as_slice() {
+            [(p, msg)] if *p == port && msg.value == *value => msg,
+            _ => panic!(
+                "expected one message {:?} on port {}, got {:?}",
+                value,
+                port,
+                describe(&messages)
+            ),
+        }
+    }
+
+    fn assert_messages(&self, expected: &[(u8, Value)]) {
+        let actual: Vec<(u8, Value)> = self
+            .messages()
+            .into_iter()
+            .map(|(port, msg)| (port, msg.value.clone()))
+            .collect();
+        assert_eq!(actual, expected, "node output mismatch");
+    }
+}
+
+fn describe(messages: &[(u8, &Payload)]) -> Vec<(u8, Value)> {
+    messages
+        .iter()
+        .map(|(port, msg)| (*port, msg.value.clone()))
+        .collect()
+}
+
+/// 节点执行成功，返回输出
+pub fn assert_ok(result: Result<NodeOutput, NodeError>) -> NodeOutput {
+    match result {
+        Ok(output) => output,
+        Err(err) => panic!("expected node output, got error {:?}", err),
+    }
+}
+
+/// 节点返回错误且变体为 kind（见 NodeError::kind），返回该错误
+pub fn assert_error(result: Result<NodeOutput, NodeError>, kind: &str) -> NodeError {
+    match result {
+        Ok(output) => panic!(
+            "expected {} error, got output {:?}",
+            kind,
+            describe(&output.messages())
+        ),
+        Err(err) => {
+            assert_eq!(err.kind(), kind, "unexpected error {:?}", err);
+            err
+        }
+    }
+}
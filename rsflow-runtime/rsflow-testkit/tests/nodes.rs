@@ -0,0 +1,77 @@
+use flow_node_inject::InjectNodeBuilder;
+use flow_node_log::LogNodeBuilder;
+use flow_node_shell::ShellNodeBuilder;
+use rsflow_core::Value;
+use rsflow_testkit::{
+    MiniEngine, NodeHarness, NodeInfoBuilder, NodeOutputExt, assert_error, assert_ok, value,
+};
+use serde_json::json;
+use std::time::Duration;
+
+#[tokio::test]
+async fn harness_runs_shell_node() {
+    let info = NodeInfoBuilder::new("shell")
+        .config(value(json!({ "command": "echo hi" })))
+        .build();
+    let harness = NodeHarness::from_builder(&ShellNodeBuilder, info)
+        .await
+        .unwrap();
+
+    assert_ok(harness.input(0, Value::NULL).await).assert_one(0, &value(json!("hi\n")));
+    // 消息中的 command 覆盖配置
+    assert_error(
+        harness
+            .input(0, value(json!({ "command": "exit 2" })))
+            .await,
+        "Shell",
+    );
+}
+
+#[tokio::test]
+async fn fake_engine_records_inject_calls() {
+    let mut harness =
+        NodeHarness::from_builder(&InjectNodeBuilder, NodeInfoBuilder::new("inject").build())
+            .await
+            .unwrap();
+    let node_id = harness.node().info().id;
+
+    harness.start().await;
+    let call = harness
+        .engine()
+        .next_call(Duration::from_secs(3))
+        .await
+        .expect("inject should start a run");
+    harness.stop().await;
+
+    assert_eq!(call.node_id(), node_id);
+}
+
+#[tokio::test]
+async fn mini_engine_routes_shell_to_log() {
+    let mut graph = MiniEngine::new();
+    let shell = graph.add(ShellNodeBuilder, NodeInfoBuilder::new("shell").build());
+    let failing = graph.add(
+        ShellNodeBuilder,
+        NodeInfoBuilder::new("shell")
+            .name("failing")
+            .error_port(1)
+            .build(),
+    );
+    let log = graph.add(LogNodeBuilder, NodeInfoBuilder::new("log").build());
+    graph
+        .wire(shell, 0, log, 0)
+        .wire(shell, 0, failing, 0)
+        .wire(failing, 1, log, 0);
+    let flow = graph.build().await.unwrap();
+
+    let run = flow
+        .run(shell, 0, value(json!({ "command": "echo hi" })))
+        .await;
+
+    // shell 的输出交给 log，failing 把 "hi\n" 当作命令执行失败，错误转交 1 号端口再到 log
+    let received = run.received_by(log);
+    assert_eq!(received.len(), 2);
+    assert_eq!(received[0], &value(json!("hi\n")));
+    assert!(run.outputs_of(failing).is_empty());
+    assert!(run.errors.is_empty(), "unexpected errors: {:?}", run.errors);
+}
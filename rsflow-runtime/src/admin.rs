@@ -0,0 +1,380 @@
+use axum::extract::{Path, State};
+use axum::http::{HeaderValue, Request, StatusCode, header};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use rsflow_core::EngineHandle;
+use rsflow_core::flow::{Flow, FlowMod};
+use rsflow_net::{debug_routes, metrics_routes};
+use serde_json::json;
+use std::io::{Error as IoError, ErrorKind};
+use std::net::SocketAddr;
+use std::path::{Path as FsPath, PathBuf};
+use std::sync::Arc;
+use tower_http::services::{ServeDir, ServeFile};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// 管理接口：flow 的增删改查与部署、节点类型、引擎状态，以及 rsflow-admin 的静态资源
+///
+/// 同时挂载 /debug（执行记录）和 /metrics，供管理界面使用。
+/// 设置了 token 时，/api、/debug、/metrics 需带 `Authorization: Bearer <token>`；
+/// 未设置 token 时只允许监听本机地址。
+pub async fn serve(
+    handle: EngineHandle,
+    addr: SocketAddr,
+    assets: PathBuf,
+    token: Option<String>,
+) -> Result<(), String> {
+    check_bind(&addr, token.as_deref())?;
+    let engine = handle.context();
+    let app = router(handle, &assets, token);
+
+    info!("Admin server starting on {}", addr);
+    axum::Server::try_bind(&addr)
+        .map_err(|e| format!("bind {}: {}", addr, e))?
+        .serve(app.into_make_service())
+        .with_graceful_shutdown(async move { engine.stopped().await })
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 未设置 token 时拒绝监听非本机地址
+pub fn check_bind(addr: &SocketAddr, token: Option<&str>) -> Result<(), String> {
+    if token.is_none_or(str::is_empty) && !addr.ip().is_loopback() {
+        return Err(format!(
+            "admin server on non-loopback address {} requires --admin-token",
+            addr
+        ));
+    }
+    Ok(())
+}
+
+fn router(handle: EngineHandle, assets: &FsPath, token: Option<String>) -> Router {
+    let engine = handle.context();
+    let mut api = Router::new()
+        .route("/api/status", get(status))
+        .route("/api/nodes", get(node_descriptors))
+        .route("/api/flow_mod", get(flow_mod))
+        .route("/api/deploy", post(deploy))
+        .route("/api/reload", post(reload))
+        .route("/api/flows", get(list_flows).post(create_flow))
+        .route(
+            "/api/flows/:flow_id",
+            get(get_flow).put(update_flow).delete(delete_flow),
+        )
+        .route("/api/flows/:flow_id/enable", post(enable_flow))
+        .route("/api/flows/:flow_id/disable", post(disable_flow))
+        .with_state(handle)
+        .merge(debug_routes(engine.clone()))
+        .merge(metrics_routes(engine));
+    // 只校验接口，静态资源不需要 token
+    if let Some(token) = token.filter(|token| !token.is_empty()) {
+        api = api.route_layer(middleware::from_fn_with_state(
+            Arc::<str>::from(token),
+            require_token,
+        ));
+    }
+
+    // 前端使用 history 路由，未匹配的路径返回 index.html
+    if assets.is_dir() {
+        let index = ServeFile::new(assets.join("index.html"));
+        api.fallback_service(ServeDir::new(assets).fallback(index))
+    } else {
+        warn!(
+            "Admin assets not found: {}, only the API is served",
+            assets.display()
+        );
+        api
+    }
+}
+
+// 校验 Authorization: Bearer <token>
+async fn require_token<B>(
+    State(token): State<Arc<str>>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|value| value == &*token);
+    if !authorized {
+        let mut response = ApiError(
+            StatusCode::UNAUTHORIZED,
+            "missing or invalid admin token".to_string(),
+        )
+        .into_response();
+        response
+            .headers_mut()
+            .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+        return response;
+    }
+    next.run(request).await
+}
+
+/// 接口错误，响应体为 { "error": message }
+struct ApiError(StatusCode, String);
+
+impl From<IoError> for ApiError {
+    fn from(err: IoError) -> Self {
+        let status = match err.kind() {
+            ErrorKind::NotFound => StatusCode::NOT_FOUND,
+            ErrorKind::AlreadyExists => StatusCode::CONFLICT,
+            ErrorKind::InvalidData | ErrorKind::InvalidInput => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError(status, err.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+type ApiResult = Result<Response, ApiError>;
+
+fn flow_not_found(flow_id: Uuid) -> IoError {
+    IoError::new(ErrorKind::NotFound, format!("Flow {} not found", flow_id))
+}
+
+async fn status(State(handle): State<EngineHandle>) -> Response {
+    Json(handle.status()).into_response()
+}
+
+async fn node_descriptors(State(handle): State<EngineHandle>) -> Response {
+    Json(handle.node_descriptors()).into_response()
+}
+
+async fn flow_mod(State(handle): State<EngineHandle>) -> Response {
+    Json(&*handle.flow_mod()).into_response()
+}
+
+async fn deploy(State(handle): State<EngineHandle>, Json(flow_mod): Json<FlowMod>) -> ApiResult {
+    let summary = handle.deploy(flow_mod).await?;
+    Ok(Json(summary).into_response())
+}
+
+async fn reload(State(handle): State<EngineHandle>) -> ApiResult {
+    let summary = handle.reload().await?;
+    Ok(Json(summary).into_response())
+}
+
+async fn list_flows(State(handle): State<EngineHandle>) -> Response {
+    Json(&handle.flow_mod().flow).into_response()
+}
+
+async fn get_flow(State(handle): State<EngineHandle>, Path(flow_id): Path<Uuid>) -> ApiResult {
+    let flow_mod = handle.flow_mod();
+    let flow = flow_mod
+        .flow
+        .iter()
+        .find(|flow| flow.id == flow_id)
+        .ok_or_else(|| flow_not_found(flow_id))?;
+    Ok(Json(flow).into_response())
+}
+
+async fn create_flow(State(handle): State<EngineHandle>, Json(flow): Json<Flow>) -> ApiResult {
+    let summary = handle
+        .update_mod(|flow_mod| {
+            if flow_mod.flow.iter().any(|f| f.id == flow.id) {
+                return Err(IoError::new(
+                    ErrorKind::AlreadyExists,
+                    format!("Flow {} already exists", flow.id),
+                ));
+            }
+            flow_mod.flow.push(flow);
+            Ok(())
+        })
+        .await?;
+    Ok((StatusCode::CREATED, Json(summary)).into_response())
+}
+
+async fn update_flow(
+    State(handle): State<EngineHandle>,
+    Path(flow_id): Path<Uuid>,
+    Json(mut flow): Json<Flow>,
+) -> ApiResult {
+    flow.id = flow_id;
+    let summary = handle
+        .update_mod(|flow_mod| {
+            let current = flow_mod
+                .flow
+                .iter_mut()
+                .find(|f| f.id == flow_id)
+                .ok_or_else(|| flow_not_found(flow_id))?;
+            *current = flow;
+            Ok(())
+        })
+        .await?;
+    Ok(Json(summary).into_response())
+}
+
+async fn delete_flow(State(handle): State<EngineHandle>, Path(flow_id): Path<Uuid>) -> ApiResult {
+    let summary = handle
+        .update_mod(|flow_mod| {
+            let len = flow_mod.flow.len();
+            flow_mod.flow.retain(|f| f.id != flow_id);
+            if flow_mod.flow.len() == len {
+                return Err(flow_not_found(flow_id));
+            }
+            Ok(())
+        })
+        .await?;
+    Ok(Json(summary).into_response())
+}
+
+async fn enable_flow(State(handle): State<EngineHandle>, Path(flow_id): Path<Uuid>) -> ApiResult {
+    set_disabled(&handle, flow_id, false).await
+}
+
+async fn disable_flow(State(handle): State<EngineHandle>, Path(flow_id): Path<Uuid>) -> ApiResult {
+    set_disabled(&handle, flow_id, true).await
+}
+
+async fn set_disabled(handle: &EngineHandle, flow_id: Uuid, disabled: bool) -> ApiResult {
+    let summary = handle
+        .update_mod(|flow_mod| {
+            let flow = flow_mod
+                .flow
+                .iter_mut()
+                .find(|f| f.id == flow_id)
+                .ok_or_else(|| flow_not_found(flow_id))?;
+            flow.disabled = disabled;
+            Ok(())
+        })
+        .await?;
+    Ok(Json(summary).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use rsflow_core::flow::{FlowBuilder, FlowModBuilder};
+    use tower::ServiceExt;
+
+    const TOKEN: &str = "secret";
+
+    async fn app(token: Option<&str>) -> (Router, EngineHandle) {
+        let handle = crate::engine_builder()
+            .build_from_mod(FlowModBuilder::new().build())
+            .await
+            .unwrap();
+        let app = router(
+            handle.clone(),
+            FsPath::new("missing-assets"),
+            token.map(str::to_string),
+        );
+        (app, handle)
+    }
+
+    async fn call(
+        app: &Router,
+        method: &str,
+        uri: &str,
+        token: Option<&str>,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, serde_json::Value) {
+        let mut request = Request::builder().method(method).uri(uri);
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        let body = match body {
+            Some(body) => {
+                request = request.header(header::CONTENT_TYPE, "application/json");
+                Body::from(body.to_string())
+            }
+            None => Body::empty(),
+        };
+        let response = app
+            .clone()
+            .oneshot(request.body(body).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let json = serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null);
+        (status, json)
+    }
+
+    #[test]
+    fn non_loopback_bind_requires_token() {
+        let local: SocketAddr = "127.0.0.1:8424".parse().unwrap();
+        let local_v6: SocketAddr = "[::1]:8424".parse().unwrap();
+        let any: SocketAddr = "0.0.0.0:8424".parse().unwrap();
+        assert!(check_bind(&local, None).is_ok());
+        assert!(check_bind(&local_v6, None).is_ok());
+        assert!(check_bind(&any, None).is_err());
+        assert!(check_bind(&any, Some("")).is_err());
+        assert!(check_bind(&any, Some(TOKEN)).is_ok());
+    }
+
+    #[tokio::test]
+    async fn token_guards_api_debug_and_metrics() {
+        let (app, _handle) = app(Some(TOKEN)).await;
+        for uri in ["/api/status", "/debug/config", "/metrics"] {
+            let (status, body) = call(&app, "GET", uri, None, None).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED, "{}", uri);
+            assert_eq!(body["error"], "missing or invalid admin token");
+            let (status, _) = call(&app, "GET", uri, Some("wrong"), None).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED, "{}", uri);
+        }
+
+        let request = Request::get("/api/status").body(Body::empty()).unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.headers()[header::WWW_AUTHENTICATE], "Bearer");
+
+        let (status, body) = call(&app, "GET", "/api/status", Some(TOKEN), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.is_object());
+        let (status, _) = call(&app, "GET", "/metrics", Some(TOKEN), None).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn manages_flows() {
+        let (app, handle) = app(None).await;
+        let flow = serde_json::to_value(FlowBuilder::new("main").build()).unwrap();
+        let flow_id = flow["id"].as_str().unwrap().to_string();
+        let path = format!("/api/flows/{}", flow_id);
+
+        let (status, _) = call(&app, "POST", "/api/flows", None, Some(flow.clone())).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let (status, body) = call(&app, "POST", "/api/flows", None, Some(flow.clone())).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert!(body["error"].as_str().unwrap().contains(&flow_id));
+
+        let (status, body) = call(&app, "GET", &path, None, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["name"], "main");
+
+        let mut renamed = flow.clone();
+        renamed["name"] = "renamed".into();
+        let (status, _) = call(&app, "PUT", &path, None, Some(renamed)).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = call(&app, "POST", &format!("{}/disable", path), None, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let current = handle.flow_mod();
+        assert_eq!(current.flow.len(), 1);
+        assert_eq!(current.flow[0].name, "renamed");
+        assert!(current.flow[0].disabled);
+
+        let (status, body) = call(&app, "GET", "/api/flows", None, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body.as_array().unwrap().len(), 1);
+
+        let (status, _) = call(&app, "DELETE", &path, None, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, body) = call(&app, "GET", &path, None, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(body["error"].as_str().unwrap().contains("not found"));
+        let (status, _) = call(&app, "DELETE", &path, None, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,145 @@
+use super::{Outcome, SuiteResult};
+use std::fmt::Write;
+use std::path::Path;
+
+/// 写入 JUnit XML 报告，每个测试文件一个 testsuite
+pub fn write_report(path: &Path, suites: &[SuiteResult]) -> std::io::Result<()> {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let total = |f: fn(&SuiteResult) -> usize| suites.iter().map(f).sum::<usize>();
+    let _ = writeln!(
+        xml,
+        "<testsuites tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">",
+        total(|s| s.cases.len()),
+        total(SuiteResult::failed),
+        total(SuiteResult::errors),
+        suites.iter().map(|s| s.duration.as_secs_f64()).sum::<f64>()
+    );
+    for suite in suites {
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">",
+            escape(&suite.name),
+            suite.cases.len(),
+            suite.failed(),
+            suite.errors(),
+            suite.duration.as_secs_f64()
+        );
+        for case in &suite.cases {
+            let _ = write!(
+                xml,
+                "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+                escape(&case.name),
+                escape(&suite.name),
+                case.duration.as_secs_f64()
+            );
+            match &case.outcome {
+                Outcome::Passed => xml.push_str("/>\n"),
+                Outcome::Failed(failures) => {
+                    let _ = writeln!(
+                        xml,
+                        ">\n      <failure message=\"{}\">{}</failure>\n    </testcase>",
+                        escape(&failures[0]),
+                        escape(&failures.join("\n"))
+                    );
+                }
+                Outcome::Error(message) => {
+                    let _ = writeln!(
+                        xml,
+                        ">\n      <error message=\"{}\"/>\n    </testcase>",
+                        escape(message)
+                    );
+                }
+            }
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    std::fs::write(path, xml)
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' => escaped.push_str("&#10;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flow_test::CaseResult;
+    use std::time::Duration;
+
+    fn case(name: &str, outcome: Outcome) -> CaseResult {
+        CaseResult {
+            name: name.to_string(),
+            duration: Duration::from_millis(250),
+            outcome,
+        }
+    }
+
+    #[test]
+    fn escapes_markup_and_control_characters() {
+        assert_eq!(
+            escape("<a href=\"x\">'&'</a>\nnext"),
+            "&lt;a href=&quot;x&quot;&gt;&apos;&amp;&apos;&lt;/a&gt;&#10;next"
+        );
+        assert_eq!(escape("中文"), "中文");
+    }
+
+    #[test]
+    fn writes_counts_and_outcomes() {
+        let suites = [
+            SuiteResult {
+                name: "a & b".to_string(),
+                cases: vec![
+                    case("passes", Outcome::Passed),
+                    case(
+                        "fails <1>",
+                        Outcome::Failed(vec!["first \"x\"".to_string(), "second".to_string()]),
+                    ),
+                ],
+                duration: Duration::from_millis(500),
+            },
+            SuiteResult {
+                name: "c".to_string(),
+                cases: vec![case(
+                    "broken",
+                    Outcome::Error("node 'x' not found".to_string()),
+                )],
+                duration: Duration::from_millis(250),
+            },
+        ];
+        let path = std::env::temp_dir().join(format!("rsflow-junit-{}.xml", uuid::Uuid::new_v4()));
+        write_report(&path, &suites).unwrap();
+        let xml = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(
+            xml.contains("<testsuites tests=\"3\" failures=\"1\" errors=\"1\" time=\"0.750\">")
+        );
+        assert!(xml.contains(
+            "<testsuite name=\"a &amp; b\" tests=\"2\" failures=\"1\" errors=\"0\" time=\"0.500\">"
+        ));
+        assert!(xml.contains("<testcase name=\"passes\" classname=\"a &amp; b\" time=\"0.250\"/>"));
+        // failure 的 message 为第一条断言，内容为全部断言
+        assert!(xml.contains(
+            "<failure message=\"first &quot;x&quot;\">first &quot;x&quot;&#10;second</failure>"
+        ));
+        assert!(xml.contains("<testcase name=\"fails &lt;1&gt;\""));
+        assert!(xml.contains(
+            "<testsuite name=\"c\" tests=\"1\" failures=\"0\" errors=\"1\" time=\"0.250\">"
+        ));
+        assert!(xml.contains("<error message=\"node &apos;x&apos; not found\"/>"));
+        assert!(xml.ends_with("</testsuites>\n"));
+    }
+}
@@ -0,0 +1,119 @@
+use rsflow_core::{
+    EngineContext, FlowContext, Node, NodeBuilder, NodeDescriptor, NodeError, NodeFactory,
+    NodeInfo, NodeInput, NodeOutput, Payload, Value,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+/// 测试文件中某个节点类型的 mock 行为
+///
+/// ```json
+/// "mocks": { "shell": { "outputs": [{ "port": 0, "value": "hello" }] } }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MockSpec {
+    /// 每次输入时依次输出，为空时没有输出
+    #[serde(default)]
+    pub outputs: Vec<MockOutput>,
+    /// 设置时返回错误（NodeError::Io），消息为该值
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MockOutput {
+    #[serde(default)]
+    pub port: u8,
+    /// 省略时原样输出输入的值
+    #[serde(default)]
+    pub value: Option<Value>,
+}
+
+// Node 实例
+struct MockNode {
+    info: NodeInfo,
+    spec: MockSpec,
+}
+
+#[async_trait::async_trait]
+impl Node for MockNode {
+    fn info(&self) -> NodeInfo {
+        self.info.clone()
+    }
+
+    // 不执行原节点的启动逻辑（定时器、监听端口等）
+    async fn engine_start(&self, _: EngineContext) {}
+
+    async fn event(&self, _: &str, _: Payload, _: &FlowContext) -> Result<(), NodeError> {
+        Ok(())
+    }
+
+    async fn input(&self, node_input: NodeInput, _: &FlowContext) -> Result<NodeOutput, NodeError> {
+        if let Some(message) = &self.spec.error {
+            return Err(NodeError::Io(std::io::Error::other(message.clone())));
+        }
+        let msgs: Vec<(u8, Payload)> = self
+            .spec
+            .outputs
+            .iter()
+            .map(|output| {
+                let msg = match &output.value {
+                    Some(value) => Payload::new(value.clone()),
+                    None => node_input.msg.clone(),
+                };
+                (output.port, msg)
+            })
+            .collect();
+        Ok(match msgs.len() {
+            0 => NodeOutput::None,
+            _ => NodeOutput::Many(msgs),
+        })
+    }
+}
+
+// NodeFactory
+struct MockNodeFactory {
+    spec: MockSpec,
+}
+
+#[async_trait::async_trait]
+impl NodeFactory for MockNodeFactory {
+    async fn create(&self, node_info: NodeInfo) -> Result<Arc<dyn Node + Send + Sync>, NodeError> {
+        Ok(Arc::new(MockNode {
+            info: node_info,
+            spec: self.spec.clone(),
+        }))
+    }
+}
+
+// NodeBuilder
+/// 替换某个节点类型，沿用原类型的说明（端口、配置 Schema），校验与真实节点一致
+pub struct MockNodeBuilder {
+    descriptor: NodeDescriptor,
+    spec: MockSpec,
+}
+
+impl MockNodeBuilder {
+    pub fn new(descriptor: NodeDescriptor, spec: MockSpec) -> Self {
+        Self { descriptor, spec }
+    }
+}
+
+#[async_trait::async_trait]
+impl NodeBuilder for MockNodeBuilder {
+    fn node_type(&self) -> &str {
+        &self.descriptor.node_type
+    }
+
+    fn descriptor(&self) -> NodeDescriptor {
+        self.descriptor.clone()
+    }
+
+    async fn register(&self, _: &Value) -> Result<Box<dyn NodeFactory>, NodeError> {
+        Ok(Box::new(MockNodeFactory {
+            spec: self.spec.clone(),
+        }))
+    }
+}
@@ -0,0 +1,856 @@
+//! rsflow test：按测试文件向 flow 注入消息，断言到达节点的消息、顺序和错误
+//!
+//! 测试在进程内运行真实的 Engine，通过执行记录（DebugTap）观察节点的输入和错误。
+//! 引擎以 start_isolated 启动：插件和节点不启动（不监听端口、不运行 inject 定时器），
+//! 只执行用例注入的消息。
+
+mod junit;
+mod mock;
+
+use mock::{MockNodeBuilder, MockSpec};
+use rsflow_core::flow::{Flow, FlowMod};
+use rsflow_core::{
+    EngineBuilder, EngineContext, FlowStatus, NodeInput, NodeRunItem, Payload, TraceEvent, Value,
+};
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+use tracing::error;
+use uuid::Uuid;
+
+/// 测试文件
+///
+/// ```json
+/// {
+///   "flow_file": "flow.json",
+///   "flow": "inject_test_flow",
+///   "mocks": { "shell": { "outputs": [{ "port": 0, "value": "mocked" }] } },
+///   "tests": [{
+///     "name": "shell output reaches log",
+///     "inject": { "node": "Inject Node 1", "port": 0, "value": "hi" },
+///     "expect": { "messages": [{ "node": "Log Node 1", "value": "mocked" }] }
+///   }]
+/// }
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TestFile {
+    /// 套件名，默认为测试文件路径
+    #[serde(default)]
+    name: Option<String>,
+    /// flow 文件路径，相对于测试文件所在目录
+    flow_file: PathBuf,
+    /// 被测 flow 的名称或 id，节点名称优先在该 flow 中查找
+    flow: String,
+    /// 每个用例的超时（秒）
+    #[serde(default = "default_timeout")]
+    timeout: u64,
+    /// 节点类型 -> mock 行为
+    #[serde(default)]
+    mocks: HashMap<String, MockSpec>,
+    tests: Vec<TestCase>,
+}
+
+fn default_timeout() -> u64 {
+    5
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TestCase {
+    name: String,
+    inject: Inject,
+    /// 覆盖测试文件的 timeout
+    #[serde(default)]
+    timeout: Option<u64>,
+    #[serde(default)]
+    expect: Expect,
+}
+
+/// 注入的消息：节点名称或 id、输入端口和 Payload 的值
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Inject {
+    node: String,
+    #[serde(default)]
+    port: u8,
+    /// 省略时为 null
+    #[serde(default)]
+    value: Option<Value>,
+}
+
+/// 断言，未设置的项不检查
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Expect {
+    status: Option<ExpectedStatus>,
+    /// 列出的节点收到的全部消息；ordered 为 true 时按执行完成的顺序比较
+    messages: Option<Vec<ExpectedMessage>>,
+    #[serde(default)]
+    ordered: bool,
+    /// 本次运行的全部节点错误（含转交错误端口或 catch 处理的错误）
+    errors: Option<Vec<ExpectedError>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ExpectedStatus {
+    Completed,
+    Failed,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ExpectedMessage {
+    node: String,
+    /// 输入端口，省略时不比较
+    #[serde(default)]
+    port: Option<u8>,
+    /// 省略时不比较；对象只比较列出的字段
+    #[serde(default)]
+    value: Option<JsonValue>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ExpectedError {
+    node: String,
+    /// 错误信息包含的文本
+    #[serde(default)]
+    contains: Option<String>,
+}
+
+/// 一个测试文件的结果
+pub struct SuiteResult {
+    name: String,
+    cases: Vec<CaseResult>,
+    duration: Duration,
+}
+
+impl SuiteResult {
+    fn passed(&self) -> usize {
+        self.count(|o| matches!(o, Outcome::Passed))
+    }
+
+    fn failed(&self) -> usize {
+        self.count(|o| matches!(o, Outcome::Failed(_)))
+    }
+
+    fn errors(&self) -> usize {
+        self.count(|o| matches!(o, Outcome::Error(_)))
+    }
+
+    fn count(&self, f: fn(&Outcome) -> bool) -> usize {
+        self.cases.iter().filter(|c| f(&c.outcome)).count()
+    }
+}
+
+struct CaseResult {
+    name: String,
+    duration: Duration,
+    outcome: Outcome,
+}
+
+enum Outcome {
+    Passed,
+    /// 断言失败，至少一条
+    Failed(Vec<String>),
+    /// 用例无法执行（文件无法加载、Engine 构建失败、节点不存在等）
+    Error(String),
+}
+
+/// 依次运行测试文件并输出结果，全部通过时返回 true
+///
+/// make_builder 提供与 run 相同的节点注册，mock 的节点类型在其上替换。
+pub async fn run(
+    files: &[PathBuf],
+    junit: Option<&Path>,
+    make_builder: fn() -> EngineBuilder,
+) -> bool {
+    let started = Instant::now();
+    let mut suites = Vec::new();
+    for file in files {
+        let suite = run_file(file, make_builder).await;
+        print_suite(&suite);
+        suites.push(suite);
+    }
+
+    let total = |f: fn(&SuiteResult) -> usize| suites.iter().map(f).sum::<usize>();
+    let (passed, failed, errors) = (
+        total(SuiteResult::passed),
+        total(SuiteResult::failed),
+        total(SuiteResult::errors),
+    );
+    println!(
+        "test result: {}. {} passed; {} failed; {} errors; finished in {:.2}s",
+        if failed + errors == 0 { "ok" } else { "FAILED" },
+        passed,
+        failed,
+        errors,
+        started.elapsed().as_secs_f64()
+    );
+
+    if let Some(path) = junit
+        && let Err(e) = junit::write_report(path, &suites)
+    {
+        error!("Failed to write JUnit report {}: {}", path.display(), e);
+        return false;
+    }
+    failed + errors == 0
+}
+
+fn print_suite(suite: &SuiteResult) {
+    println!("\nrunning {} tests from {}", suite.cases.len(), suite.name);
+    for case in &suite.cases {
+        let status = match case.outcome {
+            Outcome::Passed => "ok",
+            Outcome::Failed(_) => "FAILED",
+            Outcome::Error(_) => "ERROR",
+        };
+        println!("test {} ... {}", case.name, status);
+    }
+    for case in &suite.cases {
+        let details = match &case.outcome {
+            Outcome::Passed => continue,
+            Outcome::Failed(failures) => failures.join("\n  "),
+            Outcome::Error(message) => message.clone(),
+        };
+        println!("\n---- {} ----\n  {}", case.name, details);
+    }
+    println!();
+}
+
+async fn run_file(path: &Path, make_builder: fn() -> EngineBuilder) -> SuiteResult {
+    let started = Instant::now();
+    let file = match load(path) {
+        Ok(file) => file,
+        Err(e) => {
+            return SuiteResult {
+                name: path.display().to_string(),
+                cases: vec![CaseResult {
+                    name: "load".to_string(),
+                    duration: started.elapsed(),
+                    outcome: Outcome::Error(e),
+                }],
+                duration: started.elapsed(),
+            };
+        }
+    };
+    let name = file
+        .name
+        .clone()
+        .unwrap_or_else(|| path.display().to_string());
+    let flow_file = path.parent().unwrap_or(Path::new("")).join(&file.flow_file);
+
+    // mock 的节点类型沿用原类型的说明
+    let mut builder = make_builder();
+    let descriptors: HashMap<String, _> = builder
+        .node_descriptors()
+        .into_iter()
+        .map(|d| (d.node_type.clone(), d))
+        .collect();
+    for (node_type, spec) in &file.mocks {
+        let descriptor = descriptors
+            .get(node_type)
+            .cloned()
+            .unwrap_or_else(|| rsflow_core::NodeDescriptor::new(node_type));
+        builder = builder.override_node(MockNodeBuilder::new(descriptor, spec.clone()));
+    }
+
+    let handle = match builder.build(&flow_file.to_string_lossy()).await {
+        Ok(handle) => handle,
+        Err(e) => {
+            let message = format!("Failed to build engine from {}: {}", flow_file.display(), e);
+            return SuiteResult {
+                name,
+                cases: file
+                    .tests
+                    .iter()
+                    .map(|case| CaseResult {
+                        name: case.name.clone(),
+                        duration: Duration::ZERO,
+                        outcome: Outcome::Error(message.clone()),
+                    })
+                    .collect(),
+                duration: started.elapsed(),
+            };
+        }
+    };
+
+    // 记录所有运行的完整输入，用例按运行 id 过滤
+    let engine = handle.context();
+    engine.debug.set_enabled(true);
+    let _ = engine.debug.set_sample_rate(1.0);
+    engine.debug.set_max_value_len(usize::MAX);
+    let running = tokio::spawn({
+        let handle = handle.clone();
+        async move { handle.start_isolated().await }
+    });
+
+    let flow_mod = handle.flow_mod();
+    let mut cases = Vec::new();
+    for case in &file.tests {
+        let case_started = Instant::now();
+        let outcome = match run_case(&engine, &flow_mod, &file, case).await {
+            Ok(failures) if failures.is_empty() => Outcome::Passed,
+            Ok(failures) => Outcome::Failed(failures),
+            Err(e) => Outcome::Error(e),
+        };
+        cases.push(CaseResult {
+            name: case.name.clone(),
+            duration: case_started.elapsed(),
+            outcome,
+        });
+    }
+
+    handle.shutdown();
+    let _ = running.await;
+    SuiteResult {
+        name,
+        cases,
+        duration: started.elapsed(),
+    }
+}
+
+fn load(path: &Path) -> Result<TestFile, String> {
+    let text =
+        std::fs::read_to_string(path).map_err(|e| format!("read {}: {}", path.display(), e))?;
+    serde_json::from_str(&text).map_err(|e| format!("parse {}: {}", path.display(), e))
+}
+
+// 运行一个用例，返回断言失败的说明
+async fn run_case(
+    engine: &EngineContext,
+    flow_mod: &FlowMod,
+    file: &TestFile,
+    case: &TestCase,
+) -> Result<Vec<String>, String> {
+    let flow = flow_mod
+        .flow
+        .iter()
+        .find(|flow| flow.name == file.flow || flow.id.to_string() == file.flow)
+        .ok_or_else(|| format!("flow \"{}\" not found", file.flow))?;
+    let names: HashMap<Uuid, &str> = flow_mod
+        .flow
+        .iter()
+        .flat_map(|flow| flow.nodes.iter())
+        .map(|node| (node.id, node.name.as_str()))
+        .collect();
+    let start = resolve_node(flow_mod, flow, &case.inject.node)?;
+    let expected_messages = match &case.expect.messages {
+        Some(messages) => Some(
+            messages
+                .iter()
+                .map(|m| Ok((resolve_node(flow_mod, flow, &m.node)?, m)))
+                .collect::<Result<Vec<_>, String>>()?,
+        ),
+        None => None,
+    };
+    let expected_errors = match &case.expect.errors {
+        Some(errors) => Some(
+            errors
+                .iter()
+                .map(|e| Ok((resolve_node(flow_mod, flow, &e.node)?, e)))
+                .collect::<Result<Vec<_>, String>>()?,
+        ),
+        None => None,
+    };
+
+    let mut traces = engine.debug.subscribe();
+    let handle = engine
+        .run_flow(NodeRunItem {
+            node_id: start,
+            node_input: NodeInput {
+                port: case.inject.port,
+                msg: Payload::new(case.inject.value.clone().unwrap_or(Value::NULL)),
+            },
+        })
+        .await;
+    let run_id = handle.id();
+
+    // 等待运行结束，同时收集本次运行的执行记录
+    let timeout = Duration::from_secs(case.timeout.unwrap_or(file.timeout));
+    let deadline = tokio::time::sleep(timeout);
+    let wait = handle.clone().wait();
+    tokio::pin!(deadline, wait);
+    let mut events: Vec<TraceEvent> = Vec::new();
+    let mut lagged = 0;
+    let result = loop {
+        tokio::select! {
+            result = &mut wait => break Some(result),
+            _ = &mut deadline => break None,
+            event = traces.recv() => match event {
+                Ok(event) if event.run_id == run_id => events.push(event),
+                Ok(_) => {}
+                Err(broadcast::error::RecvError::Lagged(n)) => lagged += n,
+                Err(broadcast::error::RecvError::Closed) => break Some((&mut wait).await),
+            },
+        }
+    };
+    while let Ok(event) = traces.try_recv() {
+        if event.run_id == run_id {
+            events.push(event);
+        }
+    }
+
+    let Some(result) = result else {
+        handle.cancel();
+        return Ok(vec![format!("timed out after {}s", timeout.as_secs())]);
+    };
+    if result.status == FlowStatus::Rejected {
+        return Err("flow run rejected, engine is stopping".to_string());
+    }
+    if lagged > 0 {
+        return Err(format!(
+            "{} trace events dropped, increase config.debug.buffer",
+            lagged
+        ));
+    }
+
+    let mut failures = Vec::new();
+    if let Some(status) = case.expect.status {
+        let actual = match result.status {
+            FlowStatus::Completed => Some(ExpectedStatus::Completed),
+            FlowStatus::Failed => Some(ExpectedStatus::Failed),
+            _ => None,
+        };
+        if actual != Some(status) {
+            failures.push(format!(
+                "status: expected {:?}, got {:?}",
+                status, result.status
+            ));
+        }
+    }
+    if let Some(expected) = expected_messages {
+        check_messages(
+            &expected,
+            case.expect.ordered,
+            &events,
+            &names,
+            &mut failures,
+        );
+    }
+    if let Some(expected) = expected_errors {
+        check_errors(&expected, &events, &names, &mut failures);
+    }
+    Ok(failures)
+}
+
+// 节点名称或 id；名称先在被测 flow 中查找，找不到时在所有 flow 中查找
+fn resolve_node(flow_mod: &FlowMod, flow: &Flow, node: &str) -> Result<Uuid, String> {
+    let all = || flow_mod.flow.iter().flat_map(|flow| flow.nodes.iter());
+    if let Ok(id) = node.parse::<Uuid>() {
+        return all()
+            .any(|n| n.id == id)
+            .then_some(id)
+            .ok_or_else(|| format!("node {} not found", id));
+    }
+    let mut candidates: Vec<Uuid> = flow
+        .nodes
+        .iter()
+        .filter(|n| n.name == node)
+        .map(|n| n.id)
+        .collect();
+    if candidates.is_empty() {
+        candidates = all().filter(|n| n.name == node).map(|n| n.id).collect();
+    }
+    match candidates.as_slice() {
+        [id] => Ok(*id),
+        [] => Err(format!("node \"{}\" not found", node)),
+        _ => Err(format!("node name \"{}\" is ambiguous, use its id", node)),
+    }
+}
+
+// 列出的节点收到的消息与期望一一对应
+fn check_messages(
+    expected: &[(Uuid, &ExpectedMessage)],
+    ordered: bool,
+    events: &[TraceEvent],
+    names: &HashMap<Uuid, &str>,
+    failures: &mut Vec<String>,
+) {
+    let actual: Vec<(Uuid, u8, JsonValue)> = events
+        .iter()
+        .filter(|e| expected.iter().any(|(id, _)| *id == e.node_id))
+        .map(|e| {
+            let value = serde_json::from_str(&e.input)
+                .unwrap_or_else(|_| JsonValue::String(e.input.clone()));
+            (e.node_id, e.input_port, value)
+        })
+        .collect();
+    let matches = |(id, m): &(Uuid, &ExpectedMessage),
+                   (node_id, port, value): &(Uuid, u8, JsonValue)| {
+        id == node_id
+            && m.port.is_none_or(|p| p == *port)
+            && m.value.as_ref().is_none_or(|v| value_matches(v, value))
+    };
+    let describe = |(node_id, port, value): &(Uuid, u8, JsonValue)| {
+        format!("\"{}\" port {}: {}", name_of(names, *node_id), port, value)
+    };
+
+    if ordered {
+        for (i, m) in expected.iter().enumerate() {
+            match actual.get(i) {
+                Some(a) if matches(m, a) => {}
+                Some(a) => failures.push(format!(
+                    "message #{}: expected {}, got {}",
+                    i,
+                    describe_expected(names, m),
+                    describe(a)
+                )),
+                None => failures.push(format!(
+                    "message #{}: expected {}, not received",
+                    i,
+                    describe_expected(names, m)
+                )),
+            }
+        }
+    } else {
+        let mut used = vec![false; actual.len()];
+        for m in expected {
+            match (0..actual.len()).find(|&i| !used[i] && matches(m, &actual[i])) {
+                Some(i) => used[i] = true,
+                None => failures.push(format!(
+                    "message not received: {}",
+                    describe_expected(names, m)
+                )),
+            }
+        }
+    }
+    if actual.len() > expected.len() {
+        let received: Vec<String> = actual.iter().map(describe).collect();
+        failures.push(format!(
+            "expected {} messages, received {}:\n    {}",
+            expected.len(),
+            actual.len(),
+            received.join("\n    ")
+        ));
+    }
+}
+
+fn describe_expected(names: &HashMap<Uuid, &str>, (id, m): &(Uuid, &ExpectedMessage)) -> String {
+    let port = m.port.map(|p| format!(" port {}", p)).unwrap_or_default();
+    let value = m
+        .value
+        .as_ref()
+        .map(|v| format!(": {}", v))
+        .unwrap_or_default();
+    format!("\"{}\"{}{}", name_of(names, *id), port, value)
+}
+
+// 本次运行的节点错误与期望一一对应
+fn check_errors(
+    expected: &[(Uuid, &ExpectedError)],
+    events: &[TraceEvent],
+    names: &HashMap<Uuid, &str>,
+    failures: &mut Vec<String>,
+) {
+    let actual: Vec<(Uuid, &str)> = events
+        .iter()
+        .filter_map(|e| e.error.as_deref().map(|message| (e.node_id, message)))
+        .collect();
+    let mut used = vec![false; actual.len()];
+    for (id, e) in expected {
+        let found = (0..actual.len()).find(|&i| {
+            !used[i]
+                && actual[i].0 == *id
+                && e.contains
+                    .as_ref()
+                    .is_none_or(|text| actual[i].1.contains(text.as_str()))
+        });
+        match found {
+            Some(i) => used[i] = true,
+            None => failures.push(format!(
+                "error not raised: \"{}\"{}",
+                name_of(names, *id),
+                e.contains
+                    .as_ref()
+                    .map(|text| format!(" containing \"{}\"", text))
+                    .unwrap_or_default()
+            )),
+        }
+    }
+    for (i, (id, message)) in actual.iter().enumerate() {
+        if !used[i] {
+            failures.push(format!(
+                "unexpected error: \"{}\": {}",
+                name_of(names, *id),
+                message
+            ));
+        }
+    }
+}
+
+fn name_of<'a>(names: &HashMap<Uuid, &'a str>, id: Uuid) -> &'a str {
+    names.get(&id).copied().unwrap_or("unknown")
+}
+
+// 期望值中的对象只比较列出的字段，数字按数值比较
+fn value_matches(expected: &JsonValue, actual: &JsonValue) -> bool {
+    match (expected, actual) {
+        (JsonValue::Object(expected), JsonValue::Object(actual)) => expected
+            .iter()
+            .all(|(key, value)| actual.get(key).is_some_and(|a| value_matches(value, a))),
+        (JsonValue::Array(expected), JsonValue::Array(actual)) => {
+            expected.len() == actual.len()
+                && expected
+                    .iter()
+                    .zip(actual)
+                    .all(|(e, a)| value_matches(e, a))
+        }
+        (JsonValue::Number(expected), JsonValue::Number(actual)) => {
+            expected.as_f64() == actual.as_f64()
+        }
+        _ => expected == actual,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rsflow_core::flow::{FlowBuilder, FlowModBuilder, NodeSpec};
+    use serde_json::json;
+
+    fn trace(node_id: Uuid, port: u8, input: JsonValue, error: Option<&str>) -> TraceEvent {
+        TraceEvent {
+            flow_id: Uuid::nil(),
+            ctx_id: Uuid::nil(),
+            run_id: Uuid::nil(),
+            node_id,
+            node_name: String::new(),
+            node_type: String::new(),
+            input_port: port,
+            input: input.to_string(),
+            truncated: false,
+            output_ports: Vec::new(),
+            duration_us: 0,
+            error: error.map(str::to_string),
+            timestamp: 0,
+        }
+    }
+
+    fn message(port: Option<u8>, value: Option<JsonValue>) -> ExpectedMessage {
+        ExpectedMessage {
+            node: String::new(),
+            port,
+            value,
+        }
+    }
+
+    fn expected_error(contains: Option<&str>) -> ExpectedError {
+        ExpectedError {
+            node: String::new(),
+            contains: contains.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn value_matches_lists_fields_and_numbers() {
+        let actual = json!({ "a": 1, "b": { "c": [1, 2], "d": "x" }, "e": null });
+        assert!(value_matches(&json!({}), &actual));
+        assert!(value_matches(
+            &json!({ "a": 1.0, "b": { "d": "x" } }),
+            &actual
+        ));
+        assert!(value_matches(&json!({ "b": { "c": [1.0, 2] } }), &actual));
+        assert!(value_matches(&json!({ "e": null }), &actual));
+        // 缺少字段、数组长度不同、类型不同
+        assert!(!value_matches(&json!({ "f": null }), &actual));
+        assert!(!value_matches(&json!({ "b": { "c": [1] } }), &actual));
+        assert!(!value_matches(&json!({ "a": "1" }), &actual));
+        assert!(!value_matches(&json!([{}]), &json!([{ "a": 1 }, {}])));
+        assert!(value_matches(&json!([{}]), &json!([{ "a": 1 }])));
+        assert!(!value_matches(&json!("x"), &json!("y")));
+    }
+
+    #[test]
+    fn check_messages_unordered_and_ordered() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let names = HashMap::from([(a, "A"), (b, "B")]);
+        let events = [
+            trace(a, 0, json!("first"), None),
+            trace(Uuid::new_v4(), 0, json!("other node"), None),
+            trace(b, 1, json!({ "n": 2, "extra": true }), None),
+        ];
+        let (first, second) = (
+            message(None, Some(json!("first"))),
+            message(Some(1), Some(json!({ "n": 2 }))),
+        );
+
+        // 不按顺序时任意顺序都匹配，未列出的节点不比较
+        let mut failures = Vec::new();
+        check_messages(
+            &[(b, &second), (a, &first)],
+            false,
+            &events,
+            &names,
+            &mut failures,
+        );
+        assert!(failures.is_empty(), "{:?}", failures);
+
+        check_messages(
+            &[(b, &second), (a, &first)],
+            true,
+            &events,
+            &names,
+            &mut failures,
+        );
+        assert_eq!(failures.len(), 2);
+        assert!(failures[0].starts_with("message #0: expected \"B\" port 1"));
+        assert!(failures[0].ends_with("got \"A\" port 0: \"first\""));
+
+        // 端口不同、缺少的消息和多余的消息
+        let mut failures = Vec::new();
+        let wrong_port = message(Some(0), None);
+        check_messages(&[(b, &wrong_port)], false, &events, &names, &mut failures);
+        assert_eq!(failures[0], "message not received: \"B\" port 0");
+        let mut failures = Vec::new();
+        check_messages(&[(a, &first)], true, &events[..1], &names, &mut failures);
+        check_messages(
+            &[(a, &first), (a, &first)],
+            true,
+            &events,
+            &names,
+            &mut failures,
+        );
+        assert_eq!(
+            failures,
+            ["message #1: expected \"A\": \"first\", not received"]
+        );
+        let mut failures = Vec::new();
+        let any = message(None, None);
+        check_messages(
+            &[(a, &any)],
+            false,
+            &[events[0].clone(), events[0].clone()],
+            &names,
+            &mut failures,
+        );
+        assert_eq!(failures.len(), 1);
+        assert!(failures[0].starts_with("expected 1 messages, received 2:"));
+    }
+
+    #[test]
+    fn check_errors_matches_each_error_once() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let names = HashMap::from([(a, "A"), (b, "B")]);
+        let events = [
+            trace(a, 0, json!(1), Some("Io(timed out)")),
+            trace(a, 0, json!(2), None),
+            trace(b, 0, json!(3), Some("InvalidInput")),
+        ];
+        let (timed_out, any) = (expected_error(Some("timed out")), expected_error(None));
+
+        let mut failures = Vec::new();
+        check_errors(
+            &[(b, &any), (a, &timed_out)],
+            &events,
+            &names,
+            &mut failures,
+        );
+        assert!(failures.is_empty(), "{:?}", failures);
+
+        // 同一个错误只匹配一次，未匹配的错误单独报告
+        check_errors(&[(a, &any), (a, &any)], &events, &names, &mut failures);
+        assert_eq!(
+            failures,
+            [
+                "error not raised: \"A\"",
+                "unexpected error: \"B\": InvalidInput",
+            ]
+        );
+        let mut failures = Vec::new();
+        check_errors(&[(b, &timed_out)], &events, &names, &mut failures);
+        assert_eq!(
+            failures[0],
+            "error not raised: \"B\" containing \"timed out\""
+        );
+        assert_eq!(failures.len(), 3);
+    }
+
+    // 在临时目录写入 flow 文件和测试文件
+    fn write_suite(tests: JsonValue) -> (PathBuf, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("rsflow-test-{}", Uuid::new_v4()));
+        std::fs::create_dir(&dir).unwrap();
+        let mut flow = FlowBuilder::new("main");
+        let config = json!({ "command": "sleep 3", "timeout": 10 });
+        let sleep = flow.add(
+            NodeSpec::new("shell")
+                .name("Sleep")
+                .config(serde_json::from_value(config).unwrap()),
+        );
+        let log = flow.add(NodeSpec::new("log").name("Log"));
+        flow.wire(sleep, 0, log, 0);
+        let flow_mod = FlowModBuilder::new().flow(flow.build()).build();
+        std::fs::write(
+            dir.join("flow.json"),
+            serde_json::to_string(&flow_mod).unwrap(),
+        )
+        .unwrap();
+        let file =
+            json!({ "name": "suite", "flow_file": "flow.json", "flow": "main", "tests": tests });
+        let path = dir.join("flow.test.json");
+        std::fs::write(&path, file.to_string()).unwrap();
+        (dir, path)
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn run_file_reports_pass_failure_timeout_and_error() {
+        let (dir, path) = write_suite(json!([
+            {
+                "name": "log receives value",
+                "inject": { "node": "Log", "value": { "a": 1, "b": 2 } },
+                "expect": {
+                    "status": "completed",
+                    "messages": [{ "node": "Log", "port": 0, "value": { "a": 1.0 } }],
+                    "errors": []
+                }
+            },
+            {
+                "name": "wrong status",
+                "inject": { "node": "Log" },
+                "expect": { "status": "failed" }
+            },
+            {
+                "name": "slow shell",
+                "inject": { "node": "Sleep" },
+                "timeout": 1
+            },
+            {
+                "name": "missing node",
+                "inject": { "node": "Missing" }
+            }
+        ]));
+
+        let started = Instant::now();
+        let suite = run_file(&path, crate::engine_builder).await;
+        // 超时的运行被取消，不等待 sleep 结束
+        assert!(started.elapsed() < Duration::from_secs(3));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(suite.name, "suite");
+        assert_eq!((suite.passed(), suite.failed(), suite.errors()), (1, 2, 1));
+        let outcome = |i: usize| match &suite.cases[i].outcome {
+            Outcome::Passed => "passed".to_string(),
+            Outcome::Failed(failures) => failures.join("; "),
+            Outcome::Error(message) => message.clone(),
+        };
+        assert_eq!(outcome(0), "passed");
+        assert_eq!(outcome(1), "status: expected Failed, got Completed");
+        assert_eq!(outcome(2), "timed out after 1s");
+        assert_eq!(outcome(3), "node \"Missing\" not found");
+    }
+
+    #[tokio::test]
+    async fn run_file_reports_unreadable_file() {
+        let path = Path::new("missing.test.json");
+        let suite = run_file(path, crate::engine_builder).await;
+        assert_eq!(suite.cases.len(), 1);
+        assert_eq!(suite.cases[0].name, "load");
+        assert_eq!(suite.errors(), 1);
+    }
+}
@@ -1,69 +1,491 @@
-use rsflow_core::EngineBuilder;
+mod admin;
+mod flow_test;
+mod node_red;
+
+use rsflow_core::flow::{Diagnostic, FlowFormat, parse_flow_file};
+use rsflow_core::{EngineBuilder, EngineHandle};
+use rsflow_net::NetPlugin;
 use rsflow_nodes::register_all_nodes;
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use tracing::{error, info};
+use tracing_subscriber::EnvFilter;
 
 /// RSFlow Runtime
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
+struct Cli {
+    /// 日志格式，级别通过 RUST_LOG 环境变量配置（默认 info）
+    #[arg(long, value_enum, global = true, default_value_t = LogFormat::Human)]
+    log_format: LogFormat,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum LogFormat {
+    /// 便于阅读的文本
+    Human,
+    /// 每行一个 JSON 对象
+    Json,
+}
+
+#[derive(Subcommand, Debug)]
 enum Command {
     /// 运行 flow
     Run {
         /// Flow 文件路径
         #[arg(short, long, default_value = "../data/flow.json")]
         flow_file: String,
+        /// 监听 flow 文件变化并热重载
+        #[arg(short, long)]
+        watch: bool,
+        /// 管理接口监听地址，例如 127.0.0.1:8424
+        #[arg(long)]
+        admin: Option<SocketAddr>,
+        /// 管理界面静态资源目录（rsflow-admin 构建产物）
+        #[arg(long, default_value = "../rsflow-admin/dist")]
+        admin_assets: PathBuf,
+        /// 管理接口的 Bearer 令牌，监听非本机地址时必须设置
+        #[arg(long)]
+        admin_token: Option<String>,
+    },
+    /// 检查 flow 文件（结构、节点类型、插件和节点配置），有 error 时退出码为 1
+    Validate {
+        /// Flow 文件路径
+        #[arg(short, long, default_value = "../data/flow.json")]
+        flow_file: String,
+        /// 以 JSON 数组输出诊断
+        #[arg(long)]
+        json: bool,
+    },
+    /// 风格检查：未命名节点、未连线的端口、与平台不符的 shell 等
+    Lint {
+        /// Flow 文件路径
+        #[arg(short, long, default_value = "../data/flow.json")]
+        flow_file: String,
+        /// 以 JSON 数组输出诊断
+        #[arg(long)]
+        json: bool,
+        /// 有 warning 时退出码为 1
+        #[arg(long)]
+        deny_warnings: bool,
+    },
+    /// 转换 flow 文件格式（JSON / YAML / TOML，按扩展名选择）
+    Convert {
+        /// 输入 flow 文件
+        input: String,
+        /// 输出 flow 文件
+        output: String,
+        /// 输出简写：连线写成 wires，省略 input 和默认值
+        #[arg(long)]
+        compact: bool,
+    },
+    /// 导入 Node-RED 导出的 flow，输出不能完整转换的节点
+    Import {
+        /// Node-RED 导出的 JSON 文件
+        input: PathBuf,
+        /// 输出 flow 文件，格式按扩展名选择
+        output: String,
+        /// 输出简写：连线写成 wires，省略 input 和默认值
+        #[arg(long)]
+        compact: bool,
+        /// 以 JSON 输出导入报告
+        #[arg(long)]
+        json: bool,
+        /// inject 使用 repeat / crontab 或额外的 msg 属性时仍然导入（rsflow inject 每秒输出 "hello"）
+        #[arg(long)]
+        allow_lossy_inject: bool,
+    },
+    /// 已注册的节点类型
+    Nodes {
+        #[command(subcommand)]
+        command: NodesCommand,
+    },
+    /// 运行 flow 测试文件，有用例未通过时退出码为 1
+    Test {
+        /// 测试文件路径
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+        /// 写入 JUnit XML 报告
+        #[arg(long)]
+        junit: Option<PathBuf>,
     },
-    /// 快捷测试命令
-    Test,
     /// 直接指定 flow 文件路径
     #[command(external_subcommand)]
     FlowFile(Vec<String>),
 }
 
+#[derive(Subcommand, Debug)]
+enum NodesCommand {
+    /// 列出节点类型及其端口、配置 Schema
+    List {
+        /// 以 JSON 输出完整的节点说明
+        #[arg(long)]
+        json: bool,
+    },
+}
+
 #[tokio::main]
 async fn main() {
-    let cmd = Command::parse();
-    
-    match cmd {
-        Command::Run { flow_file } => {
-            let engine = match register_all_nodes(EngineBuilder::new())
-                .build(&flow_file)
-                .await
+    let cli = Cli::parse();
+    init_tracing(cli.log_format);
+
+    match cli.command {
+        Command::Run {
+            flow_file,
+            watch,
+            admin,
+            admin_assets,
+            admin_token,
+        } => {
+            if let Some(addr) = &admin
+                && let Err(e) = admin::check_bind(addr, admin_token.as_deref())
             {
-                Ok(engine) => engine,
-                Err(e) => {
-                    eprintln!("Failed to build engine: {:?}", e);
-                    std::process::exit(1);
-                }
-            };
-
-            // 👇 生命周期锚点
-            engine.start().await;
+                error!("{}", e);
+                std::process::exit(1);
+            }
+            let admin = admin.map(|addr| (addr, admin_assets, admin_token));
+            run_flow_file(&flow_file, watch, admin).await;
+        }
+        Command::Validate { flow_file, json } => {
+            let diagnostics = engine_builder().validate(&flow_file).await;
+            exit_with_diagnostics(&flow_file, diagnostics, json, false);
         }
-        Command::Test => {
-            println!("Running quick test...");
-            // 这里可以添加快捷测试逻辑，例如运行一个简单的内置 flow
-            println!("Test completed successfully!");
+        Command::Lint {
+            flow_file,
+            json,
+            deny_warnings,
+        } => {
+            let diagnostics = engine_builder().lint(&flow_file).await;
+            exit_with_diagnostics(&flow_file, diagnostics, json, deny_warnings);
+        }
+        Command::Convert {
+            input,
+            output,
+            compact,
+        } => convert(&input, &output, compact),
+        Command::Import {
+            input,
+            output,
+            compact,
+            json,
+            allow_lossy_inject,
+        } => import_node_red(&input, &output, compact, json, allow_lossy_inject),
+        Command::Nodes {
+            command: NodesCommand::List { json },
+        } => list_nodes(json),
+        Command::Test { files, junit } => {
+            if !flow_test::run(&files, junit.as_deref(), engine_builder).await {
+                std::process::exit(1);
+            }
         }
         Command::FlowFile(args) => {
             if let Some(flow_file) = args.first() {
-                // 使用自动注册函数
-                let engine = match register_all_nodes(EngineBuilder::new())
-                    .build(flow_file)
-                    .await
-                {
-                    Ok(engine) => engine,
-                    Err(e) => {
-                        eprintln!("Failed to build engine: {:?}", e);
-                        std::process::exit(1);
-                    }
-                };
-
-                // 👇 生命周期锚点
-                engine.start().await;
+                run_flow_file(flow_file, false, None).await;
             } else {
-                eprintln!("Error: No flow file path specified");
+                error!("No flow file path specified");
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+/// 与 run 相同的节点注册
+fn engine_builder() -> EngineBuilder {
+    register_all_nodes(EngineBuilder::new()).register_engine_plugin(NetPlugin::new())
+}
+
+/// 读取 flow 文件并以输出文件扩展名对应的格式写入
+fn convert(input: &str, output: &str, compact: bool) {
+    let result = parse_flow_file(input).and_then(|flow_mod| {
+        let content = FlowFormat::from_path(Path::new(output)).serialize(&flow_mod, compact)?;
+        std::fs::write(output, content)
+    });
+    match result {
+        Ok(()) => info!("Converted {} to {}", input, output),
+        Err(e) => {
+            error!("Failed to convert {}: {}", input, e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// 转换 Node-RED 导出并写入 flow 文件，输出导入报告
+fn import_node_red(
+    input: &Path,
+    output: &str,
+    compact: bool,
+    json: bool,
+    allow_lossy_inject: bool,
+) {
+    let result = std::fs::read_to_string(input)
+        .map_err(|e| e.to_string())
+        .and_then(|text| serde_json::from_str(&text).map_err(|e| e.to_string()))
+        .and_then(|export| node_red::import(&export, allow_lossy_inject))
+        .and_then(|(flow_mod, report)| {
+            let content = FlowFormat::from_path(Path::new(output))
+                .serialize(&flow_mod, compact)
+                .map_err(|e| e.to_string())?;
+            std::fs::write(output, content).map_err(|e| e.to_string())?;
+            Ok(report)
+        });
+    let report = match result {
+        Ok(report) => report,
+        Err(e) => {
+            error!("Failed to import {}: {}", input.display(), e);
+            std::process::exit(1);
+        }
+    };
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).unwrap_or_default()
+        );
+        return;
+    }
+    for issue in &report.issues {
+        println!(
+            "{:<12} {} \"{}\" ({}): {}",
+            issue.kind, issue.node_type, issue.name, issue.id, issue.message
+        );
+    }
+    println!(
+        "{} -> {}: {} flow(s), {} node(s) converted, {} placeholder(s), {} skipped",
+        input.display(),
+        output,
+        report.flows,
+        report.converted,
+        report.placeholders,
+        report.skipped
+    );
+    if report.placeholders > 0 {
+        println!(
+            "Replace the {}* placeholder nodes before running the flow",
+            node_red::PLACEHOLDER_PREFIX
+        );
+    }
+}
+
+/// 输出已注册节点类型的说明
+fn list_nodes(json: bool) {
+    let descriptors = engine_builder().node_descriptors();
+    if json {
+        match serde_json::to_string_pretty(&descriptors) {
+            Ok(text) => println!("{}", text),
+            Err(e) => {
+                error!("Failed to serialize node descriptors: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+    println!(
+        "{:<16} {:<10} {:>6} {:>7}  NAME",
+        "TYPE", "CATEGORY", "INPUTS", "OUTPUTS"
+    );
+    for d in &descriptors {
+        println!(
+            "{:<16} {:<10} {:>6} {:>7}  {}",
+            d.node_type,
+            d.category,
+            d.inputs.len(),
+            d.outputs.len(),
+            d.display_name
+        );
+    }
+}
+
+/// 输出诊断和汇总，有 error（deny_warnings 时含 warning）或文件无法加载时以 1 退出
+fn exit_with_diagnostics(
+    flow_file: &str,
+    diagnostics: Result<Vec<Diagnostic>, std::io::Error>,
+    json: bool,
+    deny_warnings: bool,
+) -> ! {
+    let diagnostics = match diagnostics {
+        Ok(diagnostics) => diagnostics,
+        Err(e) => {
+            error!("Failed to load {}: {}", flow_file, e);
+            std::process::exit(1);
+        }
+    };
+    let errors = diagnostics.iter().filter(|d| d.is_error()).count();
+    let warnings = diagnostics.len() - errors;
+    if json {
+        match serde_json::to_string_pretty(&diagnostics) {
+            Ok(text) => println!("{}", text),
+            Err(e) => {
+                error!("Failed to serialize diagnostics: {}", e);
                 std::process::exit(1);
             }
         }
+    } else {
+        for d in &diagnostics {
+            println!("{}", d);
+        }
+        println!(
+            "{}: {} error(s), {} warning(s)",
+            flow_file, errors, warnings
+        );
+    }
+    std::process::exit(exit_code(&diagnostics, deny_warnings))
+}
+
+/// 有 error，或 deny_warnings 时有 warning，退出码为 1
+fn exit_code(diagnostics: &[Diagnostic], deny_warnings: bool) -> i32 {
+    let failed = diagnostics.iter().any(|d| d.is_error() || deny_warnings);
+    if failed { 1 } else { 0 }
+}
+
+/// 初始化日志输出，RUST_LOG 未设置时默认 info
+fn init_tracing(format: LogFormat) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr);
+    match format {
+        LogFormat::Human => builder.init(),
+        LogFormat::Json => builder
+            .json()
+            .with_current_span(true)
+            .with_span_list(true)
+            .init(),
+    }
+}
+
+/// 构建并运行 Engine，收到 SIGINT/SIGTERM 时优雅停止
+async fn run_flow_file(
+    flow_file: &str,
+    watch: bool,
+    admin: Option<(SocketAddr, PathBuf, Option<String>)>,
+) {
+    // 使用自动注册函数
+    let handle = match engine_builder().build(flow_file).await {
+        Ok(handle) => handle,
+        Err(e) => {
+            error!("Failed to build engine: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    tokio::spawn(shutdown_on_signal(handle.clone()));
+
+    if watch {
+        let watch_handle = handle.clone();
+        tokio::spawn(async move {
+            if let Err(e) = watch_handle.watch().await {
+                error!("Failed to watch flow file: {:?}", e);
+            }
+        });
+    }
+
+    if let Some((addr, assets, token)) = admin {
+        let admin_handle = handle.clone();
+        tokio::spawn(async move {
+            if let Err(e) = admin::serve(admin_handle, addr, assets, token).await {
+                error!("Admin server error: {}", e);
+            }
+        });
+    }
+
+    // 👇 生命周期锚点
+    handle.start().await;
+}
+
+async fn shutdown_on_signal(handle: EngineHandle) {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+        let mut term = match signal(SignalKind::terminate()) {
+            Ok(term) => term,
+            Err(e) => {
+                error!("Failed to listen for SIGTERM: {:?}", e);
+                let _ = tokio::signal::ctrl_c().await;
+                handle.shutdown();
+                return;
+            }
+        };
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => info!("Received SIGINT"),
+            _ = term.recv() => info!("Received SIGTERM"),
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+        info!("Received Ctrl-C");
+    }
+    handle.shutdown();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rsflow_core::Value;
+    use rsflow_core::flow::{FlowBuilder, FlowMod, FlowModBuilder, NodeSpec};
+    use serde_json::json;
+
+    const SAMPLE: &str = "../data/flow.json";
+
+    // 写入临时 flow 文件，返回路径
+    fn write_flow(flow_mod: &FlowMod) -> String {
+        let path = std::env::temp_dir().join(format!("rsflow-{}.json", uuid::Uuid::new_v4()));
+        std::fs::write(&path, serde_json::to_string(flow_mod).unwrap()).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    fn codes(diagnostics: &[Diagnostic]) -> Vec<&'static str> {
+        diagnostics.iter().map(|d| d.code).collect()
+    }
+
+    #[tokio::test]
+    async fn sample_flow_passes_validate_and_lint() {
+        let diagnostics = engine_builder().validate(SAMPLE).await.unwrap();
+        assert_eq!(exit_code(&diagnostics, false), 0, "{:?}", diagnostics);
+
+        let diagnostics = engine_builder().lint(SAMPLE).await.unwrap();
+        assert!(!codes(&diagnostics).contains(&"shell-platform-mismatch"));
+        assert_eq!(exit_code(&diagnostics, false), 0);
+    }
+
+    #[tokio::test]
+    async fn lint_warnings_fail_only_with_deny_warnings() {
+        let mut flow = FlowBuilder::new("main");
+        let config: Value =
+            serde_json::from_value(json!({ "command": "echo ok", "shell": "other" })).unwrap();
+        flow.add(NodeSpec::new("shell").config(config));
+        let path = write_flow(&FlowModBuilder::new().flow(flow.build()).build());
+
+        let diagnostics = engine_builder().lint(&path).await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            codes(&diagnostics),
+            [
+                "unused-input-port",
+                "unused-output-port",
+                "shell-platform-mismatch"
+            ]
+        );
+        assert!(diagnostics.iter().all(|d| !d.is_error()));
+        assert_eq!(exit_code(&diagnostics, false), 0);
+        assert_eq!(exit_code(&diagnostics, true), 1);
+    }
+
+    #[tokio::test]
+    async fn validate_errors_fail() {
+        let mut flow = FlowBuilder::new("main");
+        flow.add(NodeSpec::new("missing"));
+        let path = write_flow(&FlowModBuilder::new().flow(flow.build()).build());
+
+        let diagnostics = engine_builder().validate(&path).await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(codes(&diagnostics), ["unknown-node-type"]);
+        assert_eq!(exit_code(&diagnostics, false), 1);
+
+        assert!(engine_builder().validate("missing.json").await.is_err());
+        assert_eq!(exit_code(&[], true), 0);
     }
 }
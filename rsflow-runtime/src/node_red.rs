@@ -0,0 +1,604 @@
+//! rsflow import：把 Node-RED 导出的 flow（JSON 数组）转换为 FlowMod
+//!
+//! tab 对应 Flow，节点按类型映射到 rsflow 节点并转换配置，wires 的第 i 组连到端口 i。
+//! 没有对应 rsflow 节点的类型转换为占位节点（node_type 为 `node-red:<类型>`，
+//! config 保留原始属性），加载前需要替换；所有不能完整转换的内容记入 ImportReport。
+
+use rsflow_core::flow::{FlowBuilder, FlowMod, FlowModBuilder, NodeRef, NodeSpec, stable_uuid};
+use rsflow_core::{CATCH_NODE_TYPE, Value};
+use serde::Serialize;
+use serde_json::{Map, Value as JsonValue, json};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// 占位节点类型的前缀
+pub const PLACEHOLDER_PREFIX: &str = "node-red:";
+
+/// 不转换也不报告的类型：注释和分组没有运行时行为
+const IGNORED_TYPES: &[&str] = &["comment", "group"];
+
+/// 占位节点 config 中不保留的 Node-RED 属性：位置、连线，以及已写入节点的 name / info
+const OMITTED_KEYS: &[&str] = &[
+    "id", "type", "z", "g", "x", "y", "w", "h", "l", "wires", "name", "info",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    /// 已转换，但部分属性或连线被丢弃
+    Lossy,
+    /// 转换为占位节点
+    Placeholder,
+    /// 未导入
+    Skipped,
+}
+
+impl fmt::Display for IssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // pad 使 {:<12} 等宽度参数生效
+        f.pad(match self {
+            IssueKind::Lossy => "lossy",
+            IssueKind::Placeholder => "placeholder",
+            IssueKind::Skipped => "skipped",
+        })
+    }
+}
+
+/// 一个不能完整转换的 Node-RED 节点
+#[derive(Debug, Serialize)]
+pub struct ImportIssue {
+    pub kind: IssueKind,
+    /// Node-RED 节点 id
+    pub id: String,
+    /// Node-RED 节点类型
+    pub node_type: String,
+    pub name: String,
+    pub message: String,
+}
+
+/// 导入结果汇总
+#[derive(Debug, Default, Serialize)]
+pub struct ImportReport {
+    pub flows: usize,
+    /// 已转换为 rsflow 节点的数量（含 lossy）
+    pub converted: usize,
+    pub placeholders: usize,
+    pub skipped: usize,
+    pub issues: Vec<ImportIssue>,
+}
+
+impl ImportReport {
+    fn issue(&mut self, kind: IssueKind, node: &JsonValue, message: impl Into<String>) {
+        match kind {
+            IssueKind::Lossy => {}
+            IssueKind::Placeholder => self.placeholders += 1,
+            IssueKind::Skipped => self.skipped += 1,
+        }
+        self.issues.push(ImportIssue {
+            kind,
+            id: str_of(node, "id").to_string(),
+            node_type: str_of(node, "type").to_string(),
+            name: str_of(node, "name").to_string(),
+            message: message.into(),
+        });
+    }
+}
+
+// 映射后的节点
+struct Mapped {
+    node_type: &'static str,
+    config: JsonValue,
+    /// rsflow 节点的输出端口数，超出的 wires 被丢弃
+    outputs: usize,
+    notes: Vec<String>,
+}
+
+/// 转换 Node-RED 导出：节点数组，或 `{ "flows": [...] }`（Admin API 的 v2 格式）
+///
+/// inject 使用了 repeat / crontab 或额外的 msg 属性时，rsflow inject 无法等价转换，
+/// 导入失败；allow_lossy_inject 为 true 时照常转换并记为 lossy。
+pub fn import(
+    export: &JsonValue,
+    allow_lossy_inject: bool,
+) -> Result<(FlowMod, ImportReport), String> {
+    let items = export
+        .as_array()
+        .or_else(|| export.get("flows").and_then(JsonValue::as_array))
+        .ok_or("expected a Node-RED export: a JSON array of nodes")?;
+
+    let mut report = ImportReport::default();
+    let tabs: Vec<&JsonValue> = items
+        .iter()
+        .filter(|n| str_of(n, "type") == "tab")
+        .collect();
+
+    if !allow_lossy_inject {
+        let lossy: Vec<String> = items
+            .iter()
+            .filter(|n| str_of(n, "type") == "inject" && is_in_tab(n, &tabs))
+            .filter_map(|node| {
+                let settings = inject_schedule(node);
+                (!settings.is_empty()).then(|| {
+                    format!(
+                        "inject \"{}\" ({}): {}",
+                        non_empty(str_of(node, "name"), "inject"),
+                        str_of(node, "id"),
+                        settings.join(", ")
+                    )
+                })
+            })
+            .collect();
+        if !lossy.is_empty() {
+            return Err(format!(
+                "rsflow inject emits \"hello\" every second and cannot keep {}; \
+                 pass --allow-lossy-inject to import anyway",
+                lossy.join("; ")
+            ));
+        }
+    }
+    let subflows: HashMap<&str, &str> = items
+        .iter()
+        .filter(|n| str_of(n, "type") == "subflow")
+        .map(|n| (str_of(n, "id"), str_of(n, "name")))
+        .collect();
+
+    for node in items {
+        let node_type = str_of(node, "type");
+        if node_type == "tab" || node_type == "subflow" || IGNORED_TYPES.contains(&node_type) {
+            continue;
+        }
+        if is_in_tab(node, &tabs) && !is_config_node(node) {
+            continue;
+        }
+        match subflows.get(str_of(node, "z")) {
+            Some(name) => report.issue(
+                IssueKind::Skipped,
+                node,
+                format!("inside subflow \"{}\", subflows are not supported", name),
+            ),
+            None => report.issue(
+                IssueKind::Skipped,
+                node,
+                "configuration node, referenced settings must be copied into node configs",
+            ),
+        }
+    }
+
+    let mut flow_mod = FlowModBuilder::new();
+    // 连线按 id 查找节点，没有 id 或 id 重复的节点不导入
+    let mut seen: HashSet<&str> = HashSet::new();
+    for tab in &tabs {
+        let tab_id = str_of(tab, "id");
+        let mut flow = FlowBuilder::new(non_empty(str_of(tab, "label"), tab_id))
+            .id(stable_uuid("flow", tab_id))
+            .description(str_of(tab, "info"))
+            .disabled(tab.get("disabled").and_then(JsonValue::as_bool) == Some(true));
+
+        let mut nodes: Vec<&JsonValue> = Vec::new();
+        for node in items
+            .iter()
+            .filter(|n| str_of(n, "z") == tab_id && !is_config_node(n))
+            .filter(|n| !IGNORED_TYPES.contains(&str_of(n, "type")))
+        {
+            let id = str_of(node, "id");
+            if id.is_empty() {
+                report.issue(IssueKind::Skipped, node, "missing id");
+            } else if !seen.insert(id) {
+                report.issue(
+                    IssueKind::Skipped,
+                    node,
+                    "duplicate id, only the first node with this id is imported",
+                );
+            } else {
+                nodes.push(node);
+            }
+        }
+        let mut refs: HashMap<&str, NodeRef> = HashMap::new();
+        let mut outputs: HashMap<&str, usize> = HashMap::new();
+        for node in &nodes {
+            let (spec, ports) = convert_node(node, &mut report);
+            refs.insert(str_of(node, "id"), flow.add(spec));
+            outputs.insert(str_of(node, "id"), ports);
+        }
+
+        for node in &nodes {
+            let from = refs[str_of(node, "id")];
+            let ports = outputs[str_of(node, "id")];
+            let wires = node.get("wires").and_then(JsonValue::as_array);
+            for (port, targets) in wires.into_iter().flatten().enumerate() {
+                for target in targets.as_array().into_iter().flatten() {
+                    let target = target.as_str().unwrap_or_default();
+                    if port >= ports {
+                        report.issue(
+                            IssueKind::Lossy,
+                            node,
+                            format!(
+                                "output {} has no rsflow equivalent, wire to {} dropped",
+                                port + 1,
+                                target
+                            ),
+                        );
+                    } else if let Some(to) = refs.get(target) {
+                        flow.wire(from, port as u8, *to, 0);
+                    } else {
+                        report.issue(
+                            IssueKind::Lossy,
+                            node,
+                            format!("wire to {} dropped, target is not in this flow", target),
+                        );
+                    }
+                }
+            }
+        }
+
+        report.flows += 1;
+        flow_mod = flow_mod.flow(flow.build());
+    }
+
+    // 按节点在导出中的顺序输出，重复的 id 取第一次出现的位置
+    let order: HashMap<&str, usize> = items
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(i, node)| (str_of(node, "id"), i))
+        .collect();
+    report
+        .issues
+        .sort_by_key(|issue| order.get(issue.id.as_str()).copied());
+    Ok((flow_mod.build(), report))
+}
+
+// 转换单个节点，返回节点定义和可用的输出端口数
+fn convert_node(node: &JsonValue, report: &mut ImportReport) -> (NodeSpec, usize) {
+    let id = str_of(node, "id");
+    let node_type = str_of(node, "type");
+    let name = non_empty(str_of(node, "name"), node_type);
+    let base = |rsflow_type: &str| {
+        NodeSpec::new(rsflow_type)
+            .id(stable_uuid("node", id))
+            .name(name)
+            .description(str_of(node, "info"))
+    };
+
+    if node.get("d").and_then(JsonValue::as_bool) == Some(true) {
+        report.issue(
+            IssueKind::Lossy,
+            node,
+            "node was disabled in Node-RED, rsflow has no per-node disable",
+        );
+    }
+
+    match map_node(node) {
+        Some(mapped) => {
+            report.converted += 1;
+            for note in mapped.notes {
+                report.issue(IssueKind::Lossy, node, note);
+            }
+            (
+                base(mapped.node_type).config(to_value(mapped.config)),
+                mapped.outputs,
+            )
+        }
+        None => {
+            report.issue(
+                IssueKind::Placeholder,
+                node,
+                "no equivalent rsflow node type, replace the placeholder before running",
+            );
+            let mut config = Map::new();
+            if let Some(props) = node.as_object() {
+                for (key, value) in props {
+                    if !OMITTED_KEYS.contains(&key.as_str()) {
+                        config.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+            let outputs = node
+                .get("wires")
+                .and_then(JsonValue::as_array)
+                .map_or(0, Vec::len);
+            let spec = base(&format!("{}{}", PLACEHOLDER_PREFIX, node_type))
+                .description(&format!(
+                    "Node-RED {} placeholder, replace with an rsflow node",
+                    node_type
+                ))
+                .config(to_value(JsonValue::Object(config)));
+            (spec, outputs)
+        }
+    }
+}
+
+// 按 Node-RED 类型映射到 rsflow 节点，没有对应类型时返回 None
+fn map_node(node: &JsonValue) -> Option<Mapped> {
+    let mut notes = Vec::new();
+    let mapped = match str_of(node, "type") {
+        "inject" => {
+            // rsflow inject 没有配置，每秒输出 "hello"
+            let mut dropped = inject_schedule(node);
+            if node.get("once").and_then(JsonValue::as_bool) == Some(true) {
+                dropped.push("once".to_string());
+            }
+            let payload_type = str_of(node, "payloadType");
+            if !payload_type.is_empty() {
+                dropped.push(format!("{} payload", payload_type));
+            }
+            if !dropped.is_empty() {
+                notes.push(format!(
+                    "{} dropped, rsflow inject emits \"hello\" every second",
+                    dropped.join(", ")
+                ));
+            }
+            Mapped {
+                node_type: "inject",
+                config: json!({}),
+                outputs: 1,
+                notes,
+            }
+        }
+        "debug" => {
+            if node.get("active").and_then(JsonValue::as_bool) == Some(false) {
+                notes.push("debug output was turned off, log always logs".to_string());
+            }
+            let complete = str_of(node, "complete");
+            if !complete.is_empty()
+                && complete != "payload"
+                && complete != "true"
+                && complete != "false"
+            {
+                notes.push(format!(
+                    "property \"{}\" is not selected, log logs the whole payload",
+                    complete
+                ));
+            }
+            Mapped {
+                node_type: "log",
+                config: json!({}),
+                outputs: 0,
+                notes,
+            }
+        }
+        "exec" => {
+            let mut command = str_of(node, "command").to_string();
+            let append = str_of(node, "append");
+            if !append.is_empty() {
+                command = format!("{} {}", command, append);
+            }
+            let mut config = json!({ "command": command });
+            if truthy(node.get("useSpawn")) {
+                config["stream"] = json!(true);
+            }
+            match str_of(node, "timer").trim().parse::<f64>() {
+                Ok(timer) if timer > 0.0 => config["timeout"] = json!(timer.ceil() as u64),
+                _ => notes
+                    .push("no timeout in Node-RED, rsflow shell defaults to 5 seconds".to_string()),
+            }
+            if truthy(node.get("addpay")) {
+                notes.push(
+                    "msg.payload is not appended to the command, send { command } instead"
+                        .to_string(),
+                );
+            }
+            Mapped {
+                node_type: "shell",
+                config,
+                outputs: 1,
+                notes,
+            }
+        }
+        "catch" => {
+            let mut config = json!({});
+            match node.get("scope") {
+                Some(JsonValue::Array(ids)) => {
+                    let scope: Vec<String> = ids
+                        .iter()
+                        .filter_map(JsonValue::as_str)
+                        .map(|id| stable_uuid("node", id).to_string())
+                        .collect();
+                    config["scope"] = json!(scope);
+                }
+                Some(JsonValue::String(scope)) => notes.push(format!(
+                    "scope \"{}\" is not supported, catches the whole flow",
+                    scope
+                )),
+                _ => {}
+            }
+            if node.get("uncaught").and_then(JsonValue::as_bool) == Some(true) {
+                notes.push(
+                    "\"ignore errors handled by other Catch nodes\" is not supported".to_string(),
+                );
+            }
+            Mapped {
+                node_type: CATCH_NODE_TYPE,
+                config,
+                outputs: 1,
+                notes,
+            }
+        }
+        "http in" => {
+            if truthy(node.get("upload")) {
+                notes.push("file uploads are not supported".to_string());
+            }
+            Mapped {
+                node_type: "http in",
+                config: json!({
+                    "url": str_of(node, "url"),
+                    "method": str_of(node, "method").to_uppercase(),
+                }),
+                outputs: 1,
+                notes,
+            }
+        }
+        "http response" => {
+            let mut config = json!({});
+            let status = str_of(node, "statusCode");
+            match status.trim().parse::<u64>() {
+                Ok(status) => config["status"] = json!(status),
+                Err(_) if status.trim().is_empty() => {}
+                Err(_) => notes.push(format!("invalid status code \"{}\" dropped", status)),
+            }
+            if let Some(headers) = node.get("headers").and_then(JsonValue::as_object)
+                && !headers.is_empty()
+            {
+                config["headers"] = JsonValue::Object(headers.clone());
+            }
+            Mapped {
+                node_type: "http response",
+                config,
+                outputs: 0,
+                notes,
+            }
+        }
+        _ => return None,
+    };
+    Some(mapped)
+}
+
+// tab 中的节点（不含 tab 本身）
+// inject 中 rsflow inject 无法表达的触发设置：定时、cron 和额外的 msg 属性
+fn inject_schedule(node: &JsonValue) -> Vec<String> {
+    let repeat = str_of(node, "repeat");
+    let crontab = str_of(node, "crontab");
+    let mut settings = Vec::new();
+    if !repeat.is_empty() {
+        settings.push(format!("repeat {}s", repeat));
+    }
+    if !crontab.is_empty() {
+        settings.push(format!("crontab \"{}\"", crontab));
+    }
+    if node
+        .get("props")
+        .is_some_and(|p| p.as_array().is_some_and(|p| p.len() > 1))
+    {
+        settings.push("extra msg properties".to_string());
+    }
+    settings
+}
+
+fn is_in_tab(node: &JsonValue, tabs: &[&JsonValue]) -> bool {
+    let z = str_of(node, "z");
+    !z.is_empty() && tabs.iter().any(|tab| str_of(tab, "id") == z)
+}
+
+// 配置节点（如 mqtt-broker）没有 wires，可能属于某个 tab 或全局
+fn is_config_node(node: &JsonValue) -> bool {
+    node.get("wires").is_none()
+}
+
+fn str_of<'a>(node: &'a JsonValue, key: &str) -> &'a str {
+    node.get(key)
+        .and_then(JsonValue::as_str)
+        .unwrap_or_default()
+}
+
+fn non_empty<'a>(value: &'a str, default: &'a str) -> &'a str {
+    if value.is_empty() { default } else { value }
+}
+
+// Node-RED 的布尔属性可能是 true 或 "true"，exec 的 addpay 也可能是属性名
+fn truthy(value: Option<&JsonValue>) -> bool {
+    match value {
+        Some(JsonValue::Bool(b)) => *b,
+        Some(JsonValue::String(s)) => !s.is_empty() && s != "false",
+        _ => false,
+    }
+}
+
+fn to_value(json: JsonValue) -> Value {
+    serde_json::from_value(json).unwrap_or(Value::NULL)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn export() -> JsonValue {
+        json!([
+            { "id": "t1", "type": "tab", "label": "Main" },
+            { "id": "n1", "type": "inject", "z": "t1", "repeat": "5", "wires": [["n2", "n3"]] },
+            { "id": "n2", "type": "debug", "z": "t1", "wires": [] },
+            { "id": "n3", "type": "function", "z": "t1", "func": "return msg;", "wires": [["n2"]] },
+            { "id": "n2", "type": "debug", "z": "t1", "name": "again", "wires": [] },
+            { "type": "debug", "z": "t1", "wires": [] },
+            { "id": "c1", "type": "comment", "z": "t1", "wires": [] },
+            { "id": "b1", "type": "mqtt-broker", "broker": "localhost" },
+        ])
+    }
+
+    #[test]
+    fn import_small_export() {
+        let (flow_mod, report) = import(&export(), true).unwrap();
+        assert_eq!(report.flows, 1);
+        assert_eq!(report.converted, 2);
+        assert_eq!(report.placeholders, 1);
+        // 重复 id、缺少 id 和配置节点
+        assert_eq!(report.skipped, 3);
+
+        let flow = &flow_mod.flow[0];
+        assert_eq!(flow.name, "Main");
+        assert_eq!(flow.nodes.len(), 3);
+        let placeholder = flow
+            .nodes
+            .iter()
+            .find(|node| node.node_type.starts_with(PLACEHOLDER_PREFIX))
+            .unwrap();
+        assert_eq!(placeholder.node_type, "node-red:function");
+        assert_eq!(placeholder.id, stable_uuid("node", "n3"));
+        assert_eq!(placeholder.output[0].nodes[0].id, stable_uuid("node", "n2"));
+
+        let debug = flow
+            .nodes
+            .iter()
+            .find(|node| node.id == stable_uuid("node", "n2"))
+            .unwrap();
+        assert_eq!(debug.node_type, "log");
+        assert_eq!(debug.name, "debug");
+        assert_eq!(debug.input[0].nodes.len(), 2);
+    }
+
+    #[test]
+    fn skipped_ids_are_reported() {
+        let (_, report) = import(&export(), true).unwrap();
+        let skipped: Vec<(&str, &str)> = report
+            .issues
+            .iter()
+            .filter(|issue| issue.kind == IssueKind::Skipped)
+            .map(|issue| (issue.id.as_str(), issue.message.as_str()))
+            .collect();
+        assert!(skipped.contains(&(
+            "n2",
+            "duplicate id, only the first node with this id is imported"
+        )));
+        assert!(skipped.contains(&("", "missing id")));
+    }
+
+    #[test]
+    fn inject_schedule_requires_flag() {
+        let err = import(&export(), false).unwrap_err();
+        assert!(err.contains("inject \"inject\" (n1): repeat 5s"));
+        assert!(err.contains("--allow-lossy-inject"));
+
+        let export = json!([
+            { "id": "t1", "type": "tab", "label": "Main" },
+            { "id": "n1", "type": "inject", "z": "t1", "crontab": "0 8 * * *",
+              "props": [{ "p": "payload" }, { "p": "topic", "vt": "str" }], "wires": [] },
+            { "id": "n2", "type": "inject", "z": "t1", "props": [{ "p": "payload" }], "wires": [] },
+        ]);
+        let err = import(&export, false).unwrap_err();
+        assert!(err.contains("(n1): crontab \"0 8 * * *\", extra msg properties"));
+        assert!(!err.contains("n2"));
+
+        // 显式允许时照常导入，丢弃的设置记为 lossy
+        let (flow_mod, report) = import(&export, true).unwrap();
+        assert_eq!(flow_mod.flow[0].nodes.len(), 2);
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].kind, IssueKind::Lossy);
+        assert_eq!(report.issues[0].id, "n1");
+
+        // 没有定时设置的 inject 不需要显式允许
+        let plain = json!([
+            { "id": "t1", "type": "tab", "label": "Main" },
+            { "id": "n2", "type": "inject", "z": "t1", "props": [{ "p": "payload" }], "wires": [] },
+        ]);
+        assert!(import(&plain, false).is_ok());
+    }
+}